@@ -0,0 +1,65 @@
+//! Diagnoses 100,000 random lines of brackets, a thousand times the puzzle input, on one thread
+//! against across rayon's threads. The rayon one needs the `parallel` feature. Run with
+//! `cargo +nightly bench --bench day10 --features nightly,parallel`
+#![feature(test)]
+extern crate test;
+
+use aoc21::testing::Xorshift;
+use aoc21::y2021::day10::diagnose;
+#[cfg(feature = "parallel")]
+use aoc21::y2021::day10::diagnose_parallel;
+use test::Bencher;
+
+const LINES: usize = 100_000;
+
+fn closing_for(bracket: char) -> char {
+    match bracket {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        _ => '>',
+    }
+}
+
+fn lines() -> Vec<String> {
+    let mut rng = Xorshift::default();
+    // Random walks over the brackets, mostly left incomplete with the odd wrong closing
+    (0..LINES)
+        .map(|_| {
+            let mut open = Vec::new();
+            let mut line = String::new();
+            for _ in 0..1 + rng.next_u32() % 100 {
+                let bracket = b"([{<"[rng.next_u32() as usize % 4] as char;
+                if open.is_empty() || rng.next_u32() % 3 != 0 {
+                    open.push(bracket);
+                    line.push(bracket);
+                } else if rng.next_u32() % 50 == 0 {
+                    line.push(closing_for(bracket));
+                } else {
+                    line.push(closing_for(open.pop().unwrap()));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+#[bench]
+fn serial(b: &mut Bencher) {
+    let lines = lines();
+    b.iter(|| diagnose(test::black_box(&lines)));
+}
+
+#[cfg(feature = "parallel")]
+#[bench]
+fn parallel(b: &mut Bencher) {
+    let lines = lines();
+    b.iter(|| diagnose_parallel(test::black_box(&lines)));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn same_diagnostics() {
+    let lines = lines();
+    assert_eq!(diagnose_parallel(&lines), diagnose(&lines));
+}
@@ -0,0 +1,106 @@
+//! Steps the day 11 example board 10,000 times, against the board as it was stored before it was
+//! flattened: nested rows of `i16` and a queue of flashes indexed with `isize`. Run with
+//! `cargo +nightly bench --bench day11 --features nightly`
+#![feature(test)]
+extern crate test;
+
+use aoc21::y2021::day11::OctopusBoard;
+use std::collections::VecDeque;
+use test::Bencher;
+
+const EXAMPLE: &str = include_str!("../examples/2021/day11.txt");
+const STEPS: usize = 10_000;
+
+fn example() -> Vec<Vec<i16>> {
+    EXAMPLE
+        .lines()
+        .map(|line| {
+            line.chars()
+                .map(|c| c.to_digit(10).unwrap() as i16)
+                .collect()
+        })
+        .collect()
+}
+
+struct NestedBoard {
+    octos: Vec<Vec<i16>>,
+    flash_queue: VecDeque<(isize, isize)>,
+    flashes: u64,
+}
+
+impl NestedBoard {
+    fn step(&mut self) {
+        for row in 0..self.octos.len() {
+            for col in 0..self.octos[row].len() {
+                self.increment_octo(row as isize, col as isize);
+            }
+        }
+
+        while let Some((row, col)) = self.flash_queue.pop_front() {
+            for (row, col) in
+                (-1..=1).flat_map(|drow| (-1..=1).map(move |dcol| (row + drow, col + dcol)))
+            {
+                let out_of_bounds = row < 0
+                    || col < 0
+                    || row >= self.octos.len() as isize
+                    || col >= self.octos[0].len() as isize;
+                if !out_of_bounds && self.octos[row as usize][col as usize] != 0 {
+                    self.increment_octo(row, col);
+                }
+            }
+        }
+    }
+
+    fn increment_octo(&mut self, row: isize, col: isize) {
+        let octo = &mut self.octos[row as usize][col as usize];
+        if *octo == 9 {
+            *octo = 0;
+            self.flashes += 1;
+            self.flash_queue.push_back((row, col));
+        } else {
+            *octo += 1;
+        }
+    }
+}
+
+#[bench]
+fn flat(b: &mut Bencher) {
+    let board = OctopusBoard::with_octopuses(example());
+    b.iter(|| {
+        let mut board = board.clone();
+        for _ in 0..STEPS {
+            board.step();
+        }
+        board.flashes()
+    });
+}
+
+#[bench]
+fn nested(b: &mut Bencher) {
+    b.iter(|| {
+        let mut board = NestedBoard {
+            octos: example(),
+            flash_queue: VecDeque::new(),
+            flashes: 0,
+        };
+        for _ in 0..STEPS {
+            board.step();
+        }
+        board.flashes
+    });
+}
+
+#[test]
+fn same_flashes() {
+    let mut flat = OctopusBoard::with_octopuses(example());
+    let mut nested = NestedBoard {
+        octos: example(),
+        flash_queue: VecDeque::new(),
+        flashes: 0,
+    };
+    for _ in 0..1000 {
+        flat.step();
+        nested.step();
+        assert_eq!(flat.flashes(), nested.flashes);
+    }
+}
@@ -0,0 +1,117 @@
+//! Searches a 100x100 map tiled 5 times, the size of part 2 on a real input, letting every push
+//! onto the queue through against pruning the ones that can't improve on a cell, both working out
+//! the risk of a cell as it's reached, and against expanding the map up front. Each prints the most
+//! heap it had in use at once. Run with `cargo +nightly bench --bench day15 --features nightly`
+#![feature(test)]
+extern crate test;
+
+use aoc21::testing::Xorshift;
+use aoc21::y2021::day15::{lowest_risk_materialized, lowest_risk_with, Frontier, Movement};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use test::Bencher;
+
+/// The system allocator, keeping track of how much is allocated and the most there has been
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn grow(size: usize) {
+    let now = CURRENT.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(now, Relaxed);
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            CURRENT.fetch_sub(layout.size(), Relaxed);
+            grow(new_size);
+        }
+        new
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+const SIZE: usize = 100;
+const REPEATS: usize = 5;
+
+fn map() -> Vec<Vec<i32>> {
+    let mut rng = Xorshift::default();
+    (0..SIZE)
+        .map(|_| (0..SIZE).map(|_| 1 + (rng.next_u32() % 9) as i32).collect())
+        .collect()
+}
+
+/// Most bytes allocated at once while `f` runs, on top of what was already allocated
+fn peak_heap(f: impl FnOnce()) -> usize {
+    let before = CURRENT.load(Relaxed);
+    PEAK.store(before, Relaxed);
+    f();
+    PEAK.load(Relaxed) - before
+}
+
+fn search(b: &mut Bencher, frontier: Frontier) {
+    let map = map();
+    let peak = peak_heap(|| {
+        lowest_risk_with(&map, REPEATS, Movement::Orthogonal, frontier);
+    });
+    eprintln!("{:?}: peak heap {} KiB", frontier, peak / 1024);
+    b.iter(|| {
+        lowest_risk_with(
+            test::black_box(&map),
+            REPEATS,
+            Movement::Orthogonal,
+            frontier,
+        )
+    });
+}
+
+#[bench]
+fn lazy(b: &mut Bencher) {
+    search(b, Frontier::Lazy);
+}
+
+#[bench]
+fn pruned(b: &mut Bencher) {
+    search(b, Frontier::Pruned);
+}
+
+#[bench]
+fn materialized(b: &mut Bencher) {
+    let map = map();
+    let peak = peak_heap(|| {
+        lowest_risk_materialized(&map, REPEATS);
+    });
+    eprintln!("Materialized: peak heap {} KiB", peak / 1024);
+    b.iter(|| lowest_risk_materialized(test::black_box(&map), REPEATS));
+}
+
+#[test]
+fn same_risk() {
+    let map = map();
+    assert_eq!(
+        lowest_risk_with(&map, REPEATS, Movement::Orthogonal, Frontier::Lazy),
+        lowest_risk_with(&map, REPEATS, Movement::Orthogonal, Frontier::Pruned)
+    );
+    assert_eq!(
+        lowest_risk_with(&map, REPEATS, Movement::Orthogonal, Frontier::Lazy),
+        lowest_risk_materialized(&map, REPEATS)
+    );
+}
@@ -0,0 +1,53 @@
+//! Adds up 100 numbers, the example's ten over and over to the length of a real input, with boxed
+//! trees against with an arena. Run with `cargo +nightly bench --bench day18 --features nightly`
+#![feature(test)]
+extern crate test;
+
+use aoc21::algo::snailfish::{add_numbers, parse_pairs, ArenaNumber, Number};
+use test::Bencher;
+
+const EXAMPLE: &str = include_str!("../examples/2021/day18.txt");
+const NUMBERS: usize = 100;
+
+fn numbers() -> Vec<Number> {
+    EXAMPLE
+        .lines()
+        .cycle()
+        .take(NUMBERS)
+        .map(|line| parse_pairs(line).unwrap())
+        .collect()
+}
+
+fn boxed_sum(numbers: &[Number]) -> Number {
+    numbers[1..].iter().fold(numbers[0].clone(), |sum, num| {
+        add_numbers(sum, num.clone()).unwrap()
+    })
+}
+
+fn arena_sum(numbers: &[ArenaNumber]) -> ArenaNumber {
+    numbers[1..]
+        .iter()
+        .fold(numbers[0].clone(), |sum, num| sum + num)
+}
+
+#[bench]
+fn boxed(b: &mut Bencher) {
+    let numbers = numbers();
+    b.iter(|| boxed_sum(test::black_box(&numbers)));
+}
+
+#[bench]
+fn arena(b: &mut Bencher) {
+    let numbers = numbers().iter().map(ArenaNumber::from).collect::<Vec<_>>();
+    b.iter(|| arena_sum(test::black_box(&numbers)));
+}
+
+#[test]
+fn same_sum() {
+    let numbers = numbers();
+    let arena = numbers.iter().map(ArenaNumber::from).collect::<Vec<_>>();
+    assert_eq!(
+        arena_sum(&arena).to_string(),
+        boxed_sum(&numbers).to_string()
+    );
+}
@@ -0,0 +1,27 @@
+//! Counts the universes each player wins in on the day 21 example, recursing with a cache of
+//! universes against the forward pass over dense arrays. Run with
+//! `cargo +nightly bench --bench day21 --features nightly`
+#![feature(test)]
+extern crate test;
+
+use aoc21::y2021::day21::{quantum_wins, quantum_wins_dense};
+use test::Bencher;
+
+const START: (u64, u64) = (4, 8);
+
+#[bench]
+fn recursive(b: &mut Bencher) {
+    b.iter(|| quantum_wins(test::black_box(START.0), START.1));
+}
+
+#[bench]
+fn dense(b: &mut Bencher) {
+    b.iter(|| quantum_wins_dense(test::black_box(START.0), START.1));
+}
+
+#[test]
+fn same_wins() {
+    for (p1, p2) in [START, (3, 3), (9, 1)] {
+        assert_eq!(quantum_wins(p1, p2), quantum_wins_dense(p1, p2));
+    }
+}
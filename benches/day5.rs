@@ -0,0 +1,80 @@
+//! Counts the overlaps among 500 lines with coordinates below 1000, the size of a real input, on a
+//! flat grid against an `ActivityMap`. Then among 100,000 lines, one line after another against
+//! split between rayon jobs, which needs the `parallel` feature. Run with
+//! `cargo +nightly bench --bench day5 --features nightly,parallel`
+#![feature(test)]
+extern crate test;
+
+use aoc21::testing::Xorshift;
+#[cfg(feature = "parallel")]
+use aoc21::y2021::day5::count_overlaps_parallel;
+use aoc21::y2021::day5::{count_overlaps, Line, DENSE_MAX_CELLS};
+use test::Bencher;
+
+const LINES: usize = 500;
+const MANY_LINES: usize = 100_000;
+const SIZE: u32 = 1000;
+
+fn lines(count: usize) -> Vec<Line> {
+    let mut rng = Xorshift::new(0x3b9aca07);
+    (0..count)
+        .map(|_| {
+            let (x, y) = (rng.next_u32() % SIZE, rng.next_u32() % SIZE);
+            let len = rng.next_u32() % (SIZE - x.max(y));
+            let line = match rng.next_u32() % 3 {
+                0 => format!("{},{} -> {},{}", x, y, x + len, y),
+                1 => format!("{},{} -> {},{}", x, y, x, y + len),
+                _ => format!("{},{} -> {},{}", x, y, x + len, y + len),
+            };
+            line.parse().unwrap()
+        })
+        .collect()
+}
+
+#[bench]
+fn dense(b: &mut Bencher) {
+    let lines = lines(LINES);
+    b.iter(|| count_overlaps(test::black_box(&lines), true, usize::MAX));
+}
+
+#[bench]
+fn sparse(b: &mut Bencher) {
+    let lines = lines(LINES);
+    b.iter(|| count_overlaps(test::black_box(&lines), true, 0));
+}
+
+#[bench]
+fn many(b: &mut Bencher) {
+    let lines = lines(MANY_LINES);
+    b.iter(|| count_overlaps(test::black_box(&lines), true, DENSE_MAX_CELLS));
+}
+
+#[cfg(feature = "parallel")]
+#[bench]
+fn many_parallel(b: &mut Bencher) {
+    let lines = lines(MANY_LINES);
+    let lines = lines.iter().collect::<Vec<_>>();
+    b.iter(|| count_overlaps_parallel(test::black_box(&lines), DENSE_MAX_CELLS));
+}
+
+#[test]
+fn same_count() {
+    let lines = lines(LINES);
+    assert_eq!(
+        count_overlaps(&lines, true, usize::MAX),
+        count_overlaps(&lines, true, 0)
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn same_count_parallel() {
+    let lines = lines(LINES);
+    let refs = lines.iter().collect::<Vec<_>>();
+    for dense_max_cells in [0, usize::MAX] {
+        assert_eq!(
+            count_overlaps_parallel(&refs, dense_max_cells),
+            count_overlaps(&lines, true, dense_max_cells)
+        );
+    }
+}
@@ -0,0 +1,6 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let _ = aoc21::y2021::day10::ChunkParser::parse(line);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    if let Ok(number) = aoc21::y2021::day18::parse_pairs(line) {
+        // Anything that parses has to print back the same way
+        assert_eq!(number.to_string(), line.trim());
+    }
+});
@@ -0,0 +1,1203 @@
+// D2FE28
+// 110100101111111000101000
+// VVVTTTAAAAABBBBBCCCCCXXX
+//
+// VVV: packet version == 6
+// TTT: type ID == 4
+// AAAAA: data prefixed with 1 (not last)
+// BBBBB: data prefixed with 1 (not last)
+// CCCCC: data prefixed with 0 (last)
+// XXX: ignored bits (after last group)
+//
+// All other type IDs are operators
+// - length type ID is 0 -> next 15 bits are a number that represents the total length in bits of
+// the sub-packets contained by this packet.
+//
+// - length type ID is 1 -> next 11 bits are a number that represents the number of sub-packets
+// immediately contained by this packet.
+//
+// 00111000000000000110111101000101001010010001001000000000
+// VVVTTTILLLLLLLLLLLLLLLAAAAAAAAAAABBBBBBBBBBBBBBBBXXXXXXX
+//
+// VVV: packet version == 1
+// TTT: type ID == 6
+// I:   length type ID == 0
+// L(15): length of sub-packets == 27 bits
+// A(11): first sub-packet == 10 (broken down like above)
+// A(16): second sub-packet == 20
+//
+// Parsing stops afterr length is reached (27).
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+const TYPE_SUM: i64 = 0;
+const TYPE_PRODUCT: i64 = 1;
+const TYPE_MINIMUM: i64 = 2;
+const TYPE_MAXIMUM: i64 = 3;
+const TYPE_LITERAL: i64 = 4;
+const TYPE_GREATER_THAN: i64 = 5;
+const TYPE_LESS_THAN: i64 = 6;
+const TYPE_EQUAL_TO: i64 = 7;
+
+const LEN_TOTAL_LENGTH: i64 = 0;
+const LEN_NUM_SUBPACKETS: i64 = 1;
+
+/// The bits of a transmission, most significant bit of each byte or digit first
+pub struct BitStream(Vec<bool>);
+impl BitStream {
+    fn from_vec(stream: Vec<bool>) -> Self {
+        BitStream(stream)
+    }
+
+    /// Bits of a transmission as raw bytes, e.g. read straight from a binary file
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        BitStream::from_vec(
+            bytes
+                .iter()
+                .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1 == 1))
+                .collect(),
+        )
+    }
+
+    /// Bits of a transmission written out as text, either as hex or as binary prefixed with `bin:`.
+    /// The prefix can't be mistaken for hex, the way `0b` could. Whitespace anywhere in the text is
+    /// skipped, so it can be wrapped over several lines
+    pub fn from_text(text: &str) -> Result<Self, DecodeError> {
+        let text = text.trim_start();
+        let mut stream = Vec::new();
+        match text.strip_prefix("bin:") {
+            Some(binary) => {
+                for c in binary.chars().filter(|c| !c.is_whitespace()) {
+                    stream.push(match c {
+                        '0' => false,
+                        '1' => true,
+                        _ => return Err(DecodeError::InvalidBinary(c)),
+                    });
+                }
+            }
+            None => {
+                for c in text.chars().filter(|c| !c.is_whitespace()) {
+                    stream.extend(hex_to_bits(c)?);
+                }
+            }
+        }
+        Ok(BitStream::from_vec(stream))
+    }
+
+    /// Decode the outermost packet, ignoring any padding after it
+    pub fn decode(&self) -> Result<Packet, DecodeError> {
+        self.decode_with(DecodeLimits::default())
+    }
+
+    /// Same as `decode`, failing once the packets nest deeper or number more than `limits` allow
+    pub fn decode_with(&self, limits: DecodeLimits) -> Result<Packet, DecodeError> {
+        let mut budget = Budget { limits, packets: 0 };
+        let (_, packet) = parse_packet(self.inner(), 1, &mut budget)?;
+        Ok(packet)
+    }
+
+    fn inner(&self) -> &[bool] {
+        &self.0
+    }
+}
+
+impl core::fmt::Debug for BitStream {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for &b in &self.0 {
+            if b {
+                f.write_char('1')?;
+            } else {
+                f.write_char('0')?;
+            }
+        }
+        f.write_char('\n')
+    }
+}
+
+fn to_integer(b: &[bool]) -> i64 {
+    b.iter()
+        .fold(0, |acc, &bit| (acc << 1) | if bit { 1 } else { 0 })
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum PacketData {
+    Literal(i64),
+    Packets(Vec<Packet>),
+}
+
+/// How a packet was laid out in the transmission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Encoding {
+    /// A literal written in this many 5-bit groups
+    Groups(usize),
+    /// An operator giving the number of bits its sub-packets take up, length type 0
+    TotalLength,
+    /// An operator giving the number of its sub-packets, length type 1
+    PacketCount,
+}
+
+impl Encoding {
+    /// The fewest groups a literal holding `value` can be written in
+    fn groups_for(value: i64) -> Self {
+        let bits = u64::BITS - (value as u64).leading_zeros();
+        Encoding::Groups(bits.div_ceil(4).max(1) as usize)
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Packet {
+    version: i64,
+    type_id: i64,
+    encoding: Encoding,
+    data: PacketData,
+}
+
+/// Reasons a packet can't be evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    WrongArity {
+        type_id: i64,
+        expected: usize,
+        got: usize,
+    },
+    EmptyOperands,
+    UnknownType(i64),
+    /// The value of the packet doesn't fit in an i64
+    Overflow,
+}
+
+impl core::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            EvalError::WrongArity {
+                type_id,
+                expected,
+                got,
+            } => write!(
+                f,
+                "packet of type {} expects {} sub-packets, got {}",
+                type_id, expected, got
+            ),
+            EvalError::EmptyOperands => f.write_str("operator packet has no sub-packets"),
+            EvalError::UnknownType(type_id) => write!(f, "unknown packet type {}", type_id),
+            EvalError::Overflow => f.write_str("packet value overflows an i64"),
+        }
+    }
+}
+
+impl core::error::Error for EvalError {}
+
+/// How much of a transmission the decoder will take on, so untrusted input can't run it out of
+/// stack or memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Packets on the longest path down from the root, counting the root
+    pub max_depth: usize,
+    /// Packets in the whole tree
+    pub max_packets: usize,
+}
+
+impl Default for DecodeLimits {
+    /// Far more than any puzzle input needs
+    fn default() -> Self {
+        DecodeLimits {
+            max_depth: 512,
+            max_packets: 1 << 20,
+        }
+    }
+}
+
+/// Which of the `DecodeLimits` a transmission went over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Depth,
+    Packets,
+}
+
+/// Reasons a transmission can't be decoded into a packet
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidHex(char),
+    InvalidBinary(char),
+    Truncated,
+    LimitExceeded(Limit),
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidHex(c) => write!(f, "invalid hex digit {:?}", c),
+            DecodeError::InvalidBinary(c) => write!(f, "invalid binary digit {:?}", c),
+            DecodeError::Truncated => f.write_str("transmission ends in the middle of a packet"),
+            DecodeError::LimitExceeded(Limit::Depth) => {
+                f.write_str("packets are nested too deeply to decode")
+            }
+            DecodeError::LimitExceeded(Limit::Packets) => {
+                f.write_str("transmission has too many packets to decode")
+            }
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Folds a packet tree bottom-up. `Packet::walk` calls `literal` on each literal packet and
+/// `operator` on each operator packet once all of its sub-packets have been visited
+pub trait PacketVisitor {
+    type Output;
+
+    fn literal(&mut self, packet: &Packet, value: i64) -> Self::Output;
+
+    /// `operands` holds the outputs for the sub-packets, in order
+    fn operator(&mut self, packet: &Packet, operands: Vec<Self::Output>) -> Self::Output;
+}
+
+struct VersionSum;
+
+impl PacketVisitor for VersionSum {
+    type Output = i64;
+
+    fn literal(&mut self, packet: &Packet, _: i64) -> i64 {
+        packet.version
+    }
+
+    fn operator(&mut self, packet: &Packet, operands: Vec<i64>) -> i64 {
+        packet.version + operands.iter().sum::<i64>()
+    }
+}
+
+struct Evaluator;
+
+impl PacketVisitor for Evaluator {
+    type Output = Result<i64, EvalError>;
+
+    fn literal(&mut self, packet: &Packet, value: i64) -> Self::Output {
+        if packet.type_id == TYPE_LITERAL {
+            Ok(value)
+        } else {
+            apply(packet.type_id, Vec::new())
+        }
+    }
+
+    fn operator(&mut self, packet: &Packet, operands: Vec<Self::Output>) -> Self::Output {
+        apply(packet.type_id, operands)
+    }
+}
+
+/// Apply the operator `type_id` to the values of its sub-packets, checking the number of operands
+/// before looking at any of their values
+fn apply(type_id: i64, operands: Vec<Result<i64, EvalError>>) -> Result<i64, EvalError> {
+    let expected = match type_id {
+        TYPE_LITERAL => Some(0),
+        TYPE_GREATER_THAN | TYPE_LESS_THAN | TYPE_EQUAL_TO => Some(2),
+        TYPE_SUM | TYPE_PRODUCT | TYPE_MINIMUM | TYPE_MAXIMUM => None,
+        _ => return Err(EvalError::UnknownType(type_id)),
+    };
+    match expected {
+        Some(expected) if expected != operands.len() => {
+            return Err(EvalError::WrongArity {
+                type_id,
+                expected,
+                got: operands.len(),
+            });
+        }
+        None if operands.is_empty() => return Err(EvalError::EmptyOperands),
+        _ => {}
+    }
+
+    let values = operands.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+    match type_id {
+        TYPE_SUM => exact(values.iter().map(|&v| v as i128).sum()),
+        TYPE_PRODUCT => product(&values),
+        TYPE_MINIMUM => Ok(*values.iter().min().unwrap()),
+        TYPE_MAXIMUM => Ok(*values.iter().max().unwrap()),
+        TYPE_GREATER_THAN => Ok((values[0] > values[1]) as i64),
+        TYPE_LESS_THAN => Ok((values[0] < values[1]) as i64),
+        TYPE_EQUAL_TO => Ok((values[0] == values[1]) as i64),
+        _ => unreachable!(),
+    }
+}
+
+/// `value` if it fits in an i64
+fn exact(value: i128) -> Result<i64, EvalError> {
+    i64::try_from(value).map_err(|_| EvalError::Overflow)
+}
+
+/// The product of `values`, which is only an error if the whole product doesn't fit in an i64, not
+/// if some of the values multiplied together in order don't. Then sums and products of sums and
+/// products have the same value however they're grouped, and `Simplifier` can flatten them. Past a
+/// zero the product no longer grows, so once it's too big for an i64 it can't come back
+fn product(values: &[i64]) -> Result<i64, EvalError> {
+    if values.contains(&0) {
+        return Ok(0);
+    }
+    let product = values.iter().try_fold(1i128, |acc, &v| {
+        let acc = acc * v as i128;
+        (acc.unsigned_abs() <= 1 << 63).then_some(acc)
+    });
+    exact(product.ok_or(EvalError::Overflow)?)
+}
+
+/// What `Packet::simplify` did to a tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SimplifyReport {
+    /// Operator packets replaced by the literal they evaluate to
+    pub folded: usize,
+    /// Operator packets whose sub-packets were spliced into a parent of the same type
+    pub flattened: usize,
+    /// Packets in the original tree that aren't in the simplified one
+    pub eliminated: usize,
+}
+
+/// Rebuilds a tree bottom-up, folding each operator whose sub-packets have all become literals. Each
+/// packet comes out with what it evaluates to, so the simplifier can tell which are malformed
+struct Simplifier {
+    report: SimplifyReport,
+}
+
+impl Simplifier {
+    fn is_constant(packet: &Packet) -> bool {
+        packet.type_id == TYPE_LITERAL && matches!(packet.data, PacketData::Literal(_))
+    }
+}
+
+impl PacketVisitor for Simplifier {
+    type Output = (Packet, Result<i64, EvalError>);
+
+    fn literal(&mut self, packet: &Packet, value: i64) -> Self::Output {
+        let simple = Packet {
+            version: packet.version,
+            type_id: packet.type_id,
+            encoding: packet.encoding,
+            data: PacketData::Literal(value),
+        };
+        (simple, Evaluator.literal(packet, value))
+    }
+
+    fn operator(&mut self, packet: &Packet, operands: Vec<Self::Output>) -> Self::Output {
+        let value = apply(
+            packet.type_id,
+            operands.iter().map(|(_, value)| *value).collect(),
+        );
+
+        // Malformed operators are left as they are, so evaluating still fails the same way
+        if let Ok(value) = value {
+            if operands
+                .iter()
+                .all(|(operand, _)| Simplifier::is_constant(operand))
+            {
+                self.report.folded += 1;
+                self.report.eliminated += operands.len();
+                let folded = Packet {
+                    version: packet.version,
+                    type_id: TYPE_LITERAL,
+                    encoding: Encoding::groups_for(value),
+                    data: PacketData::Literal(value),
+                };
+                return (folded, Ok(value));
+            }
+        }
+
+        // Sums of sums and so on are one sum. Only well-formed operators with sub-packets of their
+        // own are spliced in: an empty one would stop being an error, and the error from a
+        // malformed one could change if it were spread out among the parent's sub-packets
+        let associative = matches!(
+            packet.type_id,
+            TYPE_SUM | TYPE_PRODUCT | TYPE_MINIMUM | TYPE_MAXIMUM
+        );
+        let mut packets = Vec::with_capacity(operands.len());
+        for (mut operand, operand_value) in operands {
+            match &mut operand.data {
+                PacketData::Packets(nested)
+                    if associative
+                        && operand.type_id == packet.type_id
+                        && !nested.is_empty()
+                        && operand_value.is_ok() =>
+                {
+                    self.report.flattened += 1;
+                    self.report.eliminated += 1;
+                    packets.append(nested);
+                }
+                _ => packets.push(operand),
+            }
+        }
+        let simple = Packet {
+            version: packet.version,
+            type_id: packet.type_id,
+            encoding: packet.encoding,
+            data: PacketData::Packets(packets),
+        };
+        (simple, value)
+    }
+}
+
+/// Counts of what a packet tree is made of, from `Packet::stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PacketStats {
+    /// Packets of each type, indexed by type ID
+    pub by_type: [usize; 8],
+    /// Packets on the longest path down from the root, counting the root
+    pub max_depth: usize,
+    /// Bits of literal values, 4 for each group including any leading groups of zeros
+    pub literal_bits: usize,
+    /// Operator packets that give the length of their sub-packets in bits
+    pub total_length: usize,
+    /// Operator packets that give the number of their sub-packets
+    pub packet_count: usize,
+}
+
+/// Tallies up `PacketStats`, with the depth of each packet's subtree as its output
+struct StatsCollector {
+    stats: PacketStats,
+}
+
+impl StatsCollector {
+    fn count(&mut self, packet: &Packet) {
+        self.stats.by_type[packet.type_id as usize] += 1;
+        match packet.encoding {
+            Encoding::Groups(groups) => self.stats.literal_bits += 4 * groups,
+            Encoding::TotalLength => self.stats.total_length += 1,
+            Encoding::PacketCount => self.stats.packet_count += 1,
+        }
+    }
+}
+
+impl PacketVisitor for StatsCollector {
+    type Output = usize;
+
+    fn literal(&mut self, packet: &Packet, _: i64) -> usize {
+        self.count(packet);
+        1
+    }
+
+    fn operator(&mut self, packet: &Packet, operands: Vec<usize>) -> usize {
+        self.count(packet);
+        1 + operands.into_iter().max().unwrap_or(0)
+    }
+}
+
+impl Packet {
+    /// What this tree is made of. Packets that weren't decoded, such as those made by `simplify`,
+    /// count as written in the fewest groups and with the length type they were decoded with
+    pub fn stats(&self) -> PacketStats {
+        let mut collector = StatsCollector {
+            stats: PacketStats::default(),
+        };
+        collector.stats.max_depth = self.walk(&mut collector);
+        collector.stats
+    }
+
+    /// A copy of this tree with every well-formed operator folded into the literal it evaluates to,
+    /// and nested operators of the same associative type merged into one. The simplified tree
+    /// evaluates to the same value, or fails the same way, but its version sum is not kept
+    pub fn simplify(&self) -> (Packet, SimplifyReport) {
+        let mut simplifier = Simplifier {
+            report: SimplifyReport::default(),
+        };
+        let (packet, _) = self.walk(&mut simplifier);
+        (packet, simplifier.report)
+    }
+
+    /// Run `visitor` over this packet and all of its sub-packets. The tree is walked with an
+    /// explicit stack, so arbitrarily deep nesting can't overflow the call stack
+    pub fn walk<V: PacketVisitor>(&self, visitor: &mut V) -> V::Output {
+        // Each frame is a packet and the index of the next sub-packet to visit
+        let mut frames = vec![(self, 0)];
+        let mut outputs = Vec::new();
+        while let Some((packet, next)) = frames.last_mut() {
+            let packet: &Packet = packet;
+            let output = match &packet.data {
+                PacketData::Literal(value) => visitor.literal(packet, *value),
+                PacketData::Packets(packets) if *next < packets.len() => {
+                    let child = &packets[*next];
+                    *next += 1;
+                    frames.push((child, 0));
+                    continue;
+                }
+                PacketData::Packets(packets) => {
+                    let operands = outputs.split_off(outputs.len() - packets.len());
+                    visitor.operator(packet, operands)
+                }
+            };
+            frames.pop();
+            outputs.push(output);
+        }
+
+        outputs.pop().expect("the root packet has an output")
+    }
+
+    /// Evaluate the expression this packet represents, panicking if it is malformed
+    pub fn evaluate(&self) -> i64 {
+        self.try_evaluate()
+            .unwrap_or_else(|err| panic!("Malformed packet: {}", err))
+    }
+
+    /// Evaluate the expression this packet represents
+    pub fn try_evaluate(&self) -> Result<i64, EvalError> {
+        self.walk(&mut Evaluator)
+    }
+}
+
+impl Drop for Packet {
+    // The default drop recurses into sub-packets, so flatten the tree first
+    fn drop(&mut self) {
+        if let PacketData::Packets(packets) = &mut self.data {
+            let mut pending = core::mem::take(packets);
+            while let Some(mut packet) = pending.pop() {
+                if let PacketData::Packets(packets) = &mut packet.data {
+                    pending.append(packets);
+                }
+            }
+        }
+    }
+}
+
+fn hex_to_bits(hex: char) -> Result<[bool; 4], DecodeError> {
+    let num = hex.to_digit(16).ok_or(DecodeError::InvalidHex(hex))?;
+    Ok([3, 2, 1, 0].map(|bit| (num & (1 << bit)) != 0))
+}
+
+/// Read the integer in `bits[range]`, failing if the stream ends first
+fn read_integer(bits: &[bool], range: core::ops::Range<usize>) -> Result<i64, DecodeError> {
+    bits.get(range)
+        .map(to_integer)
+        .ok_or(DecodeError::Truncated)
+}
+
+fn parse_literal(bits: &[bool]) -> Result<(usize, i64), DecodeError> {
+    let mut literal = 0;
+    let mut next = 0;
+    loop {
+        let group = bits.get(next..next + 5).ok_or(DecodeError::Truncated)?;
+        literal = (literal << 4) | to_integer(&group[1..]);
+        next += 5;
+
+        if !group[0] {
+            return Ok((next, literal));
+        }
+    }
+}
+
+/// What's left of the `DecodeLimits` while decoding a transmission
+struct Budget {
+    limits: DecodeLimits,
+    packets: usize,
+}
+
+impl Budget {
+    /// Account for one more packet, `depth` packets down from the root
+    fn start_packet(&mut self, depth: usize) -> Result<(), DecodeError> {
+        if depth > self.limits.max_depth {
+            return Err(DecodeError::LimitExceeded(Limit::Depth));
+        }
+        if self.packets >= self.limits.max_packets {
+            return Err(DecodeError::LimitExceeded(Limit::Packets));
+        }
+        self.packets += 1;
+        Ok(())
+    }
+}
+
+fn parse_n_bits(
+    bits: &[bool],
+    packet_start: usize,
+    n_bits: usize,
+    depth: usize,
+    budget: &mut Budget,
+) -> Result<(usize, PacketData), DecodeError> {
+    let mut next_packet = packet_start;
+
+    let mut packets = Vec::new();
+    while next_packet - packet_start < n_bits {
+        let (i, packet) = parse_packet(&bits[next_packet..], depth, budget)?;
+        packets.push(packet);
+        next_packet += i;
+    }
+    Ok((next_packet, PacketData::Packets(packets)))
+}
+
+fn parse_n_packets(
+    bits: &[bool],
+    packet_start: usize,
+    n_packets: usize,
+    depth: usize,
+    budget: &mut Budget,
+) -> Result<(usize, PacketData), DecodeError> {
+    let mut next_packet = packet_start;
+
+    let mut packets = Vec::new();
+    for _ in 0..n_packets {
+        let (i, packet) = parse_packet(&bits[next_packet..], depth, budget)?;
+        packets.push(packet);
+        next_packet += i;
+    }
+    Ok((next_packet, PacketData::Packets(packets)))
+}
+
+/// Parse the packet at the start of `bits`, which is `depth` packets down from the root
+fn parse_packet(
+    bits: &[bool],
+    depth: usize,
+    budget: &mut Budget,
+) -> Result<(usize, Packet), DecodeError> {
+    budget.start_packet(depth)?;
+    let version = read_integer(bits, 0..3)?;
+    let type_id = read_integer(bits, 3..6)?;
+    let (next, encoding, data) = if type_id == TYPE_LITERAL {
+        let (next, literal) = parse_literal(&bits[6..])?;
+        (
+            6 + next,
+            Encoding::Groups(next / 5),
+            PacketData::Literal(literal),
+        )
+    } else {
+        match read_integer(bits, 6..7)? {
+            LEN_TOTAL_LENGTH => {
+                // Total length is the next 15 bits
+                let num_bits = read_integer(bits, 7..22)? as usize;
+                let (next, data) = parse_n_bits(bits, 22, num_bits, depth + 1, budget)?;
+                (next, Encoding::TotalLength, data)
+            }
+            LEN_NUM_SUBPACKETS => {
+                // Total number of sub-packets is the next 11
+                let num_packets = read_integer(bits, 7..18)? as usize;
+                let (next, data) = parse_n_packets(bits, 18, num_packets, depth + 1, budget)?;
+                (next, Encoding::PacketCount, data)
+            }
+            _ => unreachable!("length type is a single bit"),
+        }
+    };
+
+    Ok((
+        next,
+        Packet {
+            version,
+            type_id,
+            encoding,
+            data,
+        },
+    ))
+}
+
+/// One place two packet trees differ, as found by `diff`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketDiff {
+    /// Where the packets are: `root`, followed by the index of each sub-packet on the way down
+    pub path: String,
+    pub kind: DiffKind,
+}
+
+/// What differs between two packets at the same place, as (first, second)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Version(i64, i64),
+    Type(i64, i64),
+    Literal(i64, i64),
+    /// Both are operators, holding this many sub-packets each
+    ChildCount(usize, usize),
+}
+
+impl core::fmt::Display for PacketDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let (field, a, b) = match self.kind {
+            DiffKind::Version(a, b) => ("version", a, b),
+            DiffKind::Type(a, b) => ("type", a, b),
+            DiffKind::Literal(a, b) => ("literal", a, b),
+            DiffKind::ChildCount(a, b) => ("sub-packets", a as i64, b as i64),
+        };
+        write!(f, "{}: {} {} != {}", self.path, field, a, b)
+    }
+}
+
+/// Every place the trees `a` and `b` differ, parents before their sub-packets. A literal and an
+/// operator only differ in type. Two operators have their sub-packets compared pairwise, as far as
+/// the shorter list goes, even when their types or counts differ. How the packets were encoded is
+/// not compared, so a tree matches a re-encoding of itself
+pub fn diff(a: &Packet, b: &Packet) -> Vec<PacketDiff> {
+    let mut diffs = Vec::new();
+    // An explicit stack, so deeply nested transmissions can't overflow the call stack
+    let mut pending = vec![(a, b, String::from("root"))];
+    while let Some((a, b, path)) = pending.pop() {
+        let mut found = |kind| {
+            diffs.push(PacketDiff {
+                path: path.clone(),
+                kind,
+            })
+        };
+        if a.version != b.version {
+            found(DiffKind::Version(a.version, b.version));
+        }
+        if a.type_id != b.type_id {
+            found(DiffKind::Type(a.type_id, b.type_id));
+        }
+
+        match (&a.data, &b.data) {
+            (PacketData::Literal(x), PacketData::Literal(y)) if x != y => {
+                found(DiffKind::Literal(*x, *y))
+            }
+            (PacketData::Packets(xs), PacketData::Packets(ys)) => {
+                if xs.len() != ys.len() {
+                    found(DiffKind::ChildCount(xs.len(), ys.len()));
+                }
+                // Pushed last to first, so they come off the stack in order
+                for (i, (x, y)) in xs.iter().zip(ys.iter()).enumerate().rev() {
+                    pending.push((x, y, format!("{}.{}", path, i)));
+                }
+            }
+            _ => {}
+        }
+    }
+    diffs
+}
+
+/// Decode the outermost packet of a transmission written as hex or `bin:` binary, ignoring any
+/// padding after it
+pub fn decode(text: &str) -> Result<Packet, DecodeError> {
+    BitStream::from_text(text)?.decode()
+}
+
+/// Same as `decode`, within `limits` rather than the defaults
+pub fn decode_with(text: &str, limits: DecodeLimits) -> Result<Packet, DecodeError> {
+    BitStream::from_text(text)?.decode_with(limits)
+}
+
+/// The versions of the packet and all of its sub-packets added up
+pub fn sum_packet_versions(packet: &Packet) -> i64 {
+    packet.walk(&mut VersionSum)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn literal(v: i64) -> Packet {
+        Packet {
+            version: 0,
+            type_id: TYPE_LITERAL,
+            encoding: Encoding::groups_for(v),
+            data: PacketData::Literal(v),
+        }
+    }
+
+    fn operator(type_id: i64, packets: Vec<Packet>) -> Packet {
+        Packet {
+            version: 0,
+            type_id,
+            encoding: Encoding::PacketCount,
+            data: PacketData::Packets(packets),
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let packet = operator(TYPE_GREATER_THAN, vec![literal(1)]);
+        assert_eq!(
+            packet.try_evaluate(),
+            Err(EvalError::WrongArity {
+                type_id: TYPE_GREATER_THAN,
+                expected: 2,
+                got: 1
+            })
+        );
+
+        let packet = operator(TYPE_MINIMUM, vec![]);
+        assert_eq!(packet.try_evaluate(), Err(EvalError::EmptyOperands));
+
+        let packet = operator(TYPE_PRODUCT, vec![literal(1 << 40), literal(1 << 40)]);
+        assert_eq!(packet.try_evaluate(), Err(EvalError::Overflow));
+        let packet = operator(TYPE_SUM, vec![literal(i64::MAX), literal(1)]);
+        assert_eq!(packet.try_evaluate(), Err(EvalError::Overflow));
+
+        let packet = operator(TYPE_LITERAL, vec![literal(1)]);
+        assert!(matches!(
+            packet.try_evaluate(),
+            Err(EvalError::WrongArity { expected: 0, .. })
+        ));
+
+        // Errors in nested packets bubble up
+        let packet = operator(
+            TYPE_SUM,
+            vec![literal(3), operator(TYPE_EQUAL_TO, vec![literal(1)])],
+        );
+        assert!(matches!(
+            packet.try_evaluate(),
+            Err(EvalError::WrongArity { got: 1, .. })
+        ));
+
+        let packet = operator(
+            TYPE_MAXIMUM,
+            vec![literal(3), operator(9, vec![literal(1)])],
+        );
+        assert_eq!(packet.try_evaluate(), Err(EvalError::UnknownType(9)));
+
+        let packet = operator(TYPE_MAXIMUM, vec![literal(3), literal(7), literal(5)]);
+        assert_eq!(packet.try_evaluate(), Ok(7));
+    }
+
+    #[test]
+    fn deep_nesting() {
+        const DEPTH: i64 = 100_000;
+        let mut packet = literal(42);
+        packet.version = 1;
+        for depth in 0..DEPTH {
+            let type_id = [TYPE_SUM, TYPE_MAXIMUM, TYPE_PRODUCT][depth as usize % 3];
+            packet = operator(type_id, vec![packet]);
+            packet.version = 1;
+        }
+
+        assert_eq!(sum_packet_versions(&packet), DEPTH + 1);
+        assert_eq!(packet.evaluate(), 42);
+
+        let packet = operator(TYPE_EQUAL_TO, vec![packet, literal(42)]);
+        assert_eq!(packet.evaluate(), 1);
+
+        let (simple, report) = packet.simplify();
+        assert_eq!(simple.try_evaluate(), Ok(1));
+        assert_eq!(report.eliminated, DEPTH as usize + 2);
+    }
+
+    /// Number of packets in a tree
+    fn size(packet: &Packet) -> usize {
+        struct Size;
+        impl PacketVisitor for Size {
+            type Output = usize;
+            fn literal(&mut self, _: &Packet, _: i64) -> usize {
+                1
+            }
+            fn operator(&mut self, _: &Packet, operands: Vec<usize>) -> usize {
+                1 + operands.iter().sum::<usize>()
+            }
+        }
+        packet.walk(&mut Size)
+    }
+
+    #[test]
+    fn simplify() {
+        for hex in [
+            "C200B40A82",
+            "04005AC33890",
+            "880086C3E88112",
+            "CE00C43D881120",
+            "D8005AC2A8F0",
+            "F600BC2D8F",
+            "9C005AC2F8F0",
+            "9C0141080250320F1802104A08",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+        ] {
+            let packet = decode(hex).unwrap();
+            let (simple, report) = packet.simplify();
+            assert_eq!(simple.try_evaluate(), packet.try_evaluate(), "{}", hex);
+            assert!(matches!(simple.data, PacketData::Literal(_)), "{}", hex);
+            assert_eq!(report.eliminated, size(&packet) - 1, "{}", hex);
+            assert_eq!(report.flattened, 0);
+        }
+
+        // Everything around a malformed packet that can be folded is, but the sum holding it
+        // stays where it is rather than spreading its error through the parent
+        let packet = operator(
+            TYPE_SUM,
+            vec![
+                operator(TYPE_PRODUCT, vec![literal(2), literal(3)]),
+                operator(
+                    TYPE_SUM,
+                    vec![literal(1), operator(TYPE_LESS_THAN, vec![literal(1)])],
+                ),
+                operator(TYPE_SUM, vec![literal(4), literal(5)]),
+            ],
+        );
+        let (simple, report) = packet.simplify();
+        assert_eq!(
+            report,
+            SimplifyReport {
+                folded: 2,
+                flattened: 0,
+                eliminated: 4
+            }
+        );
+        assert_eq!(size(&simple), size(&packet) - 4);
+        assert_eq!(simple.try_evaluate(), packet.try_evaluate());
+        match &simple.data {
+            PacketData::Packets(packets) => assert_eq!(packets.len(), 3),
+            PacketData::Literal(_) => panic!("malformed packet was folded"),
+        }
+
+        let (simple, _) = operator(TYPE_MINIMUM, vec![]).simplify();
+        assert_eq!(simple.try_evaluate(), Err(EvalError::EmptyOperands));
+
+        // An empty sum inside a sum is still an error once simplified
+        let packet = operator(TYPE_SUM, vec![literal(1), operator(TYPE_SUM, vec![])]);
+        let (simple, report) = packet.simplify();
+        assert_eq!(simple.try_evaluate(), packet.try_evaluate());
+        assert_eq!(simple.try_evaluate(), Err(EvalError::EmptyOperands));
+        assert_eq!(report.flattened, 0);
+
+        // Regrouping doesn't change whether a sum or product fits
+        let big = 1 << 40;
+        for packet in [
+            operator(
+                TYPE_PRODUCT,
+                vec![
+                    operator(TYPE_PRODUCT, vec![literal(big), literal(big)]),
+                    operator(TYPE_MINIMUM, vec![literal(0), literal(1)]),
+                    operator(TYPE_MAXIMUM, vec![literal(0), operator(TYPE_SUM, vec![])]),
+                ],
+            ),
+            operator(
+                TYPE_PRODUCT,
+                vec![
+                    literal(big),
+                    operator(
+                        TYPE_PRODUCT,
+                        vec![literal(big), operator(TYPE_MAXIMUM, vec![])],
+                    ),
+                ],
+            ),
+            operator(
+                TYPE_SUM,
+                vec![
+                    literal(i64::MAX),
+                    operator(TYPE_SUM, vec![literal(i64::MAX), literal(1)]),
+                ],
+            ),
+        ] {
+            let (simple, _) = packet.simplify();
+            assert_eq!(simple.try_evaluate(), packet.try_evaluate());
+        }
+        let packet = operator(
+            TYPE_PRODUCT,
+            vec![
+                operator(TYPE_PRODUCT, vec![literal(big), literal(big)]),
+                operator(TYPE_SUM, vec![literal(0), operator(TYPE_LESS_THAN, vec![])]),
+            ],
+        );
+        let (simple, _) = packet.simplify();
+        assert_eq!(simple.try_evaluate(), packet.try_evaluate());
+        // The first sub-packet's error is the one reported, either way
+        assert_eq!(packet.try_evaluate(), Err(EvalError::Overflow));
+        assert_eq!(
+            operator(TYPE_PRODUCT, vec![literal(big), literal(big), literal(0)]).try_evaluate(),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let stats = decode("D2FE28").unwrap().stats();
+        let mut by_type = [0; 8];
+        by_type[TYPE_LITERAL as usize] = 1;
+        assert_eq!(
+            stats,
+            PacketStats {
+                by_type,
+                max_depth: 1,
+                literal_bits: 12,
+                total_length: 0,
+                packet_count: 0
+            }
+        );
+
+        // 10 fits in one group and 20 takes two
+        let stats = decode("38006F45291200").unwrap().stats();
+        assert_eq!(stats.by_type[TYPE_LESS_THAN as usize], 1);
+        assert_eq!(stats.by_type[TYPE_LITERAL as usize], 2);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.literal_bits, 12);
+        assert_eq!((stats.total_length, stats.packet_count), (1, 0));
+
+        let stats = decode("EE00D40C823060").unwrap().stats();
+        assert_eq!(stats.by_type[TYPE_MAXIMUM as usize], 1);
+        assert_eq!(stats.by_type[TYPE_LITERAL as usize], 3);
+        assert_eq!(stats.literal_bits, 12);
+        assert_eq!((stats.total_length, stats.packet_count), (0, 1));
+
+        // Three operators nested around a literal
+        let stats = decode("8A004A801A8002F478").unwrap().stats();
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(stats.by_type.iter().sum::<usize>(), 4);
+        assert_eq!(stats.total_length + stats.packet_count, 3);
+
+        for hex in [
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "9C0141080250320F1802104A08",
+        ] {
+            let packet = decode(hex).unwrap();
+            let stats = packet.stats();
+            assert_eq!(
+                stats.by_type.iter().sum::<usize>(),
+                size(&packet),
+                "{}",
+                hex
+            );
+            let operators = size(&packet) - stats.by_type[TYPE_LITERAL as usize];
+            assert_eq!(
+                stats.total_length + stats.packet_count,
+                operators,
+                "{}",
+                hex
+            );
+
+            // Folded down to one literal with enough bits for the value
+            let (simple, _) = packet.simplify();
+            let value = simple.try_evaluate().unwrap();
+            assert!(value < 1 << simple.stats().literal_bits, "{}", hex);
+        }
+
+        let (deep, _) = operator(TYPE_SUM, vec![operator(TYPE_SUM, vec![literal(1)])]).simplify();
+        assert_eq!(deep.stats().max_depth, 1);
+    }
+
+    #[test]
+    fn diffs() {
+        let at = |path: &str, kind| PacketDiff {
+            path: path.into(),
+            kind,
+        };
+
+        let example = decode("8A004A801A8002F478").unwrap();
+        assert_eq!(diff(&example, &decode("8A004A801A8002F478").unwrap()), []);
+        // The same value written with a leading group of zeros
+        assert_eq!(
+            diff(&decode("D2FE28").unwrap(), &decode("D217F140").unwrap()),
+            []
+        );
+
+        // One field at a time
+        assert_eq!(
+            diff(&literal(5), &literal(7)),
+            [at("root", DiffKind::Literal(5, 7))]
+        );
+        let mut bumped = literal(5);
+        bumped.version = 3;
+        assert_eq!(
+            diff(&literal(5), &bumped),
+            [at("root", DiffKind::Version(0, 3))]
+        );
+        let min = operator(TYPE_MINIMUM, vec![literal(2)]);
+        assert_eq!(
+            diff(&min, &literal(2)),
+            [at("root", DiffKind::Type(TYPE_MINIMUM, TYPE_LITERAL))]
+        );
+        assert_eq!(
+            diff(&min, &operator(TYPE_MAXIMUM, vec![literal(3)])),
+            [
+                at("root", DiffKind::Type(TYPE_MINIMUM, TYPE_MAXIMUM)),
+                at("root.0", DiffKind::Literal(2, 3)),
+            ]
+        );
+
+        // Sub-packets added and taken away, deeper down
+        let a = operator(
+            TYPE_SUM,
+            vec![
+                literal(1),
+                operator(TYPE_PRODUCT, vec![literal(2), literal(3)]),
+                operator(TYPE_SUM, vec![literal(8), operator(TYPE_SUM, vec![])]),
+            ],
+        );
+        let b = operator(
+            TYPE_SUM,
+            vec![
+                literal(1),
+                operator(TYPE_PRODUCT, vec![literal(2)]),
+                operator(TYPE_SUM, vec![literal(8), literal(9)]),
+                literal(4),
+            ],
+        );
+        let diffs = diff(&a, &b);
+        assert_eq!(
+            diffs,
+            [
+                at("root", DiffKind::ChildCount(3, 4)),
+                at("root.1", DiffKind::ChildCount(2, 1)),
+                at("root.2.1", DiffKind::Type(TYPE_SUM, TYPE_LITERAL)),
+            ]
+        );
+        assert_eq!(diffs[1].to_string(), "root.1: sub-packets 2 != 1");
+        assert_eq!(
+            diff(&b, &a)
+                .iter()
+                .map(|d| d.path.as_str())
+                .collect::<Vec<_>>(),
+            ["root", "root.1", "root.2.1"]
+        );
+    }
+
+    #[test]
+    fn text() {
+        // Hex that happens to start 0b is still hex
+        let hex = BitStream::from_text("0b10").unwrap();
+        assert_eq!(hex.inner(), BitStream::from_bytes(&[0x0b, 0x10]).inner());
+        let binary = BitStream::from_text(" bin:0000 1011\n0001 0000").unwrap();
+        assert_eq!(binary.inner(), hex.inner());
+        assert_eq!(
+            BitStream::from_text("bin:0b10").unwrap_err(),
+            DecodeError::InvalidBinary('b')
+        );
+    }
+
+    #[test]
+    fn limits() {
+        // Operators holding one sub-packet each, nested far deeper than the stack could recurse
+        let nested =
+            |depth: usize| format!("bin:{}00010000001", "000000100000000001".repeat(depth - 1));
+        let packet = decode(&nested(512)).unwrap();
+        assert_eq!(packet.stats().max_depth, 512);
+        assert_eq!(
+            decode(&nested(513)).unwrap_err(),
+            DecodeError::LimitExceeded(Limit::Depth)
+        );
+        assert_eq!(
+            decode(&nested(1_000_000)).unwrap_err(),
+            DecodeError::LimitExceeded(Limit::Depth)
+        );
+
+        // A sum of 2047 literals
+        let wide = format!("bin:000000111111111111{}", "00010000001".repeat(2047));
+        assert_eq!(decode(&wide).unwrap().try_evaluate(), Ok(2047));
+        let limits = DecodeLimits {
+            max_depth: 2,
+            max_packets: 2048,
+        };
+        assert_eq!(decode_with(&wide, limits).unwrap().stats().max_depth, 2);
+        assert_eq!(
+            decode_with(
+                &wide,
+                DecodeLimits {
+                    max_packets: 2047,
+                    ..limits
+                }
+            )
+            .unwrap_err(),
+            DecodeError::LimitExceeded(Limit::Packets)
+        );
+        assert_eq!(
+            decode_with(
+                &wide,
+                DecodeLimits {
+                    max_depth: 1,
+                    ..limits
+                }
+            )
+            .unwrap_err(),
+            DecodeError::LimitExceeded(Limit::Depth)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Malformed packet")]
+    fn evaluate_panics() {
+        operator(TYPE_LESS_THAN, vec![]).evaluate();
+    }
+}
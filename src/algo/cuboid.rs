@@ -0,0 +1,115 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+
+/// Box of cubes, with each range inclusive on both ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cuboid {
+    pub x: (i64, i64),
+    pub y: (i64, i64),
+    pub z: (i64, i64),
+}
+
+impl Cuboid {
+    pub fn volume(&self) -> i64 {
+        (self.x.1 - self.x.0 + 1) * (self.y.1 - self.y.0 + 1) * (self.z.1 - self.z.0 + 1)
+    }
+
+    /// `volume`, or None if it doesn't fit in an i64
+    pub fn checked_volume(&self) -> Option<i64> {
+        let side = |r: (i64, i64)| r.1.checked_sub(r.0)?.checked_add(1);
+        side(self.x)?
+            .checked_mul(side(self.y)?)?
+            .checked_mul(side(self.z)?)
+    }
+
+    pub fn contains_point(&self, x: i64, y: i64, z: i64) -> bool {
+        let within = |r: (i64, i64), v: i64| r.0 <= v && v <= r.1;
+        within(self.x, x) && within(self.y, y) && within(self.z, z)
+    }
+
+    /// Whether every cube of `other` is also in this cuboid
+    pub fn contains(&self, other: &Cuboid) -> bool {
+        let within = |a: (i64, i64), b: (i64, i64)| a.0 <= b.0 && b.1 <= a.1;
+        within(self.x, other.x) && within(self.y, other.y) && within(self.z, other.z)
+    }
+
+    /// The cubes in both cuboids, if there are any
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let overlap = |a: (i64, i64), b: (i64, i64)| {
+            let r = (max(a.0, b.0), min(a.1, b.1));
+            (r.0 <= r.1).then_some(r)
+        };
+        Some(Cuboid {
+            x: overlap(self.x, other.x)?,
+            y: overlap(self.y, other.y)?,
+            z: overlap(self.z, other.z)?,
+        })
+    }
+
+    /// The cubes in `self` but not in `other`, as up to six disjoint cuboids
+    pub fn difference(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let Some(mut overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        // Slabs either side of the overlap along x, then the rest of the overlap's x range either
+        // side along y, then the rest of its x and y ranges either side along z
+        type Axis = fn(&mut Cuboid) -> &mut (i64, i64);
+        let axes: [Axis; 3] = [|c| &mut c.x, |c| &mut c.y, |c| &mut c.z];
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in axes {
+            let (lo, hi) = *axis(&mut overlap);
+            let (start, end) = *axis(&mut rest);
+            if start < lo {
+                let mut piece = rest;
+                *axis(&mut piece) = (start, lo - 1);
+                pieces.push(piece);
+            }
+            if hi < end {
+                let mut piece = rest;
+                *axis(&mut piece) = (hi + 1, end);
+                pieces.push(piece);
+            }
+            *axis(&mut rest) = (lo, hi);
+        }
+        pieces
+    }
+
+    /// Faces of the unit cubes on the outside of the cuboid
+    pub fn surface_area(&self) -> i64 {
+        let (dx, dy, dz) = (
+            self.x.1 - self.x.0 + 1,
+            self.y.1 - self.y.0 + 1,
+            self.z.1 - self.z.0 + 1,
+        );
+        2 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Area of the face the two cuboids share if they sit side by side, or 0 if they don't touch
+    pub fn shared_face(&self, other: &Cuboid) -> i64 {
+        let overlap = |a: (i64, i64), b: (i64, i64)| max(0, 1 + min(a.1, b.1) - max(a.0, b.0));
+        let adjacent = |a: (i64, i64), b: (i64, i64)| a.1 + 1 == b.0 || b.1 + 1 == a.0;
+        if adjacent(self.x, other.x) {
+            overlap(self.y, other.y) * overlap(self.z, other.z)
+        } else if adjacent(self.y, other.y) {
+            overlap(self.x, other.x) * overlap(self.z, other.z)
+        } else if adjacent(self.z, other.z) {
+            overlap(self.x, other.x) * overlap(self.y, other.y)
+        } else {
+            0
+        }
+    }
+
+    /// The smallest cuboid containing both
+    pub fn bound(&self, other: &Cuboid) -> Cuboid {
+        let span = |a: (i64, i64), b: (i64, i64)| (min(a.0, b.0), max(a.1, b.1));
+        Cuboid {
+            x: span(self.x, other.x),
+            y: span(self.y, other.y),
+            z: span(self.z, other.z),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Counting the games of Dirac Dice, where every roll of the three-sided die splits the universe
+
+use alloc::vec;
+
+/// Each total of three rolls of the Dirac die, with the number of the 27 universes it happens in
+pub const ROLLS: [(u64, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+/// Scores below this haven't won yet
+const WINNING_SCORE: usize = 21;
+
+/// Index into the dense state space of `quantum_wins_dense`, with positions counted from 0
+fn dense_state(turn: usize, positions: [usize; 2], scores: [usize; 2]) -> usize {
+    let positions = positions[0] * 10 + positions[1];
+    let scores = scores[0] * WINNING_SCORE + scores[1];
+    (turn * 100 + positions) * WINNING_SCORE * WINNING_SCORE + scores
+}
+
+/// The same as `quantum_wins`, counting the universes in each state forwards from the start
+/// instead of recursing into them. Every move scores, so the states are visited in order of the
+/// sum of the scores and each one is complete before it is moved on from
+pub fn quantum_wins_dense(p1: u64, p2: u64) -> (u64, u64) {
+    let mut universes = vec![0u64; 2 * 100 * WINNING_SCORE * WINNING_SCORE];
+    universes[dense_state(0, [p1 as usize - 1, p2 as usize - 1], [0, 0])] = 1;
+
+    let mut wins = [0; 2];
+    for total in 0..2 * WINNING_SCORE - 1 {
+        let lowest = total.saturating_sub(WINNING_SCORE - 1);
+        for score1 in lowest..=total.min(WINNING_SCORE - 1) {
+            let scores = [score1, total - score1];
+            for turn in 0..2 {
+                for positions in (0..100).map(|pos| [pos / 10, pos % 10]) {
+                    let count = universes[dense_state(turn, positions, scores)];
+                    if count == 0 {
+                        continue;
+                    }
+
+                    for &(roll, times) in ROLLS.iter() {
+                        let (mut positions, mut scores) = (positions, scores);
+                        positions[turn] = (positions[turn] + roll as usize) % 10;
+                        scores[turn] += positions[turn] + 1;
+                        if scores[turn] >= WINNING_SCORE {
+                            wins[turn] += count * times;
+                        } else {
+                            universes[dense_state(1 - turn, positions, scores)] += count * times;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    (wins[0], wins[1])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rolls() {
+        let mut counts = [0; 10];
+        for d1 in 1..=3 {
+            for d2 in 1..=3 {
+                for d3 in 1..=3 {
+                    counts[d1 + d2 + d3] += 1;
+                }
+            }
+        }
+        for (roll, times) in ROLLS {
+            assert_eq!(counts[roll as usize], times);
+        }
+        assert_eq!(ROLLS.iter().map(|&(_, times)| times).sum::<u64>(), 27);
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(quantum_wins_dense(4, 8), (444356092776315, 341960390180808));
+    }
+}
@@ -0,0 +1,24 @@
+//! The parts of the puzzles that need nothing more than `alloc`, so they build without `std`
+
+pub mod bits;
+pub mod cuboid;
+pub mod dirac;
+pub mod snailfish;
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn builds_without_std() {
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["check", "--lib", "--no-default-features", "--quiet"])
+            .arg("--manifest-path")
+            .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+            .env(
+                "CARGO_TARGET_DIR",
+                std::env::temp_dir().join("aoc21-no-std"),
+            )
+            .status()
+            .expect("cargo should run");
+        assert!(status.success());
+    }
+}
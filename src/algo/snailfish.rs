@@ -0,0 +1,544 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Number {
+    Regular(i64),
+    Pair(Box<Number>, Box<Number>),
+}
+
+#[derive(Debug)]
+enum Xform {
+    Explode(Option<i64>, Option<i64>),
+    Split,
+    Identity,
+    /// An exploding value made a regular number too large for an i64
+    Overflow,
+}
+
+impl Xform {
+    fn reduced(&self) -> bool {
+        !matches!(self, Xform::Identity)
+    }
+}
+
+/// A regular number and how much it counts towards the magnitude of the number it's in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeafWeight {
+    /// The turns from the root down to it, `L` for left and `R` for right
+    pub path: String,
+    pub value: i64,
+    /// What the magnitude multiplies the value by: 3 for every left turn and 2 for every right one
+    pub weight: i64,
+}
+
+impl LeafWeight {
+    /// The part of the magnitude that comes from this regular number, or None if it's too large for
+    /// an i64
+    pub fn contribution(&self) -> Option<i64> {
+        self.value.checked_mul(self.weight)
+    }
+}
+
+impl Number {
+    /// The magnitude, or None if it's too large for an i64
+    pub fn magnitude(&self) -> Option<i64> {
+        match self {
+            Number::Regular(n) => Some(*n),
+            Number::Pair(lhs, rhs) => 3i64
+                .checked_mul(lhs.magnitude()?)?
+                .checked_add(2i64.checked_mul(rhs.magnitude()?)?),
+        }
+    }
+
+    /// Every regular number from left to right with its weight in the magnitude, so the
+    /// contributions add up to `magnitude()`. None if a number is nested so deep that its weight is
+    /// too large for an i64
+    pub fn leaf_weights(&self) -> Option<Vec<LeafWeight>> {
+        fn visit(
+            number: &Number,
+            path: &mut String,
+            weight: i64,
+            leaves: &mut Vec<LeafWeight>,
+        ) -> Option<()> {
+            match number {
+                Number::Regular(value) => leaves.push(LeafWeight {
+                    path: path.clone(),
+                    value: *value,
+                    weight,
+                }),
+                Number::Pair(lhs, rhs) => {
+                    for (side, turn, factor) in [(lhs, 'L', 3), (rhs, 'R', 2)] {
+                        path.push(turn);
+                        visit(side, path, weight.checked_mul(factor)?, leaves)?;
+                        path.pop();
+                    }
+                }
+            }
+            Some(())
+        }
+
+        let mut leaves = Vec::new();
+        visit(self, &mut String::new(), 1, &mut leaves)?;
+        Some(leaves)
+    }
+
+    /// The `k` regular numbers that make up the most of the magnitude, largest first. Ties go to the
+    /// leftmost. None if any contribution is too large for an i64
+    pub fn top_contributors(&self, k: usize) -> Option<Vec<LeafWeight>> {
+        let mut leaves = self
+            .leaf_weights()?
+            .into_iter()
+            .map(|leaf| Some((leaf.contribution()?, leaf)))
+            .collect::<Option<Vec<_>>>()?;
+        leaves.sort_by_key(|(contribution, _)| core::cmp::Reverse(*contribution));
+        Some(leaves.into_iter().take(k).map(|(_, leaf)| leaf).collect())
+    }
+
+    fn reduce(&mut self) -> Xform {
+        match self.explode(0) {
+            Xform::Identity => self.split(),
+            xform => xform,
+        }
+    }
+
+    fn split(&mut self) -> Xform {
+        if self.is_regular() {
+            let value = self.unwrap_regular();
+            if value > 9 {
+                *self = Number::Pair(
+                    Box::new(Number::Regular(value / 2)),
+                    Box::new(Number::Regular(value - value / 2)),
+                );
+                return Xform::Split;
+            }
+        } else {
+            let (lhs, rhs) = self.unwrap_pair();
+            let mut xform = lhs.split();
+            if !xform.reduced() {
+                xform = rhs.split();
+            }
+            return xform;
+        }
+
+        Xform::Identity
+    }
+
+    fn explode(&mut self, depth: usize) -> Xform {
+        if self.is_regular() {
+            return Xform::Identity;
+        }
+
+        let (lhs, rhs) = self.unwrap_pair();
+        if lhs.is_regular() && rhs.is_regular() && depth >= 4 {
+            let xform = Xform::Explode(Some(lhs.unwrap_regular()), Some(rhs.unwrap_regular()));
+            *self = Number::Regular(0);
+            return xform;
+        }
+
+        let (lhs, rhs) = self.unwrap_pair();
+        let mut xform = lhs.explode(depth + 1);
+        if xform.reduced() {
+            if let Xform::Explode(a, Some(b)) = xform {
+                xform = match rhs.explode_rightward(b) {
+                    Some(()) => Xform::Explode(a, None),
+                    None => Xform::Overflow,
+                };
+            }
+        } else {
+            xform = rhs.explode(depth + 1);
+            if let Xform::Explode(Some(a), b) = xform {
+                xform = match lhs.explode_leftward(a) {
+                    Some(()) => Xform::Explode(None, b),
+                    None => Xform::Overflow,
+                };
+            }
+        }
+
+        xform
+    }
+
+    /// Add value to the leftmost regular number, or None if the sum doesn't fit
+    fn explode_rightward(&mut self, value: i64) -> Option<()> {
+        if self.is_regular() {
+            let sum = self.unwrap_regular().checked_add(value)?;
+            *self = Number::Regular(sum);
+            Some(())
+        } else {
+            let (lhs, _) = self.unwrap_pair();
+            lhs.explode_rightward(value)
+        }
+    }
+
+    /// Add value to the rightmost regular number, or None if the sum doesn't fit
+    fn explode_leftward(&mut self, value: i64) -> Option<()> {
+        if self.is_regular() {
+            let sum = self.unwrap_regular().checked_add(value)?;
+            *self = Number::Regular(sum);
+            Some(())
+        } else {
+            let (_, rhs) = self.unwrap_pair();
+            rhs.explode_leftward(value)
+        }
+    }
+
+    fn is_regular(&self) -> bool {
+        matches!(self, Number::Regular(_))
+    }
+
+    fn unwrap_pair(&mut self) -> (&mut Number, &mut Number) {
+        match self {
+            Number::Pair(a, b) => (a, b),
+            _ => panic!("Not a pair"),
+        }
+    }
+
+    fn unwrap_regular(&self) -> i64 {
+        match self {
+            Number::Regular(n) => *n,
+            _ => panic!("Not a regular"),
+        }
+    }
+}
+
+impl core::fmt::Display for Number {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match &self {
+            Number::Pair(lhs, rhs) => {
+                f.write_char('[')?;
+                lhs.fmt(f)?;
+                f.write_char(',')?;
+                rhs.fmt(f)?;
+                f.write_char(']')
+            }
+            Number::Regular(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// A snailfish number that doesn't parse, with the column (from 1) of the first character that
+/// doesn't fit, or one past the end if the number is cut short. A pair nested deeper than
+/// `MAX_NESTING` doesn't fit
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseNumberError(pub usize);
+
+impl core::fmt::Display for ParseNumberError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "malformed snailfish number at column {}", self.0)
+    }
+}
+
+impl core::error::Error for ParseNumberError {}
+
+/// Deepest a number can nest pairs and still parse. A reduced number never nests more than four
+/// deep, so this only turns away input that would otherwise run the parser out of stack
+pub const MAX_NESTING: usize = 64;
+
+pub fn parse_pairs(pairs_str: &str) -> Result<Number, ParseNumberError> {
+    let chars = pairs_str.trim().chars().collect::<Vec<_>>();
+    let mut pos = 0;
+    let number = parse_number(&chars, &mut pos, 0)?;
+    if pos < chars.len() {
+        return Err(ParseNumberError(pos + 1));
+    }
+
+    Ok(number)
+}
+
+fn expect(chars: &[char], pos: &mut usize, expected: char) -> Result<(), ParseNumberError> {
+    if chars.get(*pos) == Some(&expected) {
+        *pos += 1;
+        Ok(())
+    } else {
+        Err(ParseNumberError(*pos + 1))
+    }
+}
+
+fn parse_number(chars: &[char], pos: &mut usize, depth: usize) -> Result<Number, ParseNumberError> {
+    match chars.get(*pos) {
+        Some('[') if depth == MAX_NESTING => Err(ParseNumberError(*pos + 1)),
+        Some('[') => {
+            expect(chars, pos, '[')?;
+            let lhs = parse_number(chars, pos, depth + 1)?;
+            expect(chars, pos, ',')?;
+            let rhs = parse_number(chars, pos, depth + 1)?;
+            expect(chars, pos, ']')?;
+            Ok(Number::Pair(Box::new(lhs), Box::new(rhs)))
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let start = *pos;
+            while chars.get(*pos).is_some_and(char::is_ascii_digit) {
+                *pos += 1;
+            }
+            // A regular number is written without leading zeros
+            if *c == '0' && *pos - start > 1 {
+                return Err(ParseNumberError(start + 1));
+            }
+
+            chars[start..*pos]
+                .iter()
+                .collect::<String>()
+                .parse()
+                .map(Number::Regular)
+                .map_err(|_| ParseNumberError(start + 1))
+        }
+        _ => Err(ParseNumberError(*pos + 1)),
+    }
+}
+
+/// Add two numbers and reduce the sum, or None if reducing it makes a regular number too large for
+/// an i64
+pub fn add_numbers(lhs: Number, rhs: Number) -> Option<Number> {
+    let mut result = Number::Pair(Box::new(lhs), Box::new(rhs));
+    loop {
+        match result.reduce() {
+            Xform::Identity => return Some(result),
+            Xform::Overflow => return None,
+            Xform::Explode(..) | Xform::Split => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Node {
+    Regular(i64),
+    Pair(u32, u32),
+}
+
+/// A snailfish number stored as a flat arena of nodes. Children are indices into the arena, so
+/// reducing a sum reuses the slots freed by explosions instead of going back to the allocator
+#[derive(Clone)]
+pub struct ArenaNumber {
+    nodes: Vec<Node>,
+    free: Vec<u32>,
+    root: u32,
+}
+
+impl ArenaNumber {
+    /// The magnitude, or None if it's too large for an i64
+    pub fn magnitude(&self) -> Option<i64> {
+        self.magnitude_of(self.root)
+    }
+
+    fn magnitude_of(&self, index: u32) -> Option<i64> {
+        match self.nodes[index as usize] {
+            Node::Regular(n) => Some(n),
+            Node::Pair(lhs, rhs) => 3i64
+                .checked_mul(self.magnitude_of(lhs)?)?
+                .checked_add(2i64.checked_mul(self.magnitude_of(rhs)?)?),
+        }
+    }
+
+    fn alloc(&mut self, node: Node) -> u32 {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index as usize] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                (self.nodes.len() - 1) as u32
+            }
+        }
+    }
+
+    fn regular_mut(&mut self, index: u32) -> &mut i64 {
+        match &mut self.nodes[index as usize] {
+            Node::Regular(n) => n,
+            Node::Pair(..) => panic!("Not a regular"),
+        }
+    }
+
+    /// Explodes the leftmost pair nested four deep, walking the tree in order so the regular
+    /// numbers on either side are the ones visited just before and just after it. None if that
+    /// makes one of them too large for an i64
+    fn explode(&mut self) -> Option<bool> {
+        let mut stack = vec![(self.root, 0)];
+        let mut previous = None;
+        while let Some((index, depth)) = stack.pop() {
+            match self.nodes[index as usize] {
+                Node::Regular(_) => previous = Some(index),
+                Node::Pair(lhs, rhs) => {
+                    match (self.nodes[lhs as usize], self.nodes[rhs as usize]) {
+                        (Node::Regular(a), Node::Regular(b)) if depth >= 4 => {
+                            if let Some(previous) = previous {
+                                let regular = self.regular_mut(previous);
+                                *regular = regular.checked_add(a)?;
+                            }
+
+                            if let Some(&(mut next, _)) = stack.last() {
+                                while let Node::Pair(lhs, _) = self.nodes[next as usize] {
+                                    next = lhs;
+                                }
+                                let regular = self.regular_mut(next);
+                                *regular = regular.checked_add(b)?;
+                            }
+
+                            self.nodes[index as usize] = Node::Regular(0);
+                            self.free.extend([lhs, rhs]);
+                            return Some(true);
+                        }
+                        _ => {
+                            stack.push((rhs, depth + 1));
+                            stack.push((lhs, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some(false)
+    }
+
+    fn split(&mut self) -> bool {
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            match self.nodes[index as usize] {
+                Node::Regular(value) if value > 9 => {
+                    let lhs = self.alloc(Node::Regular(value / 2));
+                    let rhs = self.alloc(Node::Regular(value - value / 2));
+                    self.nodes[index as usize] = Node::Pair(lhs, rhs);
+                    return true;
+                }
+                Node::Regular(_) => {}
+                Node::Pair(lhs, rhs) => {
+                    stack.push(rhs);
+                    stack.push(lhs);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// The regular numbers added up, stopping at i64::MAX. Explosions only ever move values or
+    /// drop them off either end, and splits keep them, so reducing a sum can't make this any larger
+    pub fn total(&self) -> i64 {
+        let mut stack = vec![self.root];
+        let mut total = 0i64;
+        while let Some(index) = stack.pop() {
+            match self.nodes[index as usize] {
+                Node::Regular(n) => total = total.saturating_add(n),
+                Node::Pair(lhs, rhs) => stack.extend([lhs, rhs]),
+            }
+        }
+        total
+    }
+
+    fn fmt_node(&self, index: u32, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.nodes[index as usize] {
+            Node::Pair(lhs, rhs) => {
+                f.write_char('[')?;
+                self.fmt_node(lhs, f)?;
+                f.write_char(',')?;
+                self.fmt_node(rhs, f)?;
+                f.write_char(']')
+            }
+            Node::Regular(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl ArenaNumber {
+    /// Adds `rhs` to this number and reduces the sum, or None if reducing it makes a regular number
+    /// too large for an i64
+    pub fn checked_add(mut self, rhs: &ArenaNumber) -> Option<ArenaNumber> {
+        let offset = self.nodes.len() as u32;
+        self.nodes.extend(rhs.nodes.iter().map(|node| match *node {
+            Node::Pair(lhs, rhs) => Node::Pair(lhs + offset, rhs + offset),
+            regular => regular,
+        }));
+        self.free
+            .extend(rhs.free.iter().map(|index| index + offset));
+
+        let root = Node::Pair(self.root, rhs.root + offset);
+        self.root = self.alloc(root);
+        while self.explode()? || self.split() {}
+        Some(self)
+    }
+}
+
+impl core::ops::Add<&ArenaNumber> for ArenaNumber {
+    type Output = ArenaNumber;
+
+    /// Adds `rhs` to this number and reduces the sum. Panics if a regular number overflows, which
+    /// `checked_add` returns None for instead
+    fn add(self, rhs: &ArenaNumber) -> ArenaNumber {
+        self.checked_add(rhs)
+            .expect("a regular number overflowed reducing the sum")
+    }
+}
+
+impl From<&Number> for ArenaNumber {
+    fn from(number: &Number) -> ArenaNumber {
+        fn push(nodes: &mut Vec<Node>, number: &Number) -> u32 {
+            let node = match number {
+                Number::Regular(n) => Node::Regular(*n),
+                Number::Pair(lhs, rhs) => Node::Pair(push(nodes, lhs), push(nodes, rhs)),
+            };
+            nodes.push(node);
+            (nodes.len() - 1) as u32
+        }
+
+        let mut nodes = Vec::new();
+        let root = push(&mut nodes, number);
+        ArenaNumber {
+            nodes,
+            free: Vec::new(),
+            root,
+        }
+    }
+}
+
+impl core::str::FromStr for ArenaNumber {
+    type Err = ParseNumberError;
+
+    fn from_str(s: &str) -> Result<ArenaNumber, ParseNumberError> {
+        parse_pairs(s).map(|number| ArenaNumber::from(&number))
+    }
+}
+
+impl core::fmt::Display for ArenaNumber {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.fmt_node(self.root, f)
+    }
+}
+
+/// Largest magnitude a reduced number can have when its regular numbers add up to at most `total`.
+/// Turning a regular number less than four deep into a pair of it and 0 can only raise the
+/// magnitude, so the most is had with a full tree four deep holding 9s in its heaviest places
+pub fn magnitude_bound(total: i64) -> i64 {
+    // Each of the 16 places is weighted 3 for every left turn and 2 for every right turn
+    const WEIGHTS: [i64; 16] = [
+        81, 54, 54, 54, 54, 36, 36, 36, 36, 36, 36, 24, 24, 24, 24, 16,
+    ];
+    let mut left = total;
+    let mut bound = 0;
+    for weight in WEIGHTS {
+        let value = left.clamp(0, 9);
+        bound += weight * value;
+        left -= value;
+    }
+    bound
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn split() {
+        let mut number = parse_pairs("[[[[0,7],4],[15,[0,13]]],[1,1]]").unwrap();
+        assert!(matches!(number.split(), Xform::Split));
+        assert_eq!(number.to_string(), "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]");
+
+        let mut number = parse_pairs("[0,[13,1]]").unwrap();
+        assert!(matches!(number.split(), Xform::Split));
+        assert_eq!(number.to_string(), "[0,[[6,7],1]]");
+    }
+}
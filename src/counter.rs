@@ -0,0 +1,216 @@
+//! Tallies of how many times each key has been seen
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Numbers that can be tallied without silently wrapping
+pub trait Count: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// `None` if the sum doesn't fit
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_count {
+    ($($t:ty),*) => {
+        $(impl Count for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *other)
+            }
+        })*
+    };
+}
+
+impl_count!(i64, u8, u32, u64, u128, usize);
+
+#[cfg(feature = "bigint")]
+impl Count for num_bigint::BigUint {
+    fn zero() -> Self {
+        0u32.into()
+    }
+
+    fn one() -> Self {
+        1u32.into()
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// A count got too big for its type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("count overflowed")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<K: Hash + Eq, N: Count = u64> {
+    counts: HashMap<K, N>,
+}
+
+impl<K: Hash + Eq, N: Count> Default for Counter<K, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, N: Count> Counter<K, N> {
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Add `n` to the count for `key`. If it overflows the count is left as it was
+    pub fn add(&mut self, key: K, n: N) -> Result<(), Overflow> {
+        match self.counts.get_mut(&key) {
+            Some(count) => *count = count.checked_add(&n).ok_or(Overflow)?,
+            None => {
+                self.counts.insert(key, n);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn increment(&mut self, key: K) -> Result<(), Overflow> {
+        self.add(key, N::one())
+    }
+
+    /// Add every count in `other` to this one. If any of them overflow, nothing is added
+    pub fn merge(&mut self, other: Counter<K, N>) -> Result<(), Overflow> {
+        let mut sums = Vec::with_capacity(other.counts.len());
+        for (key, n) in other.counts.into_iter() {
+            let sum = match self.counts.get(&key) {
+                Some(count) => count.checked_add(&n).ok_or(Overflow)?,
+                None => n,
+            };
+            sums.push((key, sum));
+        }
+
+        self.counts.extend(sums);
+        Ok(())
+    }
+
+    /// Count for `key`, which is zero if it was never added
+    pub fn get(&self, key: &K) -> N {
+        self.counts.get(key).cloned().unwrap_or_else(N::zero)
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &N)> {
+        self.counts.iter()
+    }
+
+    /// Sum of all the counts
+    pub fn total(&self) -> Result<N, Overflow> {
+        self.counts
+            .values()
+            .try_fold(N::zero(), |total, n| total.checked_add(n))
+            .ok_or(Overflow)
+    }
+}
+
+/// A key and its count
+pub type Entry<'a, K, N> = (&'a K, &'a N);
+
+impl<K: Hash + Eq + Ord, N: Count + Ord> Counter<K, N> {
+    /// Every key with its count, highest count first. Equal counts are in key order
+    pub fn most_common(&self) -> Vec<Entry<'_, K, N>> {
+        let mut entries = self.counts.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        entries
+    }
+
+    /// The entries with the lowest and highest counts, picking the smallest key on ties
+    pub fn min_max(&self) -> Option<(Entry<'_, K, N>, Entry<'_, K, N>)> {
+        let min = self
+            .counts
+            .iter()
+            .min_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)))?;
+        let max = self
+            .counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))?;
+        Some((min, max))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn counter(entries: &[(char, u8)]) -> Counter<char, u8> {
+        let mut counter = Counter::new();
+        for &(key, n) in entries {
+            counter.add(key, n).unwrap();
+        }
+        counter
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = counter(&[('a', 1), ('b', 2), ('a', 3)]);
+        a.merge(counter(&[('b', 10), ('c', 5)])).unwrap();
+        assert_eq!(a, counter(&[('a', 4), ('b', 12), ('c', 5)]));
+
+        // Merging is the same as adding each entry
+        let mut b = counter(&[('c', 5)]);
+        b.merge(counter(&[('a', 4), ('b', 12)])).unwrap();
+        assert_eq!(a, b);
+
+        // Empty counters change nothing either way round
+        a.merge(Counter::new()).unwrap();
+        assert_eq!(a, b);
+        let mut empty = Counter::new();
+        empty.merge(b.clone()).unwrap();
+        assert_eq!(empty, b);
+
+        // An overflow in one key leaves every key as it was
+        let before = a.clone();
+        assert_eq!(a.merge(counter(&[('a', 1), ('b', 250)])), Err(Overflow));
+        assert_eq!(a, before);
+        assert_eq!(a.get(&'z'), 0);
+    }
+
+    #[test]
+    fn overflow() {
+        let mut a = counter(&[('a', 255)]);
+        assert_eq!(a.increment('a'), Err(Overflow));
+        assert_eq!(a.get(&'a'), 255);
+        a.increment('b').unwrap();
+        assert_eq!(a.total(), Err(Overflow));
+    }
+
+    #[test]
+    fn ordering() {
+        let a = counter(&[('d', 1), ('b', 3), ('c', 3), ('a', 1), ('e', 2)]);
+        assert_eq!(
+            a.most_common(),
+            [(&'b', &3), (&'c', &3), (&'e', &2), (&'a', &1), (&'d', &1)]
+        );
+        assert_eq!(a.min_max(), Some(((&'a', &1), (&'b', &3))));
+        assert_eq!(counter(&[]).min_max(), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Grids of cells stored row by row
+
+use crate::error::ParseError;
+use std::collections::VecDeque;
+
+/// Rows of one character per cell, turned into cells by `cell`, which names what a cell is by
+/// returning an error message for characters it doesn't recognize. Blank lines are skipped, and
+/// every row must be as long as the first
+pub fn parse_rows<T>(
+    input: &str,
+    cell: impl Fn(char) -> Result<T, String>,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut grid: Vec<Vec<T>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let row = line
+            .chars()
+            .map(|c| cell(c).map_err(|message| ParseError::at_line(i + 1, message)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(first) = grid.first().filter(|first| first.len() != row.len()) {
+            return Err(ParseError::at_line(
+                i + 1,
+                format!(
+                    "expected {} cells, found {} in {:?}",
+                    first.len(),
+                    row.len(),
+                    line
+                ),
+            ));
+        }
+        grid.push(row);
+    }
+    Ok(grid)
+}
+
+/// One bit for each cell of a grid, for marking cells with an eighth of the memory of a `bool` each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    bits: Vec<u64>,
+    cols: usize,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        BitGrid {
+            bits: vec![0; (rows * cols).div_ceil(64)],
+            cols,
+        }
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        let i = row * self.cols + col;
+        (self.bits[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    /// Mark the cell, returning false if it was already marked
+    pub fn insert(&mut self, (row, col): (usize, usize)) -> bool {
+        let i = row * self.cols + col;
+        let mask = 1 << (i % 64);
+        let word = &mut self.bits[i / 64];
+        let added = *word & mask == 0;
+        *word |= mask;
+        added
+    }
+}
+
+/// The last few rows read from a grid that is read one row at a time, so a grid too big to hold in
+/// memory can still be walked with each cell's neighbors above and below it. Each row is only
+/// handed out once the row after it has been read
+#[derive(Debug, Clone)]
+pub struct RowWindow<T> {
+    rows: VecDeque<Vec<T>>,
+    read: usize,
+}
+
+/// A row of the grid with the rows either side of it, if there are any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rows<'a, T> {
+    /// Row number of `current`, counting from 0
+    pub index: usize,
+    pub above: Option<&'a [T]>,
+    pub current: &'a [T],
+    pub below: Option<&'a [T]>,
+}
+
+impl<'a, T> Rows<'a, T> {
+    /// The cells left, right, above and below `col` in `current`. Cells past the end of a shorter
+    /// row above or below are skipped, the same as cells off the edge of the grid
+    pub fn neighbors(&self, col: usize) -> impl Iterator<Item = &'a T> {
+        let left = col.checked_sub(1).and_then(|col| self.current.get(col));
+        let right = self.current.get(col + 1);
+        let above = self.above.and_then(|row| row.get(col));
+        let below = self.below.and_then(|row| row.get(col));
+        [left, right, above, below].into_iter().flatten()
+    }
+}
+
+impl<T> Default for RowWindow<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RowWindow<T> {
+    pub fn new() -> Self {
+        RowWindow {
+            rows: VecDeque::with_capacity(3),
+            read: 0,
+        }
+    }
+
+    /// Read the next row, returning the row before it now that its neighbors are known
+    pub fn push(&mut self, row: Vec<T>) -> Option<Rows<'_, T>> {
+        if self.rows.len() == 3 {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(row);
+        self.read += 1;
+
+        match self.rows.len() {
+            1 => None,
+            2 => Some(self.rows_at(None, 0, Some(1))),
+            _ => Some(self.rows_at(Some(0), 1, Some(2))),
+        }
+    }
+
+    /// The last row, once there are no more to read
+    pub fn finish(&self) -> Option<Rows<'_, T>> {
+        match self.rows.len() {
+            0 => None,
+            1 => Some(self.rows_at(None, 0, None)),
+            n => Some(self.rows_at(Some(n - 2), n - 1, None)),
+        }
+    }
+
+    fn rows_at(&self, above: Option<usize>, current: usize, below: Option<usize>) -> Rows<'_, T> {
+        let last = self.rows.len() - 1;
+        Rows {
+            index: self.read - 1 - (last - current),
+            above: above.map(|i| self.rows[i].as_slice()),
+            current: &self.rows[current],
+            below: below.map(|i| self.rows[i].as_slice()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window() {
+        let mut window = RowWindow::new();
+        assert_eq!(window.finish(), None);
+        assert_eq!(window.push(vec![1, 2, 3]), None);
+
+        let rows = window.push(vec![4, 5, 6]).unwrap();
+        assert_eq!(
+            (rows.index, rows.above, rows.current),
+            (0, None, &[1, 2, 3][..])
+        );
+        assert_eq!(rows.neighbors(1).collect::<Vec<_>>(), [&1, &3, &5]);
+
+        let rows = window.push(vec![7, 8]).unwrap();
+        assert_eq!(rows.index, 1);
+        assert_eq!(rows.neighbors(1).collect::<Vec<_>>(), [&4, &6, &2, &8]);
+        assert_eq!(rows.neighbors(2).collect::<Vec<_>>(), [&5, &3]);
+
+        let rows = window.finish().unwrap();
+        assert_eq!(
+            (rows.index, rows.current, rows.below),
+            (2, &[7, 8][..], None)
+        );
+        assert_eq!(rows.neighbors(0).collect::<Vec<_>>(), [&8, &4]);
+
+        let mut window = RowWindow::new();
+        window.push(vec!['a']);
+        let rows = window.finish().unwrap();
+        assert_eq!(rows.index, 0);
+        assert_eq!(rows.neighbors(0).count(), 0);
+    }
+
+    #[test]
+    fn bits() {
+        let mut grid = BitGrid::new(3, 50);
+        assert!(!grid.contains((1, 20)));
+        assert!(grid.insert((1, 20)));
+        assert!(!grid.insert((1, 20)));
+        assert!(grid.contains((1, 20)));
+        assert!(!grid.contains((1, 21)));
+        assert!(!grid.contains((0, 20)));
+
+        // The last cell is in the last word
+        assert!(grid.insert((2, 49)));
+        assert!(grid.contains((2, 49)));
+        assert_eq!(grid.bits.len(), 3);
+    }
+
+    #[test]
+    fn rows() {
+        let digit = |c: char| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("invalid digit {:?}", c))
+        };
+        assert_eq!(
+            parse_rows("12\n\n 34\n", digit),
+            Ok(vec![vec![1, 2], vec![3, 4]])
+        );
+        assert_eq!(parse_rows("", digit), Ok(Vec::new()));
+
+        let err = parse_rows("12\n3", digit).unwrap_err();
+        assert_eq!(err.line(), 2);
+        let err = parse_rows("12\n3x", digit).unwrap_err();
+        assert_eq!((err.line(), err.message()), (2, "invalid digit 'x'"));
+    }
+}
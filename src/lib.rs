@@ -1,32 +1,43 @@
-#![feature(drain_filter)]
-#![feature(iter_intersperse)]
-#[macro_use]
-extern crate lazy_static;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+#[cfg(feature = "std")]
 extern crate aoc_runner;
 
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate aoc_runner_derive;
 
-pub mod day1;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day2;
-pub mod day21;
-pub mod day22;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
+pub mod algo;
+#[cfg(feature = "std")]
+pub mod counter;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod grid;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "std")]
+mod solve;
+#[doc(hidden)]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod y2021;
+#[cfg(feature = "std")]
+pub mod y2022;
+
+#[cfg(feature = "std")]
+pub use solve::{
+    input_stats, solve, solve_timed, solve_with_progress, InputStats, PartStats, SolveError,
+    Timings, DAYS,
+};
+
+#[cfg(feature = "viz")]
+pub mod viz;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+#[cfg(feature = "std")]
 aoc_runner_derive::aoc_lib! { year = 2021 }
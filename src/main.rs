@@ -0,0 +1,392 @@
+//! Extra tooling on top of the solutions. The solutions themselves are run with `cargo aoc`, which
+//! caches puzzle inputs under `input/2021/`; this binary reads the same files.
+
+use aoc21::progress::{Amount, Progress, ProgressSink};
+use aoc21::{InputStats, SolveError, Timings};
+use std::path::PathBuf;
+use std::process::exit;
+use std::time::Duration;
+
+const USAGE: &str = "usage: aoc21 [--progress] <day> [input]
+       aoc21 --all [--parallel]
+       aoc21 stats [--json]
+       aoc21 --viz <dir> [--ppm | --gif] <day> [input]";
+
+fn input_path(year: u32, day: u32) -> PathBuf {
+    PathBuf::from(format!("input/{}/day{}.txt", year, day))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(2);
+}
+
+#[derive(Clone, Copy)]
+enum Output {
+    Ascii,
+    Ppm,
+    Gif,
+}
+
+#[cfg(feature = "viz")]
+fn visualize(day: u32, input: &str, dir: &str, output: Output) {
+    use aoc21::viz::{self, Format};
+
+    let frames = viz::render_day(day, input).unwrap_or_else(|| fail("No renderer for this day"));
+    let name = format!("day{}", day);
+    let written = match output {
+        Output::Ascii => viz::write_frames(dir.as_ref(), &name, &frames, Format::Ascii),
+        Output::Ppm => viz::write_frames(dir.as_ref(), &name, &frames, Format::Ppm),
+        Output::Gif => std::fs::create_dir_all(dir).and_then(|_| {
+            let path = std::path::Path::new(dir).join(format!("{}.gif", name));
+            viz::render_gif(&frames, &path)
+        }),
+    };
+
+    written.unwrap_or_else(|err| fail(&format!("Failed to write frames: {}", err)));
+    println!("Wrote {} frames to {}", frames.len(), dir);
+}
+
+#[cfg(not(feature = "viz"))]
+fn visualize(_day: u32, _input: &str, _dir: &str, _output: Output) {
+    fail("Built without the `viz` feature");
+}
+
+/// Redraws one line on stderr for each phase a solver reports
+struct ProgressBar {
+    phase: Option<&'static str>,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 40;
+
+    /// Leave the last line drawn in place
+    fn finish(&mut self) {
+        if self.phase.take().is_some() {
+            eprintln!();
+        }
+    }
+}
+
+impl ProgressSink for ProgressBar {
+    fn report(&mut self, progress: Progress) {
+        if self.phase != Some(progress.phase) {
+            self.finish();
+            self.phase = Some(progress.phase);
+        }
+
+        match progress.amount {
+            Amount::Percent(percent) => {
+                let filled = (percent / 100. * Self::WIDTH as f64) as usize;
+                let filled = filled.min(Self::WIDTH);
+                eprint!(
+                    "\rday {} {} [{}{}] {:3.0}%",
+                    progress.day,
+                    progress.phase,
+                    "#".repeat(filled),
+                    " ".repeat(Self::WIDTH - filled),
+                    percent
+                );
+            }
+            Amount::Items(items) => {
+                eprint!("\rday {} {}: {}", progress.day, progress.phase, items)
+            }
+        }
+    }
+}
+
+/// Run both parts of a day, drawing a progress bar for the solvers that report progress
+fn run(day: u32, input: &str, progress: bool) {
+    let mut bar = ProgressBar { phase: None };
+    for part in 1..=2 {
+        let answer = if progress {
+            aoc21::solve_with_progress(2021, day, part, input, &mut bar)
+        } else {
+            aoc21::solve(2021, day, part, input)
+        };
+        bar.finish();
+
+        match answer {
+            Ok(answer) => println!("Part {}: {}", part, answer),
+            Err(SolveError::NotSolved) if part > 1 => {}
+            Err(err) => fail(&format!("Day {} part {}: {}", day, part, err)),
+        }
+    }
+}
+
+/// One part solved by `--all`
+struct Row {
+    year: u32,
+    day: u32,
+    part: u32,
+    result: Result<(String, Timings), SolveError>,
+}
+
+/// Solve every part of one day from its cached input, or nothing if the input isn't there
+fn solve_day(year: u32, day: u32) -> Vec<Row> {
+    let input = match std::fs::read_to_string(input_path(year, day)) {
+        Ok(input) => input,
+        Err(_) => return Vec::new(),
+    };
+
+    (1..=2)
+        .map(|part| Row {
+            year,
+            day,
+            part,
+            result: aoc21::solve_timed(year, day, part, &input),
+        })
+        .filter(|row| row.result != Err(SolveError::NotSolved))
+        .collect()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3?}", duration)
+}
+
+/// Answers that span lines are summarized, so each part fits on one row
+fn summarize(answer: &str) -> String {
+    let lines = answer.trim().lines().count();
+    if lines > 1 {
+        format!("({} lines)", lines)
+    } else {
+        answer.trim().to_string()
+    }
+}
+
+/// Every day solved at once, so the timings include time spent waiting on the other days
+#[cfg(feature = "parallel")]
+fn solve_all_parallel() -> Vec<Row> {
+    use rayon::prelude::*;
+    aoc21::DAYS
+        .par_iter()
+        .flat_map_iter(|&(year, day)| solve_day(year, day))
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn solve_all_parallel() -> Vec<Row> {
+    fail("Built without the `parallel` feature");
+}
+
+/// Run every day with an input under `input/` and print how long each part took. Days are run one
+/// after another unless `parallel`
+fn run_all(parallel: bool) {
+    let rows = if parallel {
+        solve_all_parallel()
+    } else {
+        aoc21::DAYS
+            .iter()
+            .flat_map(|&(year, day)| solve_day(year, day))
+            .collect::<Vec<_>>()
+    };
+    if rows.is_empty() {
+        fail("No puzzle inputs found under input/");
+    }
+
+    // The three days that took longest over both parts
+    let mut day_totals = Vec::<((u32, u32), Duration)>::new();
+    for row in rows.iter() {
+        let total = row
+            .result
+            .as_ref()
+            .map_or(Duration::ZERO, |(_, t)| t.total());
+        match day_totals
+            .iter_mut()
+            .find(|(day, _)| *day == (row.year, row.day))
+        {
+            Some((_, day_total)) => *day_total += total,
+            None => day_totals.push(((row.year, row.day), total)),
+        }
+    }
+    day_totals.sort_by_key(|&(_, total)| std::cmp::Reverse(total));
+    let slowest = day_totals
+        .iter()
+        .take(3)
+        .map(|&(day, _)| day)
+        .collect::<Vec<_>>();
+
+    println!(
+        "  {:>4} {:>3} {:>4} {:>12} {:>12}  answer",
+        "year", "day", "part", "generator", "runner"
+    );
+    let mut totals = Timings::default();
+    for row in rows.iter() {
+        let marker = if slowest.contains(&(row.year, row.day)) {
+            '*'
+        } else {
+            ' '
+        };
+        let (generator, runner, answer) = match &row.result {
+            Ok((answer, timings)) => {
+                totals.generator += timings.generator;
+                totals.runner += timings.runner;
+                (
+                    format_duration(timings.generator),
+                    format_duration(timings.runner),
+                    summarize(answer),
+                )
+            }
+            Err(err) => ("-".to_string(), "-".to_string(), format!("error: {}", err)),
+        };
+        println!(
+            "{} {:>4} {:>3} {:>4} {:>12} {:>12}  {}",
+            marker, row.year, row.day, row.part, generator, runner, answer
+        );
+    }
+    println!(
+        "  {:<13} {:>12} {:>12}  {} total",
+        "total",
+        format_duration(totals.generator),
+        format_duration(totals.runner),
+        format_duration(totals.total())
+    );
+    println!("* one of the three slowest days");
+}
+
+/// One object per input, with durations in nanoseconds and `null` for a parse that never finished
+#[cfg(feature = "serde")]
+fn print_stats_json(stats: &[(u32, u32, InputStats)]) {
+    use serde_json::{json, Value};
+
+    let objects = stats
+        .iter()
+        .map(|(year, day, stats)| {
+            let parts = stats
+                .parts
+                .iter()
+                .map(|part| match &part.result {
+                    Ok((answer, runner)) => json!({
+                        "part": part.part,
+                        "answer": answer.trim(),
+                        "runner_ns": runner.as_nanos() as u64,
+                    }),
+                    Err(err) => json!({ "part": part.part, "error": err.to_string() }),
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "year": year,
+                "day": day,
+                "lines": stats.lines,
+                "bytes": stats.bytes,
+                "parse_ns": stats.parse.map(|parse| parse.as_nanos() as u64),
+                "parts": parts,
+            })
+        })
+        .collect::<Vec<Value>>();
+    let json = serde_json::to_string_pretty(&objects).expect("stats are always valid JSON");
+    println!("{}", json);
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_stats_json(_stats: &[(u32, u32, InputStats)]) {
+    fail("Built without the `serde` feature");
+}
+
+/// Report the size of every cached input under `input/` and how long each day takes on it, as a
+/// table or as JSON
+fn run_stats(json: bool) {
+    let stats = aoc21::DAYS
+        .iter()
+        .filter_map(|&(year, day)| {
+            let input = std::fs::read_to_string(input_path(year, day)).ok()?;
+            Some((year, day, aoc21::input_stats(year, day, &input)))
+        })
+        .collect::<Vec<_>>();
+    if stats.is_empty() {
+        fail("No puzzle inputs found under input/");
+    }
+
+    if json {
+        print_stats_json(&stats);
+        return;
+    }
+
+    println!(
+        "{:>4} {:>3} {:>6} {:>8} {:>12} {:>12} {:>12}  answers",
+        "year", "day", "lines", "bytes", "parse", "part 1", "part 2"
+    );
+    for (year, day, stats) in stats.iter() {
+        let mut runners = ["-".to_string(), "-".to_string()];
+        let mut answers = Vec::new();
+        for part in stats.parts.iter() {
+            match &part.result {
+                Ok((answer, runner)) => {
+                    runners[part.part as usize - 1] = format_duration(*runner);
+                    answers.push(summarize(answer));
+                }
+                Err(err) => answers.push(format!("error: {}", err)),
+            }
+        }
+        // A rejected input fails every part the same way
+        answers.dedup();
+        println!(
+            "{:>4} {:>3} {:>6} {:>8} {:>12} {:>12} {:>12}  {}",
+            year,
+            day,
+            stats.lines,
+            stats.bytes,
+            stats.parse.map_or("-".to_string(), format_duration),
+            runners[0],
+            runners[1],
+            answers.join(", ")
+        );
+    }
+}
+
+fn main() {
+    let mut viz_dir = None;
+    let mut progress = false;
+    let mut all = false;
+    let mut parallel = false;
+    let mut json = false;
+    let mut output = Output::Ascii;
+    let mut positional = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--viz" => viz_dir = Some(args.next().unwrap_or_else(|| fail(USAGE))),
+            "--ppm" => output = Output::Ppm,
+            "--gif" => output = Output::Gif,
+            "--progress" => progress = true,
+            "--all" => all = true,
+            "--parallel" => parallel = true,
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let stats = !all && positional.first().map(String::as_str) == Some("stats");
+    if json && !stats {
+        fail("--json only goes with stats");
+    }
+
+    if all {
+        run_all(parallel);
+        return;
+    }
+    if stats {
+        run_stats(json);
+        return;
+    }
+
+    let day = positional
+        .first()
+        .and_then(|day| day.parse::<u32>().ok())
+        .unwrap_or_else(|| fail(USAGE));
+    let path = positional
+        .get(1)
+        .map_or_else(|| input_path(2021, day), PathBuf::from);
+    let input = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| fail(&format!("Failed to read {}: {}", path.display(), err)));
+
+    match viz_dir {
+        Some(dir) => visualize(day, &input, &dir, output),
+        None => run(day, &input, progress),
+    }
+}
@@ -0,0 +1,148 @@
+//! Progress reports from the solvers that take long enough to be worth watching
+
+/// How far a solver has got through one phase of its work
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Amount {
+    /// Share of the phase done, from 0 to 100
+    Percent(f64),
+    /// Items handled so far, for work whose total isn't known up front
+    Items(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub day: u32,
+    pub phase: &'static str,
+    pub amount: Amount,
+}
+
+/// Somewhere to send progress reports, such as a progress bar
+pub trait ProgressSink {
+    fn report(&mut self, progress: Progress);
+}
+
+/// Sink that drops every report
+pub struct Silent;
+
+impl ProgressSink for Silent {
+    fn report(&mut self, _progress: Progress) {}
+}
+
+/// Item counts are only reported once every this many items, so tight loops don't spend their
+/// time in the sink
+const ITEM_STRIDE: u64 = 1 << 12;
+
+/// Threaded through a solver's main loop to report into a sink on behalf of one day
+pub struct Context<'a> {
+    day: u32,
+    sink: &'a mut dyn ProgressSink,
+    phase: &'static str,
+    items: u64,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(day: u32, sink: &'a mut dyn ProgressSink) -> Self {
+        Context {
+            day,
+            sink,
+            phase: "",
+            items: 0,
+        }
+    }
+
+    fn report(&mut self, phase: &'static str, amount: Amount) {
+        self.sink.report(Progress {
+            day: self.day,
+            phase,
+            amount,
+        });
+    }
+
+    /// `done` out of `total` steps of `phase` are finished
+    pub fn percent(&mut self, phase: &'static str, done: usize, total: usize) {
+        let percent = if total == 0 {
+            100.
+        } else {
+            100. * done as f64 / total as f64
+        };
+        self.report(phase, Amount::Percent(percent));
+    }
+
+    /// One more item of `phase` is handled. The count starts again whenever the phase changes
+    pub fn tick(&mut self, phase: &'static str) {
+        if phase != self.phase {
+            self.phase = phase;
+            self.items = 0;
+        }
+
+        self.items += 1;
+        if self.items.is_multiple_of(ITEM_STRIDE) {
+            self.report(phase, Amount::Items(self.items));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Vec<Progress>);
+
+    impl ProgressSink for Recorder {
+        fn report(&mut self, progress: Progress) {
+            self.0.push(progress);
+        }
+    }
+
+    #[test]
+    fn reports() {
+        let mut recorder = Recorder::default();
+        let mut ctx = Context::new(3, &mut recorder);
+        for _ in 0..2 * ITEM_STRIDE + 1 {
+            ctx.tick("counting");
+        }
+        for _ in 0..ITEM_STRIDE {
+            ctx.tick("recounting");
+        }
+        ctx.percent("halfway", 1, 2);
+        ctx.percent("nothing to do", 0, 0);
+
+        let reports = recorder
+            .0
+            .iter()
+            .map(|progress| (progress.day, progress.phase, progress.amount))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reports,
+            [
+                (3, "counting", Amount::Items(ITEM_STRIDE)),
+                (3, "counting", Amount::Items(2 * ITEM_STRIDE)),
+                (3, "recounting", Amount::Items(ITEM_STRIDE)),
+                (3, "halfway", Amount::Percent(50.)),
+                (3, "nothing to do", Amount::Percent(100.)),
+            ]
+        );
+    }
+
+    #[test]
+    fn solve_reports() {
+        let input = "on x=10..12,y=10..12,z=10..12\non x=11..13,y=11..13,z=11..13\n\
+                     off x=9..11,y=9..11,z=9..11\non x=10..10,y=10..10,z=10..10";
+        let mut recorder = Recorder::default();
+        assert_eq!(
+            crate::solve_with_progress(2021, 22, 2, input, &mut recorder),
+            crate::solve(2021, 22, 2, input)
+        );
+        assert_eq!(recorder.0.len(), 4);
+        assert_eq!(recorder.0[3].amount, Amount::Percent(100.));
+
+        // Days without progress reports still solve
+        let mut recorder = Recorder::default();
+        assert_eq!(
+            crate::solve_with_progress(2021, 1, 1, "1\n2\n1", &mut recorder),
+            Ok("1".to_string())
+        );
+        assert!(recorder.0.is_empty());
+    }
+}
@@ -0,0 +1,438 @@
+//! Runs a day's solution on some input without going through `cargo aoc`
+
+use crate::progress::{Context, ProgressSink};
+use std::time::{Duration, Instant};
+
+/// Reasons `solve` couldn't produce an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// There is no solution for this day and part
+    NotSolved,
+    /// The generator rejected the input
+    BadInput(String),
+    /// The input parsed, but there's no answer to this part for it
+    NoAnswer,
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolveError::NotSolved => f.write_str("no solution for this day and part"),
+            SolveError::BadInput(message) => write!(f, "bad input: {}", message),
+            SolveError::NoAnswer => f.write_str("the input has no answer for this part"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Time spent parsing the input and running the solution on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Timings {
+    pub generator: Duration,
+    pub runner: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.generator + self.runner
+    }
+}
+
+/// What a solution returns: an answer to print, or `None` when the input doesn't have one
+pub(crate) trait Answer {
+    fn answer(self) -> Result<String, SolveError>;
+}
+
+macro_rules! answers {
+    ($($t:ty),*) => {
+        $(impl Answer for $t {
+            fn answer(self) -> Result<String, SolveError> {
+                Ok(self.to_string())
+            }
+        })*
+    };
+}
+
+answers!(i32, i64, i128, u32, u64, u128, usize, String);
+
+impl<T: Answer> Answer for Option<T> {
+    fn answer(self) -> Result<String, SolveError> {
+        self.ok_or(SolveError::NoAnswer)?.answer()
+    }
+}
+
+/// Run a generator, turning its error into a `SolveError` if it's marked `(?)` as able to fail
+macro_rules! generate {
+    ($generator:expr, ?) => {
+        $generator.map_err(|err| SolveError::BadInput(err.to_string()))?
+    };
+    ($generator:expr,) => {
+        $generator
+    };
+}
+
+macro_rules! solutions {
+    (@part $year:ident $module:ident 1) => { crate::$year::$module::part1 };
+    (@part $year:ident $module:ident 2) => { crate::$year::$module::part2 };
+    ($($year:literal => $year_module:ident {
+        $($day:literal => $module:ident::$generator:ident $(($fallible:tt))? => [$($part:tt),*];)*
+    })*) => {
+        /// Parse the input and run one part on it, calling `lap` once the generator is done and
+        /// again once the solution is
+        fn run(
+            year: u32,
+            day: u32,
+            part: u32,
+            input: &str,
+            mut lap: impl FnMut(),
+        ) -> Result<String, SolveError> {
+            match (year, day) {
+                $($(($year, $day) => {
+                    let parsed = generate!(crate::$year_module::$module::$generator(input), $($fallible)?);
+                    lap();
+                    match part {
+                        $($part => {
+                            let answer = solutions!(@part $year_module $module $part)(&parsed);
+                            lap();
+                            answer.answer()
+                        })*
+                        _ => Err(SolveError::NotSolved),
+                    }
+                })*)*
+                _ => Err(SolveError::NotSolved),
+            }
+        }
+
+        /// Every (year, day) that `solve` can run, in order
+        pub const DAYS: &[(u32, u32)] = &[$($(($year, $day)),*),*];
+    };
+}
+
+solutions! {
+    2021 => y2021 {
+        1 => day1::depths(?) => [1, 2];
+        2 => day2::commands => [1, 2];
+        3 => day3::binary(?) => [1, 2];
+        4 => day4::bingo(?) => [1, 2];
+        5 => day5::lines => [1, 2];
+        6 => day6::fish(?) => [1, 2];
+        7 => day7::crabs => [1, 2];
+        8 => day8::digits => [1, 2];
+        9 => day9::heightmap(?) => [1, 2];
+        10 => day10::program => [1, 2];
+        11 => day11::octopuses(?) => [1, 2];
+        12 => day12::parse_adj_list(?) => [1, 2];
+        13 => day13::parse_instructions(?) => [1, 2];
+        14 => day14::parse_polymer_template(?) => [1, 2];
+        15 => day15::cave_map(?) => [1, 2];
+        16 => day16::transmission(?) => [1, 2];
+        17 => day17::target_area(?) => [1, 2];
+        18 => day18::fish_math(?) => [1, 2];
+        21 => day21::starting_positions(?) => [1, 2];
+        22 => day22::parse_commands(?) => [1, 2];
+        25 => day25::sea_floor(?) => [1];
+    }
+    2022 => y2022 {
+        1 => day1::calories => [1, 2];
+    }
+}
+
+/// Parse `input` with the day's generator and run one part on it, formatting the answer the same
+/// way `cargo aoc` prints it
+pub fn solve(year: u32, day: u32, part: u32, input: &str) -> Result<String, SolveError> {
+    run(year, day, part, input, || {})
+}
+
+/// `run`, along with how long the generator took if it parsed the input, and how long the solution
+/// took if it ran
+fn run_timed(
+    year: u32,
+    day: u32,
+    part: u32,
+    input: &str,
+) -> (
+    Result<String, SolveError>,
+    Option<Duration>,
+    Option<Duration>,
+) {
+    let start = Instant::now();
+    let mut laps = Vec::with_capacity(2);
+    let answer = run(year, day, part, input, || laps.push(Instant::now()));
+    let generator = laps.first().map(|&lap| lap - start);
+    let runner = laps.get(1).map(|&lap| lap - laps[0]);
+    (answer, generator, runner)
+}
+
+/// Same as `solve`, also returning how long the generator and the solution took
+pub fn solve_timed(
+    year: u32,
+    day: u32,
+    part: u32,
+    input: &str,
+) -> Result<(String, Timings), SolveError> {
+    let (answer, generator, runner) = run_timed(year, day, part, input);
+    let answer = answer?;
+    let timings = Timings {
+        generator: generator.expect("an answer is only given once the input is parsed"),
+        runner: runner.expect("an answer is only given once the solution has run"),
+    };
+    Ok((answer, timings))
+}
+
+/// One part of a day run by `input_stats`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartStats {
+    pub part: u32,
+    /// The answer and how long the solution took once the input was parsed
+    pub result: Result<(String, Duration), SolveError>,
+}
+
+/// How big an input is and how long a day takes on it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputStats {
+    pub lines: usize,
+    pub bytes: usize,
+    /// The fastest the generator parsed the input over every part, or `None` if it never did
+    pub parse: Option<Duration>,
+    /// Every part with a solution, in order
+    pub parts: Vec<PartStats>,
+}
+
+/// Solve every part of a day on `input`, gathering the size of the input along with the timings.
+/// A part without an answer still counts towards the parse time. Days without a solution come back
+/// with no parts
+pub fn input_stats(year: u32, day: u32, input: &str) -> InputStats {
+    let mut parse = None::<Duration>;
+    let parts = (1..=2)
+        .map(|part| {
+            let (result, generator, runner) = run_timed(year, day, part, input);
+            if let Some(generator) = generator {
+                parse = Some(parse.map_or(generator, |fastest| fastest.min(generator)));
+            }
+            let result = result.map(|answer| {
+                let runner = runner.expect("an answer is only given once the solution has run");
+                (answer, runner)
+            });
+            PartStats { part, result }
+        })
+        .filter(|stats| stats.result != Err(SolveError::NotSolved))
+        .collect();
+
+    InputStats {
+        lines: input.lines().count(),
+        bytes: input.len(),
+        parse,
+        parts,
+    }
+}
+
+/// Like `solve`, but the solvers that report progress report it into `sink`. Days that don't report
+/// anything are solved the same as `solve` would
+pub fn solve_with_progress(
+    year: u32,
+    day: u32,
+    part: u32,
+    input: &str,
+    sink: &mut dyn ProgressSink,
+) -> Result<String, SolveError> {
+    use crate::y2021::{day12, day21, day22};
+
+    let ctx = &mut Context::new(day, sink);
+    let answer = match (year, day, part) {
+        (2021, 12, 1) => {
+            let caves = generate!(day12::parse_adj_list(input), ?);
+            day12::part1_progress(&caves, ctx).to_string()
+        }
+        (2021, 12, 2) => {
+            let caves = generate!(day12::parse_adj_list(input), ?);
+            day12::part2_progress(&caves, ctx).to_string()
+        }
+        (2021, 21, 2) => {
+            let positions = generate!(day21::starting_positions(input), ?);
+            day21::part2_progress(&positions, ctx).to_string()
+        }
+        (2021, 22, 2) => {
+            let commands = generate!(day22::parse_commands(input), ?);
+            day22::part2_progress(&commands, ctx)
+                .ok_or(SolveError::NoAnswer)?
+                .to_string()
+        }
+        _ => return solve(year, day, part, input),
+    };
+    Ok(answer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bad_input() {
+        // Every day either answers or says what's wrong, without panicking
+        let inputs = [
+            "",
+            "\n",
+            "x",
+            "1,2\n3",
+            "12\n345\n",
+            "99999999999999999999999",
+            "a-b\nb-\n",
+            "0,0\n8,0\n\nfold along x=1",
+            ">.",
+            "5\n\n1\n",
+            "ab | ab",
+            // Answers too big for their integer types
+            &"(".repeat(40),
+            "0600848C210842108400246108421084200",
+            "on x=-9000000000000000000..9000000000000000000,y=0..0,z=0..0",
+            "down 4294967295\nforward 2147483647\nforward 2147483647",
+            "11111111111111111111111111111111",
+            "target area: x=1..2, y=-9223372036854775808..0",
+            &"(".repeat(50_000),
+            &"[".repeat(50_000),
+            "on x=9223372036854775806..9223372036854775807,y=0..0,z=0..0\n\
+             off x=9223372036854775807..9223372036854775807,y=0..1,z=0..0",
+        ];
+        for &(year, day) in DAYS {
+            for part in 1..=2 {
+                for input in inputs {
+                    let _ = solve(year, day, part, input);
+                }
+            }
+        }
+
+        assert_eq!(
+            solve(2021, 4, 1, ""),
+            Err(SolveError::BadInput(
+                "line 1: missing the numbers to draw".to_string()
+            ))
+        );
+        assert_eq!(
+            solve(
+                2021,
+                2,
+                2,
+                "down 4294967295\nforward 2147483647\nforward 2147483647"
+            ),
+            Err(SolveError::NoAnswer)
+        );
+        assert_eq!(
+            solve(2021, 3, 1, "11111111111111111111111111111111"),
+            Ok("0".to_string())
+        );
+        assert!(matches!(
+            solve(2021, 3, 1, "111111111111111111111111111111111"),
+            Err(SolveError::BadInput(_))
+        ));
+        assert_eq!(
+            solve(
+                2021,
+                4,
+                1,
+                "4294967295\n\n4294967295 4294967295\n4294967294 4294967294\n"
+            ),
+            Ok((4294967295u128 * 2 * 4294967294).to_string())
+        );
+        assert_eq!(solve(2021, 8, 2, "ab | ab"), Err(SolveError::NoAnswer));
+        let long_display = format!(
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | {}",
+            vec!["cdfgeb"; 20].join(" ")
+        );
+        assert_eq!(solve(2021, 8, 2, &long_display), Err(SolveError::NoAnswer));
+        assert_eq!(
+            solve(2021, 9, 2, &format!("0{}9", "1".repeat(1999)).repeat(3)),
+            Ok("8000000000".to_string())
+        );
+        // Nested deep enough to run a recursive parser out of stack
+        let deep = "(".repeat(50_000);
+        assert_eq!(solve(2021, 10, 1, &deep), Err(SolveError::NoAnswer));
+        assert_eq!(solve(2021, 10, 2, &deep), Err(SolveError::NoAnswer));
+        assert!(matches!(
+            solve(2021, 18, 1, &"[".repeat(50_000)),
+            Err(SolveError::BadInput(_))
+        ));
+        assert_eq!(solve(2021, 10, 2, "()"), Err(SolveError::NoAnswer));
+        assert_eq!(
+            solve(2021, 10, 2, &"(".repeat(40)),
+            Err(SolveError::NoAnswer)
+        );
+        assert_eq!(
+            solve(2021, 16, 2, "0600848C210842108400246108421084200"),
+            Err(SolveError::NoAnswer)
+        );
+        assert_eq!(
+            solve(
+                2021,
+                22,
+                2,
+                "on x=-9000000000000000000..9000000000000000000,y=0..0,z=0..0"
+            ),
+            Err(SolveError::NoAnswer)
+        );
+        assert_eq!(
+            solve(
+                2021,
+                17,
+                1,
+                "target area: x=1..2, y=-9223372036854775808..0"
+            ),
+            Err(SolveError::NoAnswer)
+        );
+        assert_eq!(
+            solve(
+                2021,
+                22,
+                2,
+                "on x=9223372036854775806..9223372036854775807,y=0..0,z=0..0\n\
+                 off x=9223372036854775807..9223372036854775807,y=0..1,z=0..0"
+            ),
+            Ok("1".to_string())
+        );
+        assert_eq!(
+            solve(2021, 18, 1, "[9223372036854775807,1]"),
+            Err(SolveError::NoAnswer)
+        );
+        assert_eq!(solve(2021, 18, 2, "[1,2]"), Err(SolveError::NoAnswer));
+        assert_eq!(solve(2021, 25, 2, ">>"), Err(SolveError::NotSolved));
+    }
+
+    #[test]
+    fn stats() {
+        let log = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        let stats = input_stats(2021, 1, log);
+        assert_eq!((stats.lines, stats.bytes), (10, 40));
+        assert!(stats.parse.is_some());
+        let answers = stats
+            .parts
+            .iter()
+            .map(|stats| (stats.part, stats.result.clone().unwrap().0))
+            .collect::<Vec<_>>();
+        assert_eq!(answers, [(1, "7".to_string()), (2, "5".to_string())]);
+
+        // Only the parts that are solved, and no parse time for an input the generator rejects
+        let stats = input_stats(2021, 25, ">.\n");
+        assert_eq!(stats.parts.len(), 1);
+        assert!(matches!(
+            solve_timed(2021, 3, 1, "x"),
+            Err(SolveError::BadInput(_))
+        ));
+        let stats = input_stats(2021, 3, "x");
+        assert_eq!((stats.lines, stats.bytes, stats.parse), (1, 1, None));
+        assert_eq!(stats.parts.len(), 2);
+        assert!(stats
+            .parts
+            .iter()
+            .all(|part| matches!(part.result, Err(SolveError::BadInput(_)))));
+
+        assert!(input_stats(2021, 24, "").parts.is_empty());
+
+        // Nothing wins, so neither part has an answer, but the input still parsed
+        let stats = input_stats(2021, 4, "5\n\n1 2\n3 4\n");
+        assert!(stats.parse.is_some());
+        assert!(stats
+            .parts
+            .iter()
+            .all(|part| part.result == Err(SolveError::NoAnswer)));
+    }
+}
@@ -0,0 +1,36 @@
+//! Helpers shared by the tests and the benches. Not part of the API
+
+/// A xorshift generator, for tests and benches that want input that looks random but is the same
+/// on every run
+#[derive(Debug, Clone)]
+pub struct Xorshift(u32);
+
+impl Xorshift {
+    /// Start from `seed`, which can't be 0 or every number after it would be too
+    pub fn new(seed: u32) -> Self {
+        assert!(seed != 0, "xorshift can't start from 0");
+        Xorshift(seed)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+impl Default for Xorshift {
+    fn default() -> Self {
+        Xorshift::new(0x2545f491)
+    }
+}
+
+/// Never ends
+impl Iterator for Xorshift {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        Some(self.next_u32())
+    }
+}
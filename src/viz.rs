@@ -0,0 +1,308 @@
+//! Renderers for the grid based days. Each day draws onto a `Canvas`, a grid of cells holding a
+//! character for the ASCII output and a color for the PPM output, and frames are written out to a
+//! directory one file per frame.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use gif::{Encoder, Frame, Repeat};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const RED: Rgb = [255, 0, 0];
+pub const YELLOW: Rgb = [255, 220, 0];
+
+/// Each cell is drawn as a square of this many pixels in PPM output
+pub const PPM_SCALE: usize = 4;
+
+const PALETTE: [Rgb; 12] = [
+    [230, 25, 75],
+    [60, 180, 75],
+    [255, 225, 25],
+    [0, 130, 200],
+    [245, 130, 48],
+    [145, 30, 180],
+    [70, 240, 240],
+    [240, 50, 230],
+    [210, 245, 60],
+    [250, 190, 212],
+    [0, 128, 128],
+    [170, 110, 40],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ascii,
+    Ppm,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Ascii => "txt",
+            Format::Ppm => "ppm",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<(char, Rgb)>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            cells: vec![(' ', BLACK); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, c: char, color: Rgb) {
+        self.cells[y * self.width + x] = (c, color);
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> (char, Rgb) {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn to_ascii(&self) -> String {
+        let mut ascii = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)) {
+            ascii.extend(row.iter().map(|&(c, _)| c));
+            ascii.push('\n');
+        }
+        ascii
+    }
+
+    /// Binary (P6) PPM with every cell blown up to a `scale` x `scale` square
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let mut ppm =
+            format!("P6\n{} {}\n255\n", self.width * scale, self.height * scale).into_bytes();
+        for row in self.cells.chunks(self.width.max(1)) {
+            for _ in 0..scale {
+                for &(_, color) in row {
+                    for _ in 0..scale {
+                        ppm.extend_from_slice(&color);
+                    }
+                }
+            }
+        }
+        ppm
+    }
+}
+
+/// Grayscale ramp from black at 0 to white at max
+pub fn gray(value: usize, max: usize) -> Rgb {
+    let level = (255 * value.min(max) / max.max(1)) as u8;
+    [level, level, level]
+}
+
+/// A color for region `label` that is distinct from the labels next to it
+pub fn label_color(label: usize) -> Rgb {
+    PALETTE[label % PALETTE.len()]
+}
+
+/// Write the frames to `<dir>/<name>_<frame>.<ext>`, creating dir if it doesn't exist
+pub fn write_frames(dir: &Path, name: &str, frames: &[Canvas], format: Format) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (i, frame) in frames.iter().enumerate() {
+        let path = dir.join(format!("{}_{:04}.{}", name, i, format.extension()));
+        match format {
+            Format::Ascii => fs::write(path, frame.to_ascii())?,
+            Format::Ppm => fs::write(path, frame.to_ppm(PPM_SCALE))?,
+        }
+    }
+    Ok(())
+}
+
+/// Options for `render_gif_with`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GifOptions {
+    /// Frames per second, at most 100 since GIF delays are in hundredths of a second
+    pub fps: u16,
+    /// Pixels per cell along each side
+    pub scale: usize,
+    /// Restrict the animation to these colors, snapping each cell to the nearest one. When `None`
+    /// each frame gets a palette quantized from its own colors
+    pub palette: Option<Vec<Rgb>>,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        GifOptions {
+            fps: 10,
+            scale: PPM_SCALE,
+            palette: None,
+        }
+    }
+}
+
+fn nearest_color(palette: &[Rgb], color: Rgb) -> u8 {
+    let distance = |other: &Rgb| {
+        color
+            .iter()
+            .zip(other.iter())
+            .map(|(&a, &b)| (a as i32 - b as i32).pow(2))
+            .sum::<i32>()
+    };
+
+    (0..palette.len())
+        .min_by_key(|&i| distance(&palette[i]))
+        .unwrap_or(0) as u8
+}
+
+fn to_gif_error(err: gif::EncodingError) -> io::Error {
+    match err {
+        gif::EncodingError::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+/// Write the frames as a looping animated GIF with the default options
+pub fn render_gif(frames: &[Canvas], path: &Path) -> io::Result<()> {
+    render_gif_with(frames, path, &GifOptions::default())
+}
+
+pub fn render_gif_with(frames: &[Canvas], path: &Path, options: &GifOptions) -> io::Result<()> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_string());
+    if options.fps == 0 || options.fps > 100 {
+        return Err(invalid("GIF frame rate must be between 1 and 100"));
+    }
+    if let Some(palette) = &options.palette {
+        if palette.is_empty() || palette.len() > 256 {
+            return Err(invalid("GIF palettes hold between 1 and 256 colors"));
+        }
+    }
+
+    let scale = options.scale.max(1);
+    let width = frames.iter().map(|f| f.width() * scale).max().unwrap_or(0);
+    let height = frames.iter().map(|f| f.height() * scale).max().unwrap_or(0);
+    if width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(invalid("Frames are too large for a GIF"));
+    }
+
+    let global_palette = options
+        .palette
+        .iter()
+        .flatten()
+        .flat_map(|color| color.iter().copied())
+        .collect::<Vec<_>>();
+
+    let file = fs::File::create(path)?;
+    let mut encoder =
+        Encoder::new(file, width as u16, height as u16, &global_palette).map_err(to_gif_error)?;
+    encoder.set_repeat(Repeat::Infinite).map_err(to_gif_error)?;
+
+    for canvas in frames {
+        // Reuse the PPM encoder for scaling, and skip past its header to get at the pixels
+        let ppm = canvas.to_ppm(scale);
+        let header_len = ppm.len() - canvas.width() * canvas.height() * scale * scale * 3;
+        let pixels = &ppm[header_len..];
+
+        let (w, h) = (
+            (canvas.width() * scale) as u16,
+            (canvas.height() * scale) as u16,
+        );
+        let mut frame = match &options.palette {
+            Some(palette) => {
+                let indices = pixels
+                    .chunks_exact(3)
+                    .map(|p| nearest_color(palette, [p[0], p[1], p[2]]))
+                    .collect::<Vec<_>>();
+                Frame::from_indexed_pixels(w, h, &indices, None)
+            }
+            None => Frame::from_rgb(w, h, pixels),
+        };
+        frame.delay = 100 / options.fps;
+        encoder.write_frame(&frame).map_err(to_gif_error)?;
+    }
+
+    Ok(())
+}
+
+/// Render the frames for a 2021 day from its puzzle input, or None if the day has no renderer
+pub fn render_day(day: u32, input: &str) -> Option<Vec<Canvas>> {
+    match day {
+        5 => Some(crate::y2021::day5::render_frames(input)),
+        7 => Some(crate::y2021::day7::render_frames(input)),
+        8 => Some(crate::y2021::day8::render_frames(input)),
+        9 => Some(crate::y2021::day9::render_frames(input)),
+        11 => Some(crate::y2021::day11::render_frames(input)),
+        13 => Some(crate::y2021::day13::render_frames(input)),
+        15 => Some(crate::y2021::day15::render_frames(input)),
+        25 => Some(crate::y2021::day25::render_frames(input)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ascii_and_ppm() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.set(0, 0, '#', WHITE);
+        canvas.set(1, 0, '.', RED);
+
+        assert_eq!(canvas.to_ascii(), "#.\n");
+
+        let ppm = canvas.to_ppm(1);
+        let header = b"P6\n2 1\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(&ppm[header.len()..], &[255, 255, 255, 255, 0, 0]);
+
+        assert_eq!(canvas.to_ppm(2).len(), b"P6\n4 2\n255\n".len() + 4 * 2 * 3);
+    }
+
+    #[test]
+    fn gif_export() {
+        let dir = std::env::temp_dir().join("aoc21_viz_gif_export");
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut frames = vec![Canvas::new(3, 2), Canvas::new(3, 2)];
+        frames[1].set(1, 1, '#', WHITE);
+
+        let path = dir.join("default.gif");
+        render_gif(&frames, &path).unwrap();
+        let gif = fs::read(&path).unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif[6..10], [12, 0, 8, 0]);
+
+        let options = GifOptions {
+            fps: 25,
+            scale: 1,
+            palette: Some(vec![BLACK, WHITE]),
+        };
+        let path = dir.join("palette.gif");
+        render_gif_with(&frames, &path, &options).unwrap();
+        let gif = fs::read(&path).unwrap();
+        assert_eq!(gif[6..10], [3, 0, 2, 0]);
+
+        let options = GifOptions {
+            fps: 0,
+            ..GifOptions::default()
+        };
+        assert!(render_gif_with(&frames, &path, &options).is_err());
+    }
+
+    #[test]
+    fn days_without_renderer() {
+        assert!(render_day(1, "199\n200").is_none());
+    }
+}
@@ -0,0 +1,11 @@
+//! Bindings for running the solutions in a browser, where a page hands over the puzzle input and
+//! shows the answer. Build with `wasm-pack build --features wasm`
+
+use wasm_bindgen::prelude::*;
+
+/// Solve one part of a 2021 day. Bad input and days without a solution are thrown as a JS `Error`
+/// holding the message, rather than panicking
+#[wasm_bindgen]
+pub fn solve(day: u32, part: u32, input: &str) -> Result<String, JsError> {
+    crate::solve(2021, day, part, input).map_err(|err| JsError::new(&err.to_string()))
+}
@@ -0,0 +1,469 @@
+use crate::error::ParseError;
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+/// The depth on a line of a sonar log, numbered from 1, or None if the line is blank
+fn parse_depth(number: usize, line: &str) -> Option<Result<u32, ParseError>> {
+    let line = line.trim();
+    (!line.is_empty()).then(|| {
+        line.parse()
+            .map_err(|err| ParseError::at_line(number, format!("bad depth: {}", err)))
+    })
+}
+
+/// Depths from a sonar log, one per line. Blank lines are skipped
+fn depth_readings(r: impl BufRead) -> impl Iterator<Item = io::Result<u32>> {
+    r.lines().enumerate().filter_map(|(i, line)| match line {
+        Ok(line) => parse_depth(i + 1, &line)
+            .map(|depth| depth.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))),
+        Err(err) => Some(Err(err)),
+    })
+}
+
+#[aoc_generator(day1)]
+pub(crate) fn depths(input: &str) -> Result<Vec<u32>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| parse_depth(i + 1, line))
+        .collect()
+}
+
+/// Count how many times the sum of a sliding `window` of depths increases. Two neighboring windows
+/// share all but their first and last depths, so only the last `window` depths have to be kept
+/// around
+fn count_increases(depths: impl IntoIterator<Item = u32>, window: usize) -> usize {
+    assert!(window > 0, "Window must hold at least one depth");
+
+    let mut ring = Vec::with_capacity(window);
+    let mut oldest = 0;
+    let mut increases = 0;
+    for depth in depths {
+        if ring.len() < window {
+            ring.push(depth);
+            continue;
+        }
+
+        if depth > ring[oldest] {
+            increases += 1;
+        }
+        ring[oldest] = depth;
+        oldest = (oldest + 1) % window;
+    }
+
+    increases
+}
+
+/// `count_increases` reading the log as it goes, stopping at the first line that can't be read
+pub fn count_increases_from_reader(r: impl BufRead, window: usize) -> io::Result<usize> {
+    let mut error = None;
+    let depths = depth_readings(r).map_while(|depth| depth.map_err(|err| error = Some(err)).ok());
+    let increases = count_increases(depths, window);
+    match error {
+        Some(err) => Err(err),
+        None => Ok(increases),
+    }
+}
+
+/// Smooth out spikes by replacing each depth with the median of the `k` depths centered on it. The
+/// window is cut short at either end of the log, and the lower median is taken when that leaves it
+/// with an even number of depths
+pub fn median_filter(depths: &[u32], k: usize) -> Vec<u32> {
+    assert!(k % 2 == 1, "Filter must be centered on a depth");
+
+    let reach = k / 2;
+    let mut window = Vec::with_capacity(k);
+    (0..depths.len())
+        .map(|i| {
+            window.clear();
+            window.extend_from_slice(
+                &depths[i.saturating_sub(reach)..depths.len().min(i + reach + 1)],
+            );
+            window.sort_unstable();
+            window[(window.len() - 1) / 2]
+        })
+        .collect()
+}
+
+/// `count_increases_from_reader` on a log that has been through `median_filter` first
+pub fn count_increases_filtered(depths: &[u32], window: usize, filter_k: usize) -> usize {
+    assert!(window > 0, "Window must hold at least one depth");
+
+    let filtered = median_filter(depths, filter_k);
+    filtered
+        .iter()
+        .zip(filtered.iter().skip(window))
+        .filter(|(first, last)| first < last)
+        .count()
+}
+
+/// What to measure over each window of depths in `count_window_trend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowStat {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Variance,
+}
+
+/// Count how many times `stat` over a sliding `window` of depths increases from one window to the
+/// next. Part 2 is the count for sums of three
+pub fn count_window_trend(depths: &[u32], window: usize, stat: WindowStat) -> usize {
+    assert!(window > 0, "Window must hold at least one depth");
+
+    let values = match stat {
+        // Every window holds the same number of depths, so the mean goes up exactly when the sum
+        // does, and comparing sums keeps it in whole numbers
+        WindowStat::Sum | WindowStat::Mean => window_sums(depths, window),
+        WindowStat::Min => window_extremes(depths, window, |kept, new| kept <= new),
+        WindowStat::Max => window_extremes(depths, window, |kept, new| kept >= new),
+        WindowStat::Variance => window_variances(depths, window),
+    };
+    values.windows(2).filter(|pair| pair[0] < pair[1]).count()
+}
+
+/// The sum of each window, adding the depth that slides in and taking away the one that slides out
+fn window_sums(depths: &[u32], window: usize) -> Vec<i128> {
+    if depths.len() < window {
+        return Vec::new();
+    }
+
+    let mut sum = depths[..window].iter().map(|&d| d as i128).sum::<i128>();
+    let mut sums = vec![sum];
+    for (&old, &new) in depths.iter().zip(&depths[window..]) {
+        sum += new as i128 - old as i128;
+        sums.push(sum);
+    }
+    sums
+}
+
+/// The minimum or maximum of each window, with a deque of the depths that could still be it.
+/// `keeps(kept, new)` says whether an earlier depth stays ahead of a later one; those that don't
+/// are dropped from the back, so the front is always the answer for the current window
+fn window_extremes(depths: &[u32], window: usize, keeps: impl Fn(u32, u32) -> bool) -> Vec<i128> {
+    let mut candidates = VecDeque::<usize>::new();
+    let mut extremes = Vec::new();
+    for (i, &depth) in depths.iter().enumerate() {
+        while candidates.back().is_some_and(|&j| !keeps(depths[j], depth)) {
+            candidates.pop_back();
+        }
+        candidates.push_back(i);
+        if candidates[0] + window <= i {
+            candidates.pop_front();
+        }
+        if i + 1 >= window {
+            extremes.push(depths[candidates[0]] as i128);
+        }
+    }
+    extremes
+}
+
+/// The variance of each window, times the window length squared. Welford's update for swapping
+/// one value for another, scaled by the window length so it stays in whole numbers and windows
+/// with the same variance compare equal:
+///
+/// n·M2' = n·M2 + (new - old)·(n·new - S' + n·old - S)
+///
+/// where S is the sum of the window and M2 the sum of squared distances from its mean
+fn window_variances(depths: &[u32], window: usize) -> Vec<i128> {
+    if depths.len() < window {
+        return Vec::new();
+    }
+
+    let n = window as i128;
+    let mut sum = depths[..window].iter().map(|&d| d as i128).sum::<i128>();
+    let mut scaled_m2 = depths[..window]
+        .iter()
+        .map(|&d| (n * d as i128 - sum).pow(2))
+        .sum::<i128>()
+        / n;
+    let mut variances = vec![scaled_m2];
+    for (&old, &new) in depths.iter().zip(&depths[window..]) {
+        let (old, new) = (old as i128, new as i128);
+        let next_sum = sum + new - old;
+        scaled_m2 += (new - old) * (n * new - next_sum + n * old - sum);
+        sum = next_sum;
+        variances.push(scaled_m2);
+    }
+    variances
+}
+
+/// Which way the sea floor goes over a run of depths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// Each depth is at least as deep as the one before
+    Deeper,
+    /// Each depth is shallower than the one before
+    Shallower,
+}
+
+/// A stretch of the depth log that keeps to one trend, from `start` for `len` depths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub trend: Trend,
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Every maximal run of at least `min_run` depths that keeps to one trend, in the order they start.
+/// The depth where the trend turns ends one run and starts the next, so neighboring runs share it.
+/// A level stretch counts as getting deeper
+pub fn detect_descents(depths: &[u32], min_run: usize) -> Vec<Run> {
+    let trend = |i: usize| {
+        if depths[i] >= depths[i - 1] {
+            Trend::Deeper
+        } else {
+            Trend::Shallower
+        }
+    };
+
+    let mut runs = Vec::new();
+    let mut keep = |run: Run| {
+        if run.len >= min_run {
+            runs.push(run);
+        }
+    };
+
+    if depths.is_empty() {
+        return runs;
+    }
+
+    // A lone depth is a level stretch until the next one says otherwise
+    let mut run = Run {
+        trend: Trend::Deeper,
+        start: 0,
+        len: 1,
+    };
+    for i in 1..depths.len() {
+        let next = trend(i);
+        if run.len > 1 && next != run.trend {
+            keep(run);
+            run = Run {
+                trend: next,
+                start: i - 1,
+                len: 1,
+            };
+        }
+        run.trend = next;
+        run.len += 1;
+    }
+    keep(run);
+
+    runs
+}
+
+#[aoc(day1, part1)]
+pub(crate) fn part1(scan_depths: &[u32]) -> usize {
+    count_increases(scan_depths.iter().copied(), 1)
+}
+
+#[aoc(day1, part2)]
+pub(crate) fn part2(scan_depths: &[u32]) -> usize {
+    count_increases(scan_depths.iter().copied(), 3)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+    use std::io::Cursor;
+
+    #[test]
+    fn reader() {
+        let log = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        assert_eq!(count_increases_from_reader(log.as_bytes(), 1).unwrap(), 7);
+        assert_eq!(count_increases_from_reader(log.as_bytes(), 3).unwrap(), 5);
+        assert_eq!(count_increases_from_reader(log.as_bytes(), 10).unwrap(), 0);
+
+        let err = count_increases_from_reader("1\n2\ndeep\n".as_bytes(), 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // The generator reads the same log, and turns down the same line
+        assert_eq!(
+            depths("1\n\n2\ndeep\n").err().map(|err| err.line()),
+            Some(4)
+        );
+        assert_eq!(depths(" 3\n\n4 \n"), Ok(vec![3, 4]));
+    }
+
+    #[test]
+    fn filtered() {
+        assert_eq!(median_filter(&[1, 9, 2, 3, 0, 4], 3), [1, 2, 3, 2, 3, 0]);
+        assert_eq!(median_filter(&[5, 1, 3], 1), [5, 1, 3]);
+        assert_eq!(median_filter(&[], 5), [0u32; 0]);
+
+        // A staircase of 20 plateaus, with a spike up or down in the middle of each
+        let stairs = (0..100).map(|i| 100 + 10 * (i / 5)).collect::<Vec<u32>>();
+        let spiked = |spike: &dyn Fn(usize) -> u32| {
+            let mut depths = stairs.clone();
+            for plateau in 0..20 {
+                depths[5 * plateau + 2] = spike(plateau);
+            }
+            depths
+        };
+        let up = spiked(&|_| 10_000);
+        let down = spiked(&|_| 0);
+        let mixed = spiked(&|plateau| if plateau % 3 == 0 { 0 } else { 10_000 });
+
+        assert_eq!(part1(&stairs), 19);
+        for depths in [&up, &down, &mixed] {
+            assert_ne!(part1(depths), 19);
+            assert_eq!(median_filter(depths, 3), stairs);
+            assert_eq!(count_increases_filtered(depths, 1, 3), 19);
+            assert_eq!(count_increases_filtered(depths, 3, 3), part2(&stairs));
+        }
+
+        // No filtering is the plain count
+        let log = depths("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+        assert_eq!(count_increases_filtered(&log, 1, 1), part1(&log));
+        assert_eq!(count_increases_filtered(&log, 3, 1), part2(&log));
+    }
+
+    #[test]
+    fn descents() {
+        let log = depths("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+        let run = |trend, start, len| Run { trend, start, len };
+        assert_eq!(
+            detect_descents(&log, 1),
+            [
+                run(Trend::Deeper, 0, 4),
+                run(Trend::Shallower, 3, 2),
+                run(Trend::Deeper, 4, 4),
+                run(Trend::Shallower, 7, 2),
+                run(Trend::Deeper, 8, 2),
+            ]
+        );
+        assert_eq!(
+            detect_descents(&log, 3),
+            [run(Trend::Deeper, 0, 4), run(Trend::Deeper, 4, 4)]
+        );
+        assert!(detect_descents(&log, 5).is_empty());
+
+        // Level stretches carry on a descent, and break up a climb
+        let plateaus = [5, 5, 5, 7, 7, 4, 3, 3, 1, 1];
+        assert_eq!(
+            detect_descents(&plateaus, 1),
+            [
+                run(Trend::Deeper, 0, 5),
+                run(Trend::Shallower, 4, 3),
+                run(Trend::Deeper, 6, 2),
+                run(Trend::Shallower, 7, 2),
+                run(Trend::Deeper, 8, 2),
+            ]
+        );
+        assert_eq!(detect_descents(&[4, 4, 4], 3), [run(Trend::Deeper, 0, 3)]);
+        assert_eq!(
+            detect_descents(&[9, 6, 2], 1),
+            [run(Trend::Shallower, 0, 3)]
+        );
+        assert_eq!(detect_descents(&[7], 1), [run(Trend::Deeper, 0, 1)]);
+        assert!(detect_descents(&[], 1).is_empty());
+
+        // Runs cover the whole log, each sharing its first depth with the end of the one before,
+        // and every step deeper belongs to a descent
+        let staircase = (0..60).map(|i| (i % 7) * (i / 7)).collect::<Vec<u32>>();
+        let runs = detect_descents(&staircase, 1);
+        assert_eq!(runs[0].start, 0);
+        for pair in runs.windows(2) {
+            assert_eq!(pair[0].start + pair[0].len - 1, pair[1].start);
+            assert_ne!(pair[0].trend, pair[1].trend);
+        }
+        let last = runs.last().unwrap();
+        assert_eq!(last.start + last.len, staircase.len());
+        let climbs = runs
+            .iter()
+            .filter(|run| run.trend == Trend::Deeper)
+            .map(|run| {
+                count_increases(staircase[run.start..run.start + run.len].iter().copied(), 1)
+            })
+            .sum::<usize>();
+        assert_eq!(climbs, part1(&staircase));
+    }
+
+    #[test]
+    fn window_trends() {
+        let log = depths("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+        assert_eq!(count_window_trend(&log, 1, WindowStat::Sum), part1(&log));
+        assert_eq!(count_window_trend(&log, 3, WindowStat::Mean), 5);
+        // Minimums 199 200 200 200 200 207 240 260, maximums 208 210 210 210 240 269 269 269
+        assert_eq!(count_window_trend(&log, 3, WindowStat::Min), 4);
+        assert_eq!(count_window_trend(&log, 3, WindowStat::Max), 3);
+        assert_eq!(count_window_trend(&log, 11, WindowStat::Max), 0);
+        // A window of one depth never varies
+        assert_eq!(count_window_trend(&log, 1, WindowStat::Variance), 0);
+
+        // Shifting the same spread up doesn't change the variance
+        let steps = [1, 3, 5, 7, 9, 11];
+        assert_eq!(count_window_trend(&steps, 3, WindowStat::Variance), 0);
+        assert_eq!(
+            count_window_trend(&[5, 5, 1, 9, 9], 2, WindowStat::Variance),
+            2
+        );
+
+        // Every window recomputed from scratch. The variance is scaled by the window length
+        // squared the same way, so equal variances stay equal
+        let naive = |depths: &[u32], window: usize, stat: WindowStat| {
+            let values = depths
+                .windows(window)
+                .map(|w| {
+                    let w = w.iter().map(|&d| d as i128).collect::<Vec<_>>();
+                    let (n, sum) = (window as i128, w.iter().sum::<i128>());
+                    match stat {
+                        WindowStat::Sum => sum,
+                        WindowStat::Mean => sum / n,
+                        WindowStat::Min => *w.iter().min().unwrap(),
+                        WindowStat::Max => *w.iter().max().unwrap(),
+                        WindowStat::Variance => {
+                            w.iter().map(|d| (n * d - sum).pow(2)).sum::<i128>() / n
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            values.windows(2).filter(|pair| pair[0] < pair[1]).count()
+        };
+
+        let mut rng = Xorshift::new(0x3c6ef372);
+        let stats = [
+            WindowStat::Sum,
+            WindowStat::Min,
+            WindowStat::Max,
+            WindowStat::Variance,
+        ];
+        for _ in 0..300 {
+            let spread = [4, 100, u32::MAX][rng.next_u32() as usize % 3];
+            let log = (0..rng.next_u32() % 40)
+                .map(|_| rng.next_u32() % spread)
+                .collect::<Vec<_>>();
+            let window = 1 + rng.next_u32() as usize % 6;
+            for stat in stats {
+                assert_eq!(
+                    count_window_trend(&log, window, stat),
+                    naive(&log, window, stat),
+                    "{:?} of {} over {:?}",
+                    stat,
+                    window,
+                    log
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn large_log() {
+        let mut log = Vec::new();
+        for n in Xorshift::default().take(500_000) {
+            log.extend_from_slice(format!("{}\n", n % 10_000).as_bytes());
+        }
+
+        let scan_depths = depths(std::str::from_utf8(&log).unwrap()).unwrap();
+        assert_eq!(
+            count_increases_from_reader(Cursor::new(&log), 1).unwrap(),
+            part1(&scan_depths)
+        );
+        assert_eq!(
+            count_increases_from_reader(Cursor::new(&log), 3).unwrap(),
+            part2(&scan_depths)
+        );
+    }
+}
@@ -0,0 +1,622 @@
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub enum SyntaxError {
+    Corrupt(usize, char, char),
+    Incomplete(Chunk),
+    Empty,
+    /// The chunk opened at this column is nested deeper than `MAX_NESTING`
+    TooDeep(usize),
+}
+
+/// Deepest chunks can nest and still parse. Chunks are parsed, completed and dropped one level of
+/// recursion at a time, so this keeps deeply nested lines from running out of stack
+pub const MAX_NESTING: usize = 1024;
+
+impl SyntaxError {
+    pub fn incomplete(chunk: Chunk) -> Result<Chunk, Self> {
+        Err(SyntaxError::Incomplete(chunk))
+    }
+
+    pub fn corrupt(col: usize, act: char, exp: char) -> Result<Chunk, Self> {
+        Err(SyntaxError::Corrupt(col, act, exp))
+    }
+}
+
+pub struct Chunk {
+    pub opening: char,
+    pub child: Vec<Chunk>,
+    pub closing: Option<char>,
+}
+
+impl std::fmt::Debug for Chunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.opening)?;
+        for chunk in self.child.iter() {
+            chunk.fmt(f)?;
+        }
+        if let Some(closed) = self.closing {
+            write!(f, "{}", closed)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn get_corrupt_score(c: char) -> u64 {
+    match c {
+        ')' => 3,
+        ']' => 57,
+        '}' => 1197,
+        '>' => 25137,
+        _ => panic!("Unexpected character ({})", c),
+    }
+}
+
+pub fn get_ac_score(c: char) -> u64 {
+    match c {
+        ')' => 1,
+        ']' => 2,
+        '}' => 3,
+        '>' => 4,
+        _ => panic!("Unexpected character ({})", c),
+    }
+}
+
+/// Points for each closing character. `corrupt` scores the first wrong character on a corrupt
+/// line, and `autocomplete` each character needed to complete an incomplete one. Characters
+/// missing from a table score nothing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreTable {
+    pub corrupt: HashMap<char, u64>,
+    pub autocomplete: HashMap<char, u64>,
+}
+
+impl Default for ScoreTable {
+    /// The scores from the puzzle
+    fn default() -> Self {
+        let closing = [')', ']', '}', '>'];
+        ScoreTable {
+            corrupt: closing.map(|c| (c, get_corrupt_score(c))).into(),
+            autocomplete: closing.map(|c| (c, get_ac_score(c))).into(),
+        }
+    }
+}
+
+/// How the scores of the completed lines are combined into one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregation {
+    /// The middle score, or the higher of the two middle scores if there are an even number
+    #[default]
+    Median,
+    /// The mean score, rounded down
+    Mean,
+    Sum,
+}
+
+pub fn is_open(tok: char) -> bool {
+    matches!(tok, '(' | '[' | '{' | '<')
+}
+
+fn closing_for(tok: char) -> char {
+    match tok {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        '<' => '>',
+        t => panic!("Unknown token: {}", t),
+    }
+}
+
+impl Chunk {
+    pub fn opens_with(opening: char) -> Self {
+        assert!(
+            is_open(opening),
+            "Chunk not opening with opening token: {}",
+            opening
+        );
+
+        Chunk {
+            opening,
+            child: vec![],
+            closing: None,
+        }
+    }
+
+    pub fn close_with(&mut self, c: char) {
+        self.closing = Some(c);
+    }
+
+    pub fn add_child(&mut self, child: Chunk) {
+        self.child.push(child);
+    }
+
+    pub fn get_missing(&self) -> Vec<char> {
+        let mut missing = self
+            .child
+            .iter()
+            .flat_map(|child| child.get_missing())
+            .collect::<Vec<_>>();
+
+        if self.closing.is_none() {
+            missing.push(closing_for(self.opening));
+        }
+
+        missing
+    }
+}
+
+pub struct ChunkParser<'a> {
+    line: &'a str,
+    col: usize,
+}
+
+impl<'a> ChunkParser<'a> {
+    pub fn parse(line: &str) -> Result<Chunk, SyntaxError> {
+        let mut parser = ChunkParser::with_input(line);
+        parser.parse_chunks(0)
+    }
+
+    fn with_input(line: &'a str) -> Self {
+        ChunkParser { line, col: 0 }
+    }
+
+    fn parse_chunks(&mut self, depth: usize) -> Result<Chunk, SyntaxError> {
+        let car = match self.consume() {
+            Some(car) => car,
+            None => return Err(SyntaxError::Empty),
+        };
+        if !is_open(car) {
+            return SyntaxError::corrupt(self.col - 1, car, 'o');
+        }
+        if depth == MAX_NESTING {
+            return Err(SyntaxError::TooDeep(self.col - 1));
+        }
+
+        let mut chunk = Chunk::opens_with(car);
+        while !self.done() {
+            if let Some(next) = self.peek() {
+                let closed = closing_for(chunk.opening);
+
+                if is_open(next) {
+                    let chunk_or_err = self.parse_chunks(depth + 1);
+                    match chunk_or_err {
+                        Ok(child) => chunk.add_child(child),
+                        Err(SyntaxError::Incomplete(child)) => {
+                            chunk.add_child(child);
+                            return SyntaxError::incomplete(chunk);
+                        }
+                        err => return err,
+                    }
+                } else if next == closed {
+                    self.consume();
+                    chunk.close_with(next);
+                    return Ok(chunk);
+                } else {
+                    return SyntaxError::corrupt(self.col, next, closed);
+                }
+            } else {
+                return SyntaxError::incomplete(chunk);
+            }
+        }
+
+        SyntaxError::incomplete(chunk)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.line.chars().nth(self.col)
+    }
+
+    fn consume(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.col += 1;
+        c
+    }
+
+    fn done(&self) -> bool {
+        self.col >= self.line.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    Corrupt,
+    /// Chunks are nested deeper than `MAX_NESTING`, so the line can't be scored
+    TooDeep,
+    /// The line ran out with chunks still open, `completion` being the characters that close them
+    Incomplete {
+        completion: String,
+    },
+}
+
+/// A problem with one line of the navigation subsystem. Lines and columns start at 1, and the
+/// column of an incomplete line is one past its end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub kind: DiagnosticKind,
+    /// The offending character, `None` when the line ended early
+    pub found: Option<char>,
+    /// The character that should have been there, `None` when any opening character would do
+    pub expected: Option<char>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        if self.kind == DiagnosticKind::TooDeep {
+            return write!(f, "chunks nested more than {} deep", MAX_NESTING);
+        }
+        match (self.expected, self.found) {
+            (Some(expected), Some(found)) => {
+                write!(f, "expected {:?}, found {:?}", expected, found)
+            }
+            (None, Some(found)) => write!(f, "expected an opening character, found {:?}", found),
+            (Some(expected), None) => write!(f, "expected {:?}, found end of line", expected),
+            (None, None) => f.write_str("empty line"),
+        }
+    }
+}
+
+/// Parse one line, with `i` its index in the file
+fn diagnose_line(i: usize, line: &str) -> Option<Diagnostic> {
+    match ChunkParser::parse(line) {
+        Ok(_) | Err(SyntaxError::Empty) => None,
+        Err(SyntaxError::Corrupt(col, act, exp)) => Some(Diagnostic {
+            line: i + 1,
+            column: col + 1,
+            kind: DiagnosticKind::Corrupt,
+            found: Some(act),
+            expected: if exp == 'o' { None } else { Some(exp) },
+        }),
+        Err(SyntaxError::TooDeep(col)) => Some(Diagnostic {
+            line: i + 1,
+            column: col + 1,
+            kind: DiagnosticKind::TooDeep,
+            found: line.chars().nth(col),
+            expected: None,
+        }),
+        Err(SyntaxError::Incomplete(chunk)) => {
+            let completion = chunk.get_missing().into_iter().collect::<String>();
+            Some(Diagnostic {
+                line: i + 1,
+                column: line.chars().count() + 1,
+                expected: completion.chars().next(),
+                kind: DiagnosticKind::Incomplete { completion },
+                found: None,
+            })
+        }
+    }
+}
+
+/// Parse every line, returning a diagnostic for each one that is corrupt or incomplete
+pub fn diagnose(lines: &[String]) -> Vec<Diagnostic> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| diagnose_line(i, line))
+        .collect()
+}
+
+/// Same as `diagnose`, with the lines parsed in parallel. Every line is parsed on its own, so this
+/// only pays off on files far bigger than the puzzle input
+#[cfg(feature = "parallel")]
+pub fn diagnose_parallel(lines: &[String]) -> Vec<Diagnostic> {
+    use rayon::prelude::*;
+
+    lines
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, line)| diagnose_line(i, line))
+        .collect()
+}
+
+/// Render diagnostics the way a compiler would, quoting the line with a caret under the problem
+pub fn render_report(lines: &[String], diagnostics: &[Diagnostic]) -> String {
+    let mut report = String::new();
+    for diagnostic in diagnostics {
+        let line = &lines[diagnostic.line - 1];
+        let number = diagnostic.line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        report += &format!("error: {}\n", diagnostic);
+        report += &format!("{} |\n", gutter);
+        report += &format!("{} | {}\n", number, line);
+        report += &format!("{} | {}^\n", gutter, " ".repeat(diagnostic.column - 1));
+        if let DiagnosticKind::Incomplete { completion } = &diagnostic.kind {
+            report += &format!("{} = help: close with {:?}\n", gutter, completion);
+        }
+        report += "\n";
+    }
+    report
+}
+
+#[aoc_generator(day10)]
+pub(crate) fn program(input: &str) -> Vec<String> {
+    input.lines().map(|s| s.trim().to_string()).collect()
+}
+
+/// Whether any line is nested too deep to tell if it's corrupt or how to complete it
+fn any_too_deep(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.kind == DiagnosticKind::TooDeep)
+}
+
+/// Total score of the characters that corrupt lines, or None if a line is nested too deep
+fn syntax_error_score(diagnostics: Vec<Diagnostic>, table: &ScoreTable) -> Option<u64> {
+    if any_too_deep(&diagnostics) {
+        return None;
+    }
+    let score = diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.kind == DiagnosticKind::Corrupt)
+        .filter_map(|diagnostic| diagnostic.found)
+        .map(|found| table.corrupt.get(&found).copied().unwrap_or(0))
+        .sum();
+    Some(score)
+}
+
+fn checked_sum(scores: &[u64]) -> Option<u64> {
+    scores
+        .iter()
+        .try_fold(0u64, |acc, &score| acc.checked_add(score))
+}
+
+/// Score of the completions for incomplete lines, combined by `aggregation`. None if no lines are
+/// incomplete, a line is nested too deep, or a score is too large for a u64
+fn completion_score(
+    diagnostics: Vec<Diagnostic>,
+    table: &ScoreTable,
+    aggregation: Aggregation,
+) -> Option<u64> {
+    if any_too_deep(&diagnostics) {
+        return None;
+    }
+    let mut ac_scores = diagnostics
+        .into_iter()
+        .filter_map(|diagnostic| match diagnostic.kind {
+            DiagnosticKind::Incomplete { completion } => Some(completion),
+            DiagnosticKind::Corrupt | DiagnosticKind::TooDeep => None,
+        })
+        .map(|completion| {
+            completion.chars().try_fold(0u64, |acc, closing| {
+                acc.checked_mul(5)?
+                    .checked_add(table.autocomplete.get(&closing).copied().unwrap_or(0))
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if ac_scores.is_empty() {
+        return None;
+    }
+
+    match aggregation {
+        Aggregation::Median => {
+            ac_scores.sort();
+            Some(ac_scores[ac_scores.len() / 2])
+        }
+        Aggregation::Mean => Some(checked_sum(&ac_scores)? / ac_scores.len() as u64),
+        Aggregation::Sum => checked_sum(&ac_scores),
+    }
+}
+
+/// Part 1 scored with `table`, or None if a line is nested too deep
+pub fn syntax_error_score_with(lines: &[String], table: &ScoreTable) -> Option<u64> {
+    syntax_error_score(diagnose(lines), table)
+}
+
+/// Part 2 scored with `table` and combined by `aggregation`, or None if no lines are incomplete
+pub fn completion_score_with(
+    lines: &[String],
+    table: &ScoreTable,
+    aggregation: Aggregation,
+) -> Option<u64> {
+    completion_score(diagnose(lines), table, aggregation)
+}
+
+#[aoc(day10, part1)]
+pub(crate) fn part1(lines: &[String]) -> Option<u64> {
+    syntax_error_score_with(lines, &ScoreTable::default())
+}
+
+#[aoc(day10, part2)]
+pub(crate) fn part2(lines: &[String]) -> Option<u64> {
+    completion_score_with(lines, &ScoreTable::default(), Aggregation::Median)
+}
+
+#[cfg(feature = "parallel")]
+#[aoc(day10, part1, Rayon)]
+fn part1_rayon(lines: &[String]) -> Option<u64> {
+    syntax_error_score(diagnose_parallel(lines), &ScoreTable::default())
+}
+
+#[cfg(feature = "parallel")]
+#[aoc(day10, part2, Rayon)]
+fn part2_rayon(lines: &[String]) -> Option<u64> {
+    completion_score(
+        diagnose_parallel(lines),
+        &ScoreTable::default(),
+        Aggregation::Median,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let input = program(
+            r"[({(<(())[]>[[{[]{<()<>>
+{([(<{}[<>[]}>{[]{[(<()>
+x
+(((({<>}<{<{<>}{[]{[]{}",
+        );
+        let diagnostics = diagnose(&input);
+        assert_eq!(
+            diagnostics[1],
+            Diagnostic {
+                line: 2,
+                column: 13,
+                kind: DiagnosticKind::Corrupt,
+                found: Some('}'),
+                expected: Some(']'),
+            }
+        );
+
+        assert_eq!(
+            render_report(&input, &diagnostics),
+            r#"error: 1:25: expected '}', found end of line
+  |
+1 | [({(<(())[]>[[{[]{<()<>>
+  |                         ^
+  = help: close with "}}]])})]"
+
+error: 2:13: expected ']', found '}'
+  |
+2 | {([(<{}[<>[]}>{[]{[(<()>
+  |             ^
+
+error: 3:1: expected an opening character, found 'x'
+  |
+3 | x
+  | ^
+
+error: 4:24: expected '}', found end of line
+  |
+4 | (((({<>}<{<{<>}{[]{[]{}
+  |                        ^
+  = help: close with "}}>}>))))"
+
+"#
+        );
+    }
+
+    #[test]
+    fn custom_scores() {
+        let lines = program(include_str!("../../examples/2021/day10.txt"));
+        let table = ScoreTable::default();
+        assert_eq!(syntax_error_score_with(&lines, &table), Some(26397));
+        assert_eq!(
+            completion_score_with(&lines, &table, Aggregation::Median),
+            Some(288957)
+        );
+
+        let lines =
+            program("[({(<(())[]>[[{[]{<()<>>\n(((({<>}<{<{<>}{[]{[]{}\n<{([{{}}[<[[[<>{}]]]>[]]");
+        let scores: [u64; 3] = [288957, 1480781, 294];
+        assert_eq!(
+            completion_score_with(&lines, &table, Aggregation::Sum),
+            Some(scores.iter().sum())
+        );
+        assert_eq!(
+            completion_score_with(&lines, &table, Aggregation::Mean),
+            Some(scores.iter().sum::<u64>() / 3)
+        );
+
+        // Only angle brackets score, though everything after one still shifts its score along
+        let table = ScoreTable {
+            corrupt: HashMap::from([('>', 1)]),
+            autocomplete: HashMap::from([('>', 1)]),
+        };
+        let lines = program(include_str!("../../examples/2021/day10.txt"));
+        assert_eq!(syntax_error_score_with(&lines, &table), Some(1));
+        let lines = program("<{([\n(<\n[");
+        assert_eq!(
+            completion_score_with(&lines, &table, Aggregation::Sum),
+            Some(1 + 5)
+        );
+        assert_eq!(
+            completion_score_with(&lines, &ScoreTable::default(), Aggregation::Median),
+            Some(21)
+        );
+        assert_eq!(
+            completion_score_with(&program("()"), &table, Aggregation::Mean),
+            None
+        );
+    }
+
+    #[test]
+    fn unexpected_input() {
+        assert!(matches!(ChunkParser::parse(""), Err(SyntaxError::Empty)));
+        assert!(matches!(
+            ChunkParser::parse("x"),
+            Err(SyntaxError::Corrupt(0, 'x', _))
+        ));
+        assert!(matches!(
+            ChunkParser::parse("(é"),
+            Err(SyntaxError::Corrupt(_, 'é', ')'))
+        ));
+        assert!(matches!(
+            ChunkParser::parse("[é]"),
+            Err(SyntaxError::Corrupt(_, 'é', ']'))
+        ));
+
+        // Nested too deep, failing at the first chunk past the limit
+        let nested = |depth| format!("{}{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(ChunkParser::parse(&nested(MAX_NESTING)).is_ok());
+        assert!(matches!(
+            ChunkParser::parse(&nested(MAX_NESTING + 1)),
+            Err(SyntaxError::TooDeep(MAX_NESTING))
+        ));
+        let lines = program(&format!("{}\n(]", "(".repeat(100_000)));
+        let diagnostics = diagnose(&lines);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::TooDeep);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            format!("1:{}: chunks nested more than 1024 deep", MAX_NESTING + 1)
+        );
+        assert_eq!((part1(&lines), part2(&lines)), (None, None));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel() {
+        use crate::testing::Xorshift;
+
+        let mut rng = Xorshift::default();
+
+        // Random walks over the brackets, mostly left incomplete with the odd wrong closing. They're
+        // kept short enough for the completion scores to fit
+        let lines = (0..5000)
+            .map(|_| {
+                let mut open = Vec::new();
+                let mut line = String::new();
+                for _ in 0..1 + rng.next_u32() % 24 {
+                    let bracket = b"([{<"[rng.next_u32() as usize % 4] as char;
+                    if open.is_empty() || rng.next_u32() % 3 != 0 {
+                        open.push(bracket);
+                        line.push(bracket);
+                    } else if rng.next_u32() % 20 == 0 {
+                        line.push(closing_for(bracket));
+                    } else {
+                        line.push(closing_for(open.pop().unwrap()));
+                    }
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(diagnose_parallel(&lines), diagnose(&lines));
+        assert_eq!(part1_rayon(&lines), part1(&lines));
+        assert_eq!(part2_rayon(&lines), part2(&lines));
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day10.txt");
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(program(EXAMPLE), part1, part1_rayon);
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(program(EXAMPLE), part2, part2_rayon);
+    }
+}
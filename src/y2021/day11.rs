@@ -0,0 +1,554 @@
+use crate::error::ParseError;
+use crate::grid::parse_rows;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+type Octopus = i16;
+
+/// The board is stored row by row with a border of cells around it, so every octopus has all eight
+/// neighbors at the same offsets. Border cells stay at 0, which is the same as having already
+/// flashed, so a flash never spreads into them
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OctopusBoard {
+    cells: Vec<u8>,
+    rows: usize,
+    cols: usize,
+    /// Offsets from a cell to each of its neighbors
+    neighbors: [isize; 8],
+    /// Octopuses that have flashed and not yet energized their neighbors. Kept between steps so
+    /// stepping doesn't allocate
+    #[cfg_attr(feature = "serde", serde(skip))]
+    flash_stack: Vec<usize>,
+    flashes: u64,
+}
+
+impl std::fmt::Debug for OctopusBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f)?;
+            for octo in row.iter() {
+                write!(f, "{}", octo)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl OctopusBoard {
+    /// A board of the given energy levels, row by row. Panics if a level isn't from 0 to 9
+    pub fn with_octopuses(octos: Vec<Vec<Octopus>>) -> Self {
+        assert!(
+            octos.iter().flatten().all(|octo| (0..=9).contains(octo)),
+            "Energy levels go from 0 to 9"
+        );
+        let rows = octos.len();
+        let cols = octos.first().map_or(0, |row| row.len());
+        let stride = cols as isize + 2;
+
+        let mut cells = vec![0; (rows + 2) * (cols + 2)];
+        for (row, octos) in octos.iter().enumerate() {
+            let start = (row + 1) * (cols + 2) + 1;
+            for (cell, &octo) in cells[start..start + cols].iter_mut().zip(octos.iter()) {
+                *cell = octo as u8;
+            }
+        }
+
+        OctopusBoard {
+            cells,
+            rows,
+            cols,
+            neighbors: [
+                -stride - 1,
+                -stride,
+                -stride + 1,
+                -1,
+                1,
+                stride - 1,
+                stride,
+                stride + 1,
+            ],
+            flash_stack: Vec::new(),
+            flashes: 0,
+        }
+    }
+
+    /// Each row of the board, without the border
+    fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        let stride = self.cols + 2;
+        (1..=self.rows).map(move |row| &self.cells[row * stride + 1..row * stride + 1 + self.cols])
+    }
+
+    pub fn flashes(&self) -> u64 {
+        self.flashes
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.cells.iter().all(|&octo| octo == 0)
+    }
+
+    /// The board followed by its state after each of the next `steps` steps
+    pub fn history(&self, steps: usize) -> Vec<OctopusBoard> {
+        let mut board = self.clone();
+        let mut history = Vec::with_capacity(steps + 1);
+        history.push(board.clone());
+        for _ in 0..steps {
+            board.step();
+            history.push(board.clone());
+        }
+        history
+    }
+
+    /// Steps until every octopus first flashes together, and the number of steps before they do
+    /// it again. `None` if the board settles into a cycle without ever synchronizing
+    pub fn find_cycle(&self) -> Option<(u64, u64)> {
+        let (start, period) = self.cycle();
+
+        // Once the board is synchronized it comes back round, so it's somewhere in the cycle
+        let mut board = self.clone();
+        for step in 0..start + period {
+            if board.is_synchronized() {
+                return Some((step, period));
+            }
+            board.step();
+        }
+        None
+    }
+
+    /// Total flashes after `steps` steps, skipping over whole repeats of the board's cycle
+    pub fn flashes_after(&self, steps: u64) -> u64 {
+        let (start, period) = self.cycle();
+        let mut board = self.clone();
+        if steps < start + period {
+            board.advance(steps);
+            return board.flashes();
+        }
+
+        board.advance(start);
+        let before_cycle = board.flashes();
+        board.advance(period);
+        let per_cycle = board.flashes() - before_cycle;
+
+        let (cycles, rest) = ((steps - start) / period, (steps - start) % period);
+        let mut board = self.clone();
+        board.advance(start + rest);
+        board.flashes() + cycles * per_cycle
+    }
+
+    fn advance(&mut self, steps: u64) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cells.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn same_state(&self, other: &OctopusBoard) -> bool {
+        self.state_hash() == other.state_hash() && self.cells == other.cells
+    }
+
+    /// (steps before the board starts repeating, length of the repeat) using Brent's algorithm
+    fn cycle(&self) -> (u64, u64) {
+        let mut power = 1;
+        let mut period = 1;
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.step();
+        while !tortoise.same_state(&hare) {
+            if power == period {
+                tortoise = hare.clone();
+                power *= 2;
+                period = 0;
+            }
+            hare.step();
+            period += 1;
+        }
+
+        // Walk two boards a period apart until they meet at the start of the cycle
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.advance(period);
+        let mut start = 0;
+        while !tortoise.same_state(&hare) {
+            tortoise.step();
+            hare.step();
+            start += 1;
+        }
+
+        (start, period)
+    }
+
+    pub fn step(&mut self) {
+        self.energize_all();
+        self.spread_flashes(|_, _| {});
+    }
+
+    /// Increase the energy level of all octopuses by 1
+    fn energize_all(&mut self) {
+        let stride = self.cols + 2;
+        for row in 1..=self.rows {
+            for cell in row * stride + 1..row * stride + 1 + self.cols {
+                self.increment_octo(cell);
+            }
+        }
+    }
+
+    /// Flash all octopuses with an energy level >9. Adjacent octopuses that haven't flashed yet
+    /// get energized, and may flash in turn. A flash next to the edge calls `off_edge` with
+    /// the (row, col) of each border cell it reaches, which is -1 or one past the end on that side
+    fn spread_flashes(&mut self, mut off_edge: impl FnMut(isize, isize)) {
+        let stride = self.cols + 2;
+        while let Some(cell) = self.flash_stack.pop() {
+            for offset in self.neighbors {
+                let neighbor = cell.wrapping_add_signed(offset);
+                if self.cells[neighbor] != 0 {
+                    self.increment_octo(neighbor);
+                    continue;
+                }
+
+                // Either it has already flashed or it's on the border
+                let (row, col) = (neighbor / stride, neighbor % stride);
+                if row == 0 || row > self.rows || col == 0 || col > self.cols {
+                    off_edge(row as isize - 1, col as isize - 1);
+                }
+            }
+        }
+    }
+
+    /// Give the octopus at (row, col) one more energy, unless it has already flashed this step
+    fn energize(&mut self, row: usize, col: usize) {
+        let cell = (row + 1) * (self.cols + 2) + col + 1;
+        if self.cells[cell] != 0 {
+            self.increment_octo(cell);
+        }
+    }
+
+    fn increment_octo(&mut self, cell: usize) {
+        let octo = &mut self.cells[cell];
+        if *octo == 9 {
+            *octo = 0;
+            self.flashes += 1;
+            self.flash_stack.push(cell);
+        } else {
+            *octo += 1;
+        }
+    }
+}
+
+/// Boards of the same size laid edge to edge in a grid, so a flash next to the edge of one board
+/// spreads to the octopuses across the edge on the boards around it, diagonally included
+#[derive(Debug, Clone)]
+pub struct CoupledBoards {
+    /// Row by row, `tile_cols` to a row
+    boards: Vec<OctopusBoard>,
+    tile_cols: usize,
+}
+
+impl CoupledBoards {
+    /// Lay out `boards` row by row, `tile_cols` boards to a row. Panics if there are no boards, if
+    /// they don't fill whole rows or if they aren't all the same size
+    pub fn new(boards: Vec<OctopusBoard>, tile_cols: usize) -> Self {
+        assert!(!boards.is_empty(), "Need at least one board");
+        assert!(
+            tile_cols > 0 && boards.len().is_multiple_of(tile_cols),
+            "Boards must fill whole rows"
+        );
+        let size = boards.first().map(|board| (board.rows, board.cols));
+        assert!(
+            boards
+                .iter()
+                .all(|board| Some((board.rows, board.cols)) == size),
+            "Boards must all be the same size"
+        );
+        CoupledBoards { boards, tile_cols }
+    }
+
+    /// The board in row `row` and column `col` of the tiling
+    pub fn board(&self, row: usize, col: usize) -> &OctopusBoard {
+        &self.boards[row * self.tile_cols + col]
+    }
+
+    pub fn flashes(&self) -> u64 {
+        self.boards.iter().map(OctopusBoard::flashes).sum()
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.boards.iter().all(OctopusBoard::is_synchronized)
+    }
+
+    pub fn step(&mut self) {
+        self.boards.iter_mut().for_each(OctopusBoard::energize_all);
+
+        // Each board flashes as far as its own edges, then the flashes that reached across them
+        // energize the boards on the other side, which may flash in turn
+        let (rows, cols) = (self.boards[0].rows as isize, self.boards[0].cols as isize);
+        let tile_rows = (self.boards.len() / self.tile_cols) as isize;
+        let tile_cols = self.tile_cols as isize;
+        let mut crossed = Vec::new();
+        loop {
+            for (i, board) in self.boards.iter_mut().enumerate() {
+                let (tile_row, tile_col) = (i as isize / tile_cols, i as isize % tile_cols);
+                board.spread_flashes(|row, col| {
+                    crossed.push((tile_row * rows + row, tile_col * cols + col))
+                });
+            }
+            if crossed.is_empty() {
+                break;
+            }
+
+            for (row, col) in crossed.drain(..) {
+                if row < 0 || row >= tile_rows * rows || col < 0 || col >= tile_cols * cols {
+                    continue;
+                }
+                let board = ((row / rows) * tile_cols + col / cols) as usize;
+                self.boards[board].energize((row % rows) as usize, (col % cols) as usize);
+            }
+        }
+    }
+}
+
+/// Rows of single digit energy levels, all as long as the first
+#[aoc_generator(day11)]
+pub(crate) fn octopuses(input: &str) -> Result<OctopusBoard, ParseError> {
+    let board = parse_rows(input, |c| {
+        c.to_digit(10)
+            .map(|d| d as Octopus)
+            .ok_or_else(|| format!("invalid energy level {:?}", c))
+    })?;
+
+    Ok(OctopusBoard::with_octopuses(board))
+}
+
+const N_STEPS: usize = 100;
+
+/// Upper bound on the number of frames rendered, in case the board never synchronizes
+#[cfg(feature = "viz")]
+const MAX_FRAMES: usize = 1000;
+
+#[cfg(feature = "viz")]
+fn render(octo_board: &OctopusBoard) -> crate::viz::Canvas {
+    use crate::viz::{gray, Canvas, YELLOW};
+
+    let mut canvas = Canvas::new(octo_board.cols, octo_board.rows);
+    for (row, octos) in octo_board.rows().enumerate() {
+        for (col, &octo) in octos.iter().enumerate() {
+            if octo == 0 {
+                canvas.set(col, row, '*', YELLOW);
+            } else {
+                let c = std::char::from_digit(octo as u32, 10).unwrap();
+                canvas.set(col, row, c, gray(octo as usize, 12));
+            }
+        }
+    }
+    canvas
+}
+
+/// One frame per board, e.g. from `OctopusBoard::history`
+#[cfg(feature = "viz")]
+pub fn render_history(history: &[OctopusBoard]) -> Vec<crate::viz::Canvas> {
+    history.iter().map(render).collect()
+}
+
+/// One frame for the starting board and one after each step, up until every octopus flashes at once
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    let mut octo_board = match octopuses(input) {
+        Ok(board) => board,
+        Err(_) => return Vec::new(),
+    };
+    let mut frames = vec![render(&octo_board)];
+    while !octo_board.is_synchronized() && frames.len() < MAX_FRAMES {
+        octo_board.step();
+        frames.push(render(&octo_board));
+    }
+    frames
+}
+
+#[aoc(day11, part1)]
+pub(crate) fn part1(octo_board: &OctopusBoard) -> u64 {
+    let mut octo_board = octo_board.clone();
+    for _ in 0..N_STEPS {
+        octo_board.step();
+    }
+
+    octo_board.flashes()
+}
+
+/// None if the octopuses never all flash at once
+#[aoc(day11, part2)]
+pub(crate) fn part2(octo_board: &OctopusBoard) -> Option<u64> {
+    octo_board.find_cycle().map(|(first_sync, _)| first_sync)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    #[test]
+    fn history() {
+        let input = octopuses("11111\n19991\n19191\n19991\n11111").unwrap();
+        let history = input.history(2);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].flashes(), 0);
+        assert_eq!(history[1].flashes(), 9);
+        assert_eq!(history[2].flashes(), 9);
+    }
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day11.txt");
+
+    #[test]
+    fn cycle() {
+        let board = octopuses(EXAMPLE).unwrap();
+        assert_eq!(board.find_cycle(), Some((195, 10)));
+
+        // Once synchronized the board stays that way
+        let board = octopuses("000\n000\n000").unwrap();
+        assert_eq!(board.find_cycle(), Some((0, 10)));
+
+        // Two octopuses next to each other that never flash together
+        let board = octopuses("5\n1").unwrap();
+        assert_eq!(board.find_cycle(), None);
+        assert_eq!(part2(&board), None);
+    }
+
+    #[test]
+    fn extrapolate_flashes() {
+        let board = octopuses(EXAMPLE).unwrap();
+        for steps in [0, 10, 100, 194, 195, 196, 250] {
+            let mut simulated = board.clone();
+            simulated.advance(steps);
+            assert_eq!(board.flashes_after(steps), simulated.flashes());
+        }
+
+        // Every octopus flashes once per cycle after they synchronize
+        let steps = 1_000_000_000_007;
+        let mut at_sync = board.clone();
+        at_sync.advance(195 + 2);
+        let cycles = (steps - 195) / 10;
+        assert_eq!(board.flashes_after(steps), at_sync.flashes() + cycles * 100);
+    }
+
+    #[test]
+    fn rectangular() {
+        let mut rng = Xorshift::default();
+
+        // Flashing doesn't care which way round the board is, so a board and its transpose
+        // flash the same number of times
+        for (rows, cols) in [(1, 7), (3, 5), (8, 2)] {
+            let octos = (0..rows)
+                .map(|_| {
+                    (0..cols)
+                        .map(|_| (rng.next_u32() % 10) as Octopus)
+                        .collect()
+                })
+                .collect::<Vec<Vec<_>>>();
+            let transposed = (0..cols)
+                .map(|col| octos.iter().map(|row| row[col]).collect())
+                .collect();
+
+            let mut board = OctopusBoard::with_octopuses(octos);
+            let mut transposed = OctopusBoard::with_octopuses(transposed);
+            for _ in 0..50 {
+                board.step();
+                transposed.step();
+                assert_eq!(board.flashes(), transposed.flashes());
+                assert_eq!(board.is_synchronized(), transposed.is_synchronized());
+            }
+        }
+    }
+
+    #[test]
+    fn coupled() {
+        let example = || octopuses(EXAMPLE).unwrap();
+
+        // Two copies of the example, one above the other, are one board twice as tall
+        let mut coupled = CoupledBoards::new(vec![example(), example()], 1);
+        let mut tall = octopuses(&format!("{}\n{}", EXAMPLE, EXAMPLE)).unwrap();
+        let mut apart = example();
+        let mut diverged = false;
+        for _ in 0..N_STEPS {
+            coupled.step();
+            tall.step();
+            apart.step();
+            assert_eq!(coupled.flashes(), tall.flashes());
+            diverged |= coupled.flashes() != 2 * apart.flashes();
+        }
+        // Flashes spill across the seam, so the pair don't flash the same as two boards apart
+        assert!(diverged);
+        assert_ne!(coupled.flashes(), 2 * part1(&example()));
+        assert_ne!(
+            format!("{:?}", coupled.board(0, 0)),
+            format!("{:?}", coupled.board(1, 0))
+        );
+
+        // One board alone is the same as not coupling it
+        let mut alone = CoupledBoards::new(vec![example()], 1);
+        for _ in 0..N_STEPS {
+            alone.step();
+        }
+        assert_eq!(alone.flashes(), part1(&example()));
+
+        let mut rng = Xorshift::default();
+
+        // A 2x3 tiling of small boards against the one board they make up
+        let (rows, cols) = (3, 4);
+        let world = (0..2 * rows)
+            .map(|_| {
+                (0..3 * cols)
+                    .map(|_| (rng.next_u32() % 10) as Octopus)
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        let tiles = (0..6)
+            .map(|i| {
+                let (tile_row, tile_col) = (i / 3, i % 3);
+                OctopusBoard::with_octopuses(
+                    world[tile_row * rows..(tile_row + 1) * rows]
+                        .iter()
+                        .map(|row| row[tile_col * cols..(tile_col + 1) * cols].to_vec())
+                        .collect(),
+                )
+            })
+            .collect();
+        let mut coupled = CoupledBoards::new(tiles, 3);
+        let mut whole = OctopusBoard::with_octopuses(world);
+        for _ in 0..200 {
+            coupled.step();
+            whole.step();
+            assert_eq!(coupled.flashes(), whole.flashes());
+            assert_eq!(coupled.is_synchronized(), whole.is_synchronized());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least one board")]
+    fn no_boards() {
+        CoupledBoards::new(vec![], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Energy levels go from 0 to 9")]
+    fn energy_out_of_range() {
+        OctopusBoard::with_octopuses(vec![vec![1, 2], vec![10, 3]]);
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn render_steps() {
+        let frames = render_frames("11111\n19991\n19191\n19991\n11111");
+        assert_eq!(frames[0].to_ascii(), "11111\n19991\n19191\n19991\n11111\n");
+        assert_eq!(frames[1].to_ascii(), "34543\n4***4\n5***5\n4***4\n34543\n");
+        assert!(frames
+            .last()
+            .unwrap()
+            .to_ascii()
+            .chars()
+            .all(|c| c == '*' || c == '\n'));
+    }
+}
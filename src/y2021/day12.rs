@@ -0,0 +1,773 @@
+use crate::error::ParseError;
+use crate::progress::{Context, Silent};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub enum Cave {
+    Large(String),
+    Small(String),
+    Start,
+    End,
+}
+
+impl std::str::FromStr for Cave {
+    type Err = std::string::ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.chars().all(char::is_uppercase) {
+            Ok(Cave::Large(s.to_string()))
+        } else if s == "start" {
+            Ok(Cave::Start)
+        } else if s == "end" {
+            Ok(Cave::End)
+        } else {
+            Ok(Cave::Small(s.to_string()))
+        }
+    }
+}
+
+impl std::fmt::Debug for Cave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match &self {
+            Cave::Large(s) | Cave::Small(s) => s,
+            Cave::Start => "start",
+            Cave::End => "end",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Caves serialize as their names so they can be used as map keys
+#[cfg(feature = "serde")]
+impl serde::Serialize for Cave {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:?}", self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Cave {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(name.parse().unwrap())
+    }
+}
+
+/// What to do with a passage from a large cave back to itself. It could be taken forever, so paths
+/// through it can't be counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfLoops {
+    Ignore,
+    Reject,
+}
+
+/// A large cave had a passage back to itself with `SelfLoops::Reject`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfLoopError(pub Cave);
+
+impl std::fmt::Display for SelfLoopError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "large cave {:?} has a passage to itself", self.0)
+    }
+}
+
+impl std::error::Error for SelfLoopError {}
+
+/// Which caves a path may go back into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitPolicy {
+    /// The visit limits `find_paths` counts paths with
+    Limits,
+    /// The rule `find_paths2` counts paths with, where one small cave may be visited twice
+    OneSmallTwice,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaveGraph {
+    adj_list: HashMap<Cave, Vec<Cave>>,
+    visited_twice: Option<Cave>,
+    visit_limits: HashMap<Cave, usize>,
+}
+
+impl CaveGraph {
+    /// Graph where each pair of caves is connected both ways. Repeated passages are only counted
+    /// once and passages from a cave to itself are dropped
+    pub fn with_caves(caves: Vec<(Cave, Cave)>) -> Self {
+        CaveGraph::try_with_caves(caves, SelfLoops::Ignore).expect("self-loops are ignored")
+    }
+
+    pub fn try_with_caves(
+        caves: Vec<(Cave, Cave)>,
+        self_loops: SelfLoops,
+    ) -> Result<Self, SelfLoopError> {
+        let backwards = caves.iter().map(|(a, b)| (b.clone(), a.clone())).collect();
+        CaveGraph::build([caves, backwards].concat(), self_loops)
+    }
+
+    /// Graph where each pair (a, b) is a passage that can only be taken from a to b
+    pub fn with_directed_caves(caves: Vec<(Cave, Cave)>) -> Self {
+        CaveGraph::try_with_directed_caves(caves, SelfLoops::Ignore)
+            .expect("self-loops are ignored")
+    }
+
+    pub fn try_with_directed_caves(
+        caves: Vec<(Cave, Cave)>,
+        self_loops: SelfLoops,
+    ) -> Result<Self, SelfLoopError> {
+        CaveGraph::build(caves, self_loops)
+    }
+
+    fn build(passages: Vec<(Cave, Cave)>, self_loops: SelfLoops) -> Result<Self, SelfLoopError> {
+        let mut adj_list = HashMap::<Cave, Vec<Cave>>::new();
+        for (a, b) in passages.into_iter() {
+            if a == b {
+                // Only large caves can be re-entered, a loop on anything else can never be taken
+                if matches!(a, Cave::Large(_)) && self_loops == SelfLoops::Reject {
+                    return Err(SelfLoopError(a));
+                }
+                continue;
+            }
+
+            let connections = adj_list.entry(a).or_default();
+            if !connections.contains(&b) {
+                connections.push(b);
+            }
+        }
+
+        Ok(CaveGraph {
+            adj_list,
+            visited_twice: None,
+            visit_limits: HashMap::new(),
+        })
+    }
+
+    /// Override how many times `find_paths` may visit each cave. By default small caves can be
+    /// visited once and large caves any number of times. `find_paths2` always uses the default
+    /// limits, since its one small cave visited twice is its own rule
+    pub fn with_visit_limits(mut self, limits: HashMap<Cave, usize>) -> Self {
+        for (cave, limit) in limits.into_iter() {
+            if limit == 0 {
+                self.remove_cave(&cave);
+            } else {
+                self.visit_limits.insert(cave, limit);
+            }
+        }
+        self
+    }
+
+    pub fn find_paths(&self) -> u32 {
+        self.find_paths_progress(&mut Context::new(12, &mut Silent))
+    }
+
+    pub fn find_paths2(&self) -> u32 {
+        self.find_paths2_progress(&mut Context::new(12, &mut Silent))
+    }
+
+    /// `find_paths`, reporting each path found into `ctx`
+    pub fn find_paths_progress(&self, ctx: &mut Context) -> u32 {
+        self.find_path_from(&Cave::Start, ctx)
+    }
+
+    /// `find_paths2`, reporting each path found into `ctx`
+    pub fn find_paths2_progress(&self, ctx: &mut Context) -> u32 {
+        self.find_path_from2(&Cave::Start, ctx)
+    }
+
+    /// Same as `find_paths`, or `find_paths2` if `twice`, but counted by a search that remembers
+    /// how many paths lead on from each cave with each set of small caves already visited. The
+    /// visited caves are kept as a bitmask, so there can be at most 64 small caves. Only the default
+    /// visit limits are used
+    pub fn count_paths_bitmask(&self, twice: bool) -> u32 {
+        let mut caves = self.adj_list.keys().collect::<Vec<_>>();
+        caves.extend(self.adj_list.values().flatten());
+        caves.push(&Cave::Start);
+        caves.sort_by_key(|cave| format!("{:?}", cave));
+        caves.dedup();
+        let index = |cave: &Cave| caves.iter().position(|&c| c == cave).unwrap();
+
+        let small = caves
+            .iter()
+            .filter(|cave| matches!(cave, Cave::Small(_) | Cave::Start))
+            .collect::<Vec<_>>();
+        assert!(small.len() <= 64, "too many small caves for a bitmask");
+        let bits = caves
+            .iter()
+            .map(|cave| {
+                small
+                    .iter()
+                    .position(|&small| small == cave)
+                    .map_or(0, |bit| 1u64 << bit)
+            })
+            .collect::<Vec<_>>();
+        let neighbors = caves
+            .iter()
+            .map(|&cave| self.neighbors(cave).iter().map(index).collect())
+            .collect::<Vec<Vec<_>>>();
+
+        let search = BitmaskSearch {
+            neighbors,
+            bits,
+            start: index(&Cave::Start),
+            end: caves.iter().position(|&c| *c == Cave::End),
+        };
+        search.count(search.start, 0, twice, &mut HashMap::new())
+    }
+
+    /// Number of paths from start to end that `policy` allows, by how many caves they pass through
+    /// counting start and end. The paths are counted the same way as `find_paths` and
+    /// `find_paths2`, without building any of them
+    pub fn path_length_histogram(&self, policy: VisitPolicy) -> BTreeMap<usize, u64> {
+        let mut histogram = BTreeMap::new();
+        self.count_lengths_from(&Cave::Start, policy, 1, &mut histogram);
+        histogram
+    }
+
+    /// Add the paths from `cave` to end into `histogram`, with `length` caves on the way to it
+    /// including `cave` itself
+    fn count_lengths_from(
+        &self,
+        cave: &Cave,
+        policy: VisitPolicy,
+        length: usize,
+        histogram: &mut BTreeMap<usize, u64>,
+    ) {
+        if *cave == Cave::End {
+            // As in `find_path_from2`, a path that could have visited a cave twice but didn't was
+            // already counted without the allowance
+            if let Some(twice_cave) = &self.visited_twice {
+                if self.adj_list.contains_key(twice_cave) {
+                    return;
+                }
+            }
+            *histogram.entry(length).or_default() += 1;
+            return;
+        }
+
+        let mut next_graph = self.clone();
+        match policy {
+            VisitPolicy::Limits => next_graph.visit(cave),
+            VisitPolicy::OneSmallTwice => {
+                if matches!(cave, Cave::Start | Cave::Small(_)) {
+                    next_graph.remove_cave(cave);
+                }
+                if matches!(cave, Cave::Small(_)) && self.visited_twice.is_none() {
+                    let mut twice_graph = self.clone();
+                    twice_graph.visited_twice = Some(cave.clone());
+                    for next in self.neighbors(cave) {
+                        twice_graph.count_lengths_from(next, policy, length + 1, histogram);
+                    }
+                }
+            }
+        }
+
+        for next in self.neighbors(cave) {
+            next_graph.count_lengths_from(next, policy, length + 1, histogram);
+        }
+    }
+
+    /// The path from start to end through the most caves that `policy` allows, or None if end
+    /// can't be reached. Like counting paths, this never finishes if two large caves that can be
+    /// visited any number of times are connected
+    pub fn longest_path(&self, policy: VisitPolicy) -> Option<Vec<Cave>> {
+        let mut path = self.longest_path_from(&Cave::Start, policy)?;
+        path.reverse();
+        Some(path)
+    }
+
+    /// Longest path from `cave` to end, in reverse
+    fn longest_path_from(&self, cave: &Cave, policy: VisitPolicy) -> Option<Vec<Cave>> {
+        if *cave == Cave::End {
+            return Some(vec![Cave::End]);
+        }
+        // Nothing past here can get to the end, so don't walk every way around it
+        if !self.reaches_end(cave) {
+            return None;
+        }
+
+        let mut next_graphs = Vec::new();
+        let mut next_graph = self.clone();
+        match policy {
+            VisitPolicy::Limits => next_graph.visit(cave),
+            VisitPolicy::OneSmallTwice => {
+                if matches!(cave, Cave::Start | Cave::Small(_)) {
+                    next_graph.remove_cave(cave);
+                }
+                if matches!(cave, Cave::Small(_)) && self.visited_twice.is_none() {
+                    let mut twice_graph = self.clone();
+                    twice_graph.visited_twice = Some(cave.clone());
+                    next_graphs.push(twice_graph);
+                }
+            }
+        }
+        next_graphs.push(next_graph);
+
+        let mut longest = next_graphs
+            .iter()
+            .flat_map(|next_graph| {
+                self.neighbors(cave)
+                    .iter()
+                    .filter_map(|next| next_graph.longest_path_from(next, policy))
+            })
+            .reduce(|longest, path| {
+                if path.len() > longest.len() {
+                    path
+                } else {
+                    longest
+                }
+            })?;
+        longest.push(cave.clone());
+        Some(longest)
+    }
+
+    fn reaches_end(&self, cave: &Cave) -> bool {
+        let mut seen = HashSet::from([cave]);
+        let mut stack = vec![cave];
+        while let Some(cave) = stack.pop() {
+            if *cave == Cave::End {
+                return true;
+            }
+            for next in self.neighbors(cave) {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    fn neighbors(&self, cave: &Cave) -> &[Cave] {
+        // Caves with no way out of them only show up in directed graphs
+        self.adj_list
+            .get(cave)
+            .map_or(&[], |caves| caves.as_slice())
+    }
+
+    fn find_path_from2(&self, cave: &Cave, ctx: &mut Context) -> u32 {
+        if *cave == Cave::End {
+            // If we allowed visiting twice but didn't, this path was already hit
+            if let Some(twice_cave) = &self.visited_twice {
+                if self.adj_list.contains_key(twice_cave) {
+                    return 0;
+                }
+            }
+
+            ctx.tick("paths");
+            return 1;
+        }
+
+        let visit_neighbors_on = |next_graph: Self, ctx: &mut Context| {
+            self.neighbors(cave)
+                .iter()
+                .map(|next| next_graph.find_path_from2(next, ctx))
+                .sum::<u32>()
+        };
+
+        let mut next_graph = self.clone();
+        if cave == &Cave::Start {
+            next_graph.remove_cave(cave);
+        } else if matches!(cave, &Cave::Small(_)) {
+            next_graph.remove_cave(cave);
+            if self.visited_twice.is_none() {
+                let mut sm_twice_graph = self.clone();
+                sm_twice_graph.visited_twice = Some(cave.clone());
+                return visit_neighbors_on(sm_twice_graph, ctx)
+                    + visit_neighbors_on(next_graph, ctx);
+            }
+        }
+
+        visit_neighbors_on(next_graph, ctx)
+    }
+
+    fn find_path_from(&self, cave: &Cave, ctx: &mut Context) -> u32 {
+        if *cave == Cave::End {
+            ctx.tick("paths");
+            return 1;
+        }
+
+        let mut next_graph = self.clone();
+        next_graph.visit(cave);
+
+        self.neighbors(cave)
+            .iter()
+            .map(|next| next_graph.find_path_from(next, ctx))
+            .sum()
+    }
+
+    /// Use up one of the visits to cave, removing it from the graph once it can't be visited again
+    fn visit(&mut self, cave: &Cave) {
+        let default_limit = match cave {
+            Cave::Small(_) | Cave::Start => Some(1),
+            Cave::Large(_) | Cave::End => None,
+        };
+
+        match self.visit_limits.get(cave).copied().or(default_limit) {
+            Some(limit) if limit <= 1 => self.remove_cave(cave),
+            Some(limit) => {
+                self.visit_limits.insert(cave.clone(), limit - 1);
+            }
+            None => {}
+        }
+    }
+
+    fn remove_cave(&mut self, cave: &Cave) {
+        // Passages into the cave may come from caves it has no passage back to, so check them all
+        self.adj_list.remove(cave);
+        for connections in self.adj_list.values_mut() {
+            connections.retain(|c| c != cave);
+        }
+    }
+}
+
+/// The caves numbered for `CaveGraph::count_paths_bitmask`
+struct BitmaskSearch {
+    neighbors: Vec<Vec<usize>>,
+    /// The bit for each small cave, or 0 for caves that can be visited any number of times
+    bits: Vec<u64>,
+    start: usize,
+    end: Option<usize>,
+}
+
+impl BitmaskSearch {
+    /// Paths from `cave` to the end, having visited the small caves in `visited`, and with one
+    /// small cave still allowed a second visit if `twice`
+    fn count(
+        &self,
+        cave: usize,
+        visited: u64,
+        twice: bool,
+        cache: &mut HashMap<(usize, u64, bool), u32>,
+    ) -> u32 {
+        if Some(cave) == self.end {
+            return 1;
+        }
+        if let Some(&paths) = cache.get(&(cave, visited, twice)) {
+            return paths;
+        }
+
+        let visited_here = visited | self.bits[cave];
+        let mut paths = 0;
+        for &next in self.neighbors[cave].iter() {
+            if visited_here & self.bits[next] == 0 {
+                paths += self.count(next, visited_here, twice, cache);
+            } else if twice && next != self.start {
+                paths += self.count(next, visited_here, false, cache);
+            }
+        }
+        cache.insert((cave, visited, twice), paths);
+        paths
+    }
+}
+
+/// One `a-b` tunnel per line. Two large caves can't be joined, since a path could go back and forth
+/// between them forever
+#[aoc_generator(day12)]
+pub(crate) fn parse_adj_list(input: &str) -> Result<CaveGraph, ParseError> {
+    let mut adj_vec = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let (a, b) = match line.split_once('-') {
+            Some((a, b)) => (a.parse::<Cave>().unwrap(), b.parse::<Cave>().unwrap()),
+            None => continue,
+        };
+        if a != b && matches!((&a, &b), (Cave::Large(_), Cave::Large(_))) {
+            return Err(ParseError::at_line(
+                i + 1,
+                format!("large caves {:?} and {:?} are joined", a, b),
+            ));
+        }
+        adj_vec.push((a, b));
+    }
+
+    Ok(CaveGraph::with_caves(adj_vec))
+}
+
+#[aoc(day12, part1)]
+pub(crate) fn part1(caves: &CaveGraph) -> u32 {
+    caves.find_paths()
+}
+
+#[aoc(day12, part2)]
+pub(crate) fn part2(caves: &CaveGraph) -> u32 {
+    caves.find_paths2()
+}
+
+#[aoc(day12, part1, Bitmask)]
+fn part1_bitmask(caves: &CaveGraph) -> u32 {
+    caves.count_paths_bitmask(false)
+}
+
+#[aoc(day12, part2, Bitmask)]
+fn part2_bitmask(caves: &CaveGraph) -> u32 {
+    caves.count_paths_bitmask(true)
+}
+
+pub(crate) fn part1_progress(caves: &CaveGraph, ctx: &mut Context) -> u32 {
+    caves.find_paths_progress(ctx)
+}
+
+pub(crate) fn part2_progress(caves: &CaveGraph, ctx: &mut Context) -> u32 {
+    caves.find_paths2_progress(ctx)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn small_example() {
+        let input = parse_adj_list(
+            r"start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end",
+        )
+        .unwrap();
+        assert_eq!(part1(&input), 10);
+        assert_eq!(part2(&input), 36);
+    }
+
+    #[test]
+    fn joined_large_caves() {
+        let err = parse_adj_list("start-A\nA-B\nB-end").unwrap_err();
+        assert_eq!(err.line(), 2);
+
+        // A passage from a cave to itself is dropped, so it can't be taken over and over
+        let caves = parse_adj_list("start-A\nA-A\nA-end").unwrap();
+        assert_eq!(part1(&caves), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let caves = parse_adj_list("start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end").unwrap();
+        let json = serde_json::to_string(&caves).unwrap();
+        let caves: CaveGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(caves.find_paths(), 10);
+        assert_eq!(caves.find_paths2(), 36);
+    }
+
+    fn edges(input: &str) -> Vec<(Cave, Cave)> {
+        input
+            .split_whitespace()
+            .filter_map(|line| line.split_once('-'))
+            .map(|(a, b)| (a.parse::<Cave>().unwrap(), b.parse::<Cave>().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn directed() {
+        let caves = edges("start-A A-b b-end A-end start-b");
+        assert_eq!(CaveGraph::with_caves(caves.clone()).find_paths(), 5);
+        assert_eq!(
+            CaveGraph::with_directed_caves(caves.clone()).find_paths(),
+            3
+        );
+        assert_eq!(
+            CaveGraph::with_directed_caves(caves.clone()).count_paths_bitmask(false),
+            3
+        );
+
+        let limits = HashMap::from([(Cave::Small("b".to_string()), 2)]);
+        let graph = CaveGraph::with_directed_caves(caves.clone()).with_visit_limits(limits.clone());
+        assert_eq!(graph.find_paths(), 3);
+        let graph = CaveGraph::with_caves(caves).with_visit_limits(limits);
+        assert_eq!(graph.find_paths(), 9);
+    }
+
+    #[test]
+    fn normalized() {
+        let caves = edges("start-A start-b A-c A-b b-d A-end b-end");
+
+        // Repeating a passage, in either direction, doesn't add paths
+        let repeated = [caves.clone(), edges("A-b b-A start-A")].concat();
+        let graph = CaveGraph::with_caves(repeated.clone());
+        assert_eq!(graph.find_paths(), 10);
+        assert_eq!(graph.find_paths2(), 36);
+        let graph = CaveGraph::with_directed_caves([caves.clone(), edges("A-b start-A")].concat());
+        assert_eq!(
+            graph.find_paths(),
+            CaveGraph::with_directed_caves(caves.clone()).find_paths()
+        );
+
+        // A large cave looping back to itself used to recurse forever
+        let looped = [caves.clone(), edges("A-A b-b")].concat();
+        let graph = CaveGraph::with_caves(looped.clone());
+        assert_eq!(graph.find_paths(), 10);
+        assert_eq!(graph.find_paths2(), 36);
+        assert_eq!(
+            CaveGraph::try_with_caves(looped.clone(), SelfLoops::Reject).unwrap_err(),
+            SelfLoopError(Cave::Large("A".to_string()))
+        );
+        assert!(CaveGraph::try_with_directed_caves(looped, SelfLoops::Reject).is_err());
+
+        // Small caves can't be re-entered, so their loops are always dropped
+        let looped = [caves, edges("b-b")].concat();
+        assert!(CaveGraph::try_with_caves(looped, SelfLoops::Reject).is_ok());
+    }
+
+    /// Check `path` only takes passages in `caves` and visits small caves at most `twice` + 1 times
+    fn check_path(caves: &[(Cave, Cave)], path: &[Cave], twice: bool) {
+        assert_eq!(path.first(), Some(&Cave::Start));
+        assert_eq!(path.last(), Some(&Cave::End));
+        for step in path.windows(2) {
+            assert!(caves
+                .iter()
+                .any(|(a, b)| (a, b) == (&step[0], &step[1]) || (b, a) == (&step[0], &step[1])));
+        }
+
+        let mut visits = HashMap::new();
+        for cave in path.iter().filter(|cave| !matches!(cave, Cave::Large(_))) {
+            *visits.entry(cave).or_insert(0) += 1;
+        }
+        let repeats = visits.values().filter(|&&n| n > 1).count();
+        assert!(visits.values().all(|&n| n <= 2));
+        assert!(repeats <= twice as usize);
+        assert_eq!(visits[&Cave::Start], 1);
+    }
+
+    #[test]
+    fn longest() {
+        for input in [
+            "start-A start-b A-c A-b b-d A-end b-end",
+            "dc-end HN-start start-kj dc-start dc-HN LN-dc HN-end kj-sa kj-HN kj-dc",
+        ] {
+            let caves = edges(input);
+            let graph = CaveGraph::with_caves(caves.clone());
+
+            let path = graph.longest_path(VisitPolicy::Limits).unwrap();
+            check_path(&caves, &path, false);
+            assert_eq!(path.len(), 7, "{:?}", path);
+
+            let path = graph.longest_path(VisitPolicy::OneSmallTwice).unwrap();
+            check_path(&caves, &path, true);
+            assert_eq!(path.len(), 9, "{:?}", path);
+        }
+
+        let caves = edges("start-A start-b A-c A-b b-d A-end b-end");
+        let graph = CaveGraph::with_caves(caves.clone())
+            .with_visit_limits(HashMap::from([(Cave::Small("b".to_string()), 3)]));
+        assert_eq!(graph.longest_path(VisitPolicy::Limits).unwrap().len(), 11);
+
+        let graph = CaveGraph::with_directed_caves(edges("start-A A-b b-A A-end"));
+        assert_eq!(
+            graph.longest_path(VisitPolicy::Limits),
+            Some(
+                edges("start-A A-b b-A A-end")
+                    .into_iter()
+                    .map(|(a, _)| a)
+                    .chain([Cave::End])
+                    .collect()
+            )
+        );
+
+        let graph = CaveGraph::with_caves(edges("start-a a-b c-end"));
+        assert_eq!(graph.longest_path(VisitPolicy::Limits), None);
+        assert_eq!(graph.longest_path(VisitPolicy::OneSmallTwice), None);
+    }
+
+    #[test]
+    fn length_histogram() {
+        for (input, paths, paths2) in [
+            ("start-A start-b A-c A-b b-d A-end b-end", 10, 36),
+            (include_str!("../../examples/2021/day12.txt"), 226, 3509),
+        ] {
+            let graph = CaveGraph::with_caves(edges(input));
+            for (policy, total) in [
+                (VisitPolicy::Limits, paths),
+                (VisitPolicy::OneSmallTwice, paths2),
+            ] {
+                let histogram = graph.path_length_histogram(policy);
+                assert_eq!(histogram.values().sum::<u64>(), total);
+                let longest = graph.longest_path(policy).unwrap().len();
+                assert_eq!(histogram.keys().last(), Some(&longest));
+            }
+        }
+
+        // start,A,end and start,b,end are the shortest, then start,A,b,end and start,b,A,end
+        let graph = CaveGraph::with_caves(edges("start-A start-b A-c A-b b-d A-end b-end"));
+        let histogram = graph.path_length_histogram(VisitPolicy::Limits);
+        assert_eq!(histogram.iter().next(), Some((&3, &2)));
+        assert_eq!(histogram.get(&4), Some(&2));
+
+        let graph = CaveGraph::with_caves(edges("start-a a-b c-end"));
+        assert!(graph.path_length_histogram(VisitPolicy::Limits).is_empty());
+    }
+
+    #[test]
+    fn visit_limits() {
+        let caves = edges("start-A start-b A-c A-b b-d A-end b-end");
+        let small_b = Cave::Small("b".to_string());
+        let with_limit = |cave: &Cave, limit| {
+            CaveGraph::with_caves(caves.clone())
+                .with_visit_limits(HashMap::from([(cave.clone(), limit)]))
+                .find_paths()
+        };
+
+        assert_eq!(with_limit(&small_b, 1), 10);
+        assert_eq!(with_limit(&small_b, 2), 30);
+        assert_eq!(with_limit(&small_b, 3), 70);
+        assert_eq!(with_limit(&Cave::Large("A".to_string()), 1), 4);
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+    use crate::y2021::day12_2;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day12.txt");
+
+    // The string-keyed runners parse their own graph, so every runner starts from the text
+    fn parsed(input: &str, part: fn(&CaveGraph) -> u32) -> u32 {
+        part(&parse_adj_list(input).unwrap())
+    }
+
+    fn strings(input: &str, part: fn(&day12_2::CaveGraph) -> u32) -> u32 {
+        part(&day12_2::parse_adj_list(input))
+    }
+
+    fn reference1(input: &str) -> u32 {
+        parsed(input, part1)
+    }
+
+    fn bitmask1(input: &str) -> u32 {
+        parsed(input, part1_bitmask)
+    }
+
+    fn strings1(input: &str) -> u32 {
+        strings(input, day12_2::part1)
+    }
+
+    fn reference2(input: &str) -> u32 {
+        parsed(input, part2)
+    }
+
+    fn bitmask2(input: &str) -> u32 {
+        parsed(input, part2_bitmask)
+    }
+
+    fn strings2(input: &str) -> u32 {
+        strings(input, day12_2::part2)
+    }
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(EXAMPLE, reference1, bitmask1, strings1);
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(EXAMPLE, reference2, bitmask2, strings2);
+    }
+
+    #[test]
+    fn no_start() {
+        let input = "A-b\nb-end";
+        assert_implementations_agree!(input, reference1, bitmask1, strings1);
+        assert_implementations_agree!(input, reference2, bitmask2, strings2);
+        assert_eq!(strings1(input), 0);
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub enum Cave {
+    Large(String),
+    Small(String),
+    Start,
+    End,
+}
+
+impl std::str::FromStr for Cave {
+    type Err = std::string::ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.chars().all(char::is_uppercase) {
+            Ok(Cave::Large(s.to_string()))
+        } else if s == "start" {
+            Ok(Cave::Start)
+        } else if s == "end" {
+            Ok(Cave::End)
+        } else {
+            Ok(Cave::Small(s.to_string()))
+        }
+    }
+}
+
+impl std::fmt::Debug for Cave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match &self {
+            Cave::Large(s) | Cave::Small(s) => s,
+            Cave::Start => "start",
+            Cave::End => "end",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Which small caves a path may go through more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Revisits {
+    /// Small caves are visited at most once
+    Never,
+    /// A single small cave may be visited twice, the rest at most once
+    OneSmallCave,
+}
+
+#[derive(Debug, Clone)]
+struct CavePath {
+    caves: Vec<Cave>,
+    revisits: Revisits,
+    /// Number of caves on the path where a small cave was visited for the second time
+    revisited_at: Option<usize>,
+}
+
+impl CavePath {
+    fn start(revisits: Revisits) -> Self {
+        CavePath {
+            caves: vec![Cave::Start],
+            revisits,
+            revisited_at: None,
+        }
+    }
+
+    fn visit(&mut self, cave: Cave) {
+        if matches!(cave, Cave::Small(_)) && self.caves.contains(&cave) {
+            self.revisited_at = Some(self.caves.len());
+        }
+        self.caves.push(cave);
+    }
+
+    /// Undo the last `visit`
+    fn leave(&mut self) {
+        self.caves.pop();
+        if self.revisited_at == Some(self.caves.len()) {
+            self.revisited_at = None;
+        }
+    }
+
+    fn can_visit(&self, cave: &Cave) -> bool {
+        match cave {
+            Cave::Start => false,
+            Cave::Large(_) | Cave::End => true,
+            Cave::Small(_) => {
+                !self.caves.contains(cave)
+                    || (self.revisits == Revisits::OneSmallCave && self.revisited_at.is_none())
+            }
+        }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.caves.last().unwrap() == &Cave::End
+    }
+
+    fn current(&self) -> &Cave {
+        self.caves.last().unwrap()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaveGraph {
+    adj_list: HashMap<Cave, Vec<Cave>>,
+    record_paths: bool,
+    paths: Vec<Vec<Cave>>,
+}
+
+impl CaveGraph {
+    pub fn with_caves(caves: Vec<(Cave, Cave)>) -> Self {
+        let mut adj_list: HashMap<Cave, Vec<Cave>> = HashMap::new();
+        for (a, b) in caves.into_iter() {
+            let a_value = a.clone();
+            let b_value = b.clone();
+            adj_list.entry(a).or_default().push(b_value);
+            adj_list.entry(b).or_default().push(a_value);
+        }
+
+        CaveGraph {
+            adj_list,
+            record_paths: false,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Keep every path `find_paths` finds, rather than only counting them
+    pub fn record_paths(&mut self, record: bool) {
+        self.record_paths = record;
+    }
+
+    /// Paths found by the last `find_paths` while recording, each from start to end
+    pub fn paths(&self) -> &[Vec<Cave>] {
+        &self.paths
+    }
+
+    pub fn find_paths(&mut self, revisits: Revisits) -> u32 {
+        self.paths.clear();
+        self.find_paths_from(&mut CavePath::start(revisits))
+    }
+
+    fn find_paths_from(&mut self, path: &mut CavePath) -> u32 {
+        if path.is_at_end() {
+            if self.record_paths {
+                self.paths.push(path.caves.clone());
+            }
+            return 1;
+        }
+
+        let mut found = 0;
+        for cave in self.neighbors(path).into_iter() {
+            path.visit(cave);
+            found += self.find_paths_from(path);
+            path.leave();
+        }
+        found
+    }
+
+    fn neighbors(&self, path: &CavePath) -> Vec<Cave> {
+        // A cave with no tunnels, like a missing start, leads nowhere
+        self.adj_list
+            .get(path.current())
+            .into_iter()
+            .flatten()
+            .filter(|&next| path.can_visit(next))
+            .cloned()
+            .collect()
+    }
+}
+
+#[aoc_generator(day12, part1, Strings)]
+#[aoc_generator(day12, part2, Strings)]
+pub(crate) fn parse_adj_list(input: &str) -> CaveGraph {
+    let adj_vec = input
+        .lines()
+        .filter_map(|line| line.split_once('-'))
+        .map(|(a, b)| (a.parse::<Cave>().unwrap(), b.parse::<Cave>().unwrap()))
+        .collect::<Vec<_>>();
+
+    CaveGraph::with_caves(adj_vec)
+}
+
+#[aoc(day12, part1, Strings)]
+pub(crate) fn part1(caves: &CaveGraph) -> u32 {
+    let mut caves = caves.clone();
+    caves.find_paths(Revisits::Never)
+}
+
+#[aoc(day12, part2, Strings)]
+pub(crate) fn part2(caves: &CaveGraph) -> u32 {
+    let mut caves = caves.clone();
+    caves.find_paths(Revisits::OneSmallCave)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn small_example() {
+        let input = parse_adj_list(
+            r"start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end",
+        );
+        assert_eq!(part1(&input), 10);
+        assert_eq!(part2(&input), 36);
+    }
+
+    #[test]
+    fn example() {
+        let input = parse_adj_list(include_str!("../../examples/2021/day12.txt"));
+        assert_eq!(part1(&input), 226);
+        assert_eq!(part2(&input), 3509);
+    }
+
+    #[test]
+    fn recorded_paths() {
+        let mut caves = parse_adj_list(
+            r"start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end",
+        );
+        assert_eq!(caves.find_paths(Revisits::Never), 10);
+        assert!(caves.paths().is_empty());
+
+        caves.record_paths(true);
+        assert_eq!(caves.find_paths(Revisits::OneSmallCave), 36);
+        assert_eq!(caves.paths().len(), 36);
+        let path = |caves: &str| {
+            caves
+                .split(',')
+                .map(|cave| cave.parse::<Cave>().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert!(caves.paths().contains(&path("start,A,b,A,b,A,c,A,end")));
+        assert!(!caves.paths().contains(&path("start,b,d,b,A,c,A,c,A,end")));
+        for path in caves.paths() {
+            assert_eq!(path.first(), Some(&Cave::Start));
+            assert_eq!(path.last(), Some(&Cave::End));
+            let small = path
+                .iter()
+                .filter(|cave| matches!(cave, Cave::Small(_)))
+                .collect::<Vec<_>>();
+            let twice = small
+                .iter()
+                .filter(|&&cave| small.iter().filter(|&&other| other == cave).count() > 1)
+                .count();
+            assert!(twice <= 2, "{:?}", path);
+        }
+
+        assert_eq!(caves.find_paths(Revisits::Never), 10);
+        assert_eq!(caves.paths().len(), 10);
+    }
+}
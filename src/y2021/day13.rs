@@ -0,0 +1,636 @@
+use crate::error::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FoldDirection {
+    Left,
+    Up,
+}
+
+/// Where to fold, either at a fixed line or halfway across the sheet as it is when folded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FoldLine {
+    At(usize),
+    Half,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldInstruction {
+    line: FoldLine,
+    direction: FoldDirection,
+}
+
+/// A transparent sheet with dots at (x, y) positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paper {
+    dots: Vec<(usize, usize)>,
+    size: (usize, usize),
+    /// (width, height) of the smallest sheet that holds the dots
+    extent: (usize, usize),
+}
+
+/// The smallest (width, height) that holds every dot
+fn extent(dots: &[(usize, usize)]) -> (usize, usize) {
+    let width = dots.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+    let height = dots.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+    (width, height)
+}
+
+impl Paper {
+    /// A sheet just big enough to hold the dots
+    pub fn with_dots(dots: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut dots = dots.into_iter().collect::<Vec<_>>();
+        dots.sort_unstable();
+        dots.dedup();
+        let extent = extent(&dots);
+        Paper {
+            dots,
+            size: extent,
+            extent,
+        }
+    }
+
+    /// (width, height) of the sheet. Folding along a line leaves the part of the sheet before it,
+    /// so the sheet's size only depends on where it was folded and not on which dots are left
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// (width, height) of the part of the sheet the dots are on, which can be smaller than `size`
+    /// once a fold leaves nothing near the edge
+    pub fn extent(&self) -> (usize, usize) {
+        self.extent
+    }
+
+    /// The dots on either sheet, as if one was laid over the other
+    pub fn union(&self, other: &Paper) -> Paper {
+        Paper::with_dots(self.dots.iter().chain(other.dots.iter()).copied())
+    }
+
+    /// The dots on both sheets
+    pub fn intersection(&self, other: &Paper) -> Paper {
+        Paper::with_dots(self.dots.iter().copied().filter(|dot| other.has_dot(dot)))
+    }
+
+    /// The dots on only one of the sheets
+    pub fn symmetric_difference(&self, other: &Paper) -> Paper {
+        let only_self = self.dots.iter().filter(|dot| !other.has_dot(dot));
+        let only_other = other.dots.iter().filter(|dot| !self.has_dot(dot));
+        Paper::with_dots(only_self.chain(only_other).copied())
+    }
+
+    fn has_dot(&self, dot: &(usize, usize)) -> bool {
+        self.dots.binary_search(dot).is_ok()
+    }
+
+    /// The instruction with its line worked out for this sheet, so it folds in the same place on
+    /// any other
+    fn fix_line(&self, instr: &FoldInstruction) -> FoldInstruction {
+        let (width, height) = self.size;
+        let length = match instr.direction {
+            FoldDirection::Up => height,
+            FoldDirection::Left => width,
+        };
+        FoldInstruction {
+            line: FoldLine::At(instr.line.resolve(length)),
+            direction: instr.direction,
+        }
+    }
+
+    /// Whether folding along the instruction's line leaves every dot where it is, because none are
+    /// past it
+    fn folds_nothing(&self, instr: &FoldInstruction) -> bool {
+        let (width, height) = self.size;
+        match instr.direction {
+            FoldDirection::Up => self.extent.1 <= instr.line.resolve(height) + 1,
+            FoldDirection::Left => self.extent.0 <= instr.line.resolve(width) + 1,
+        }
+    }
+
+    /// Fold the sheet along the instruction's line. A dot more than twice as far from the edge as
+    /// the line would be folded off the sheet, so if there are any the sheet is left as it is and
+    /// this returns false
+    fn fold(&mut self, instr: &FoldInstruction) -> bool {
+        let (width, height) = self.size;
+        let line = match instr.direction {
+            FoldDirection::Up => instr.line.resolve(height),
+            FoldDirection::Left => instr.line.resolve(width),
+        };
+        let off_sheet = |coord: usize| coord > line && coord - line > line;
+        let folded_off = self.dots.iter().any(|&(x, y)| match instr.direction {
+            FoldDirection::Up => off_sheet(y),
+            FoldDirection::Left => off_sheet(x),
+        });
+        if folded_off {
+            return false;
+        }
+
+        match instr.direction {
+            FoldDirection::Up => {
+                self.dots
+                    .iter_mut()
+                    .filter(|(_, y)| *y > line)
+                    .for_each(|(_, y)| *y = 2 * line - *y);
+                self.size.1 = self.size.1.min(line);
+            }
+            FoldDirection::Left => {
+                self.dots
+                    .iter_mut()
+                    .filter(|(x, _)| *x > line)
+                    .for_each(|(x, _)| *x = 2 * line - *x);
+                self.size.0 = self.size.0.min(line);
+            }
+        }
+        self.dots.sort_unstable();
+        self.dots.dedup();
+        self.extent = extent(&self.dots);
+        true
+    }
+
+    /// Dots in order of x, then y
+    pub fn dots(&self) -> &[(usize, usize)] {
+        &self.dots
+    }
+}
+
+impl std::fmt::Display for FoldInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let axis = match self.direction {
+            FoldDirection::Left => 'x',
+            FoldDirection::Up => 'y',
+        };
+        match self.line {
+            FoldLine::At(line) => write!(f, "fold along {}={}", axis, line),
+            FoldLine::Half => write!(f, "fold along {}", axis),
+        }
+    }
+}
+
+/// Why `optimize_folds` left a fold out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redundancy {
+    /// There were no dots past its line, so it moved nothing
+    PastDots,
+    /// It moved dots, but the folds after it put them in the same places without it
+    Absorbed,
+}
+
+/// The folds `optimize_folds` kept and the sheet they make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldPlan {
+    /// The folds that are needed, in order, each along a fixed line
+    pub folds: Vec<FoldInstruction>,
+    /// The index of each fold left out of the original list, in order, and why
+    pub redundant: Vec<(usize, Redundancy)>,
+    /// The sheet after the kept folds. It has the same dots as after every fold, but can be larger
+    pub sheet: Paper,
+}
+
+/// Fold `paper` with `folds` one after another, or None if one of them folds a dot off the sheet
+fn fold_all<'a>(
+    paper: &Paper,
+    folds: impl IntoIterator<Item = &'a FoldInstruction>,
+) -> Option<Paper> {
+    let mut paper = paper.clone();
+    folds
+        .into_iter()
+        .all(|fold| paper.fold(fold))
+        .then_some(paper)
+}
+
+/// A shortest list of folds, taken in order from `folds`, that leaves the same dots as all of
+/// them. None if the folds can't all be made.
+///
+/// Folds halfway across are fixed to the line they fold along first, since leaving out an earlier
+/// fold changes the size of the sheet they are made on. Folds with no dots past their line are
+/// then left out, and of the rest, any whose leaving out still gives the same dots, until none can
+/// go. The result can't lose another fold, though a different set of folds could be shorter
+pub fn optimize_folds(paper: &Paper, folds: &[FoldInstruction]) -> Option<FoldPlan> {
+    let mut sheet = paper.clone();
+    let mut kept = Vec::new();
+    let mut redundant = Vec::new();
+    for (i, fold) in folds.iter().enumerate() {
+        let fold = sheet.fix_line(fold);
+        if sheet.folds_nothing(&fold) {
+            redundant.push((i, Redundancy::PastDots));
+        } else {
+            kept.push((i, fold.clone()));
+        }
+        if !sheet.fold(&fold) {
+            return None;
+        }
+    }
+
+    let same_dots = |kept: &[(usize, FoldInstruction)]| {
+        fold_all(paper, kept.iter().map(|(_, fold)| fold))
+            .filter(|folded| folded.dots() == sheet.dots())
+    };
+    let mut dropped = true;
+    while dropped {
+        dropped = false;
+        for i in (0..kept.len()).rev() {
+            let removed = kept.remove(i);
+            if same_dots(&kept).is_some() {
+                redundant.push((removed.0, Redundancy::Absorbed));
+                dropped = true;
+            } else {
+                kept.insert(i, removed);
+            }
+        }
+    }
+    redundant.sort_unstable_by_key(|&(i, _)| i);
+
+    Some(FoldPlan {
+        sheet: same_dots(&kept)?,
+        folds: kept.into_iter().map(|(_, fold)| fold).collect(),
+        redundant,
+    })
+}
+
+impl FoldLine {
+    /// The line to fold along on a sheet `length` long in the direction of the fold
+    fn resolve(self, length: usize) -> usize {
+        match self {
+            FoldLine::At(line) => line,
+            FoldLine::Half => length / 2,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseFoldError;
+impl std::str::FromStr for FoldInstruction {
+    type Err = ParseFoldError;
+
+    /// `fold along x=N` or `fold along y=N`, with nothing else on the line. Folds without a
+    /// coordinate are made halfway across the sheet. Folding vertically is along a vertical line,
+    /// like `fold along x=...`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let relative = match s.trim() {
+            "fold along x" | "fold in half vertically" => Some(FoldDirection::Left),
+            "fold along y" | "fold in half horizontally" => Some(FoldDirection::Up),
+            _ => None,
+        };
+        if let Some(direction) = relative {
+            return Ok(FoldInstruction {
+                line: FoldLine::Half,
+                direction,
+            });
+        }
+
+        let (axis, line) = s
+            .trim()
+            .strip_prefix("fold along ")
+            .and_then(|fold| fold.split_once('='))
+            .ok_or(ParseFoldError)?;
+        let direction = match axis {
+            "x" => FoldDirection::Left,
+            "y" => FoldDirection::Up,
+            _ => return Err(ParseFoldError),
+        };
+        if !line.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseFoldError);
+        }
+        let line = FoldLine::At(line.parse().or(Err(ParseFoldError))?);
+        Ok(FoldInstruction { line, direction })
+    }
+}
+
+#[cfg(feature = "viz")]
+fn render(paper: &Paper) -> crate::viz::Canvas {
+    use crate::viz::{Canvas, BLACK, WHITE};
+
+    let (width, height) = paper.extent();
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            canvas.set(x, y, '.', BLACK);
+        }
+    }
+    for &(x, y) in paper.dots() {
+        canvas.set(x, y, '#', WHITE);
+    }
+    canvas
+}
+
+/// The unfolded paper, then one frame after each fold
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    let (mut paper, instructions) = match parse_instructions(input) {
+        Ok(parsed) => parsed,
+        Err(_) => return Vec::new(),
+    };
+    let mut frames = vec![render(&paper)];
+    for inst in instructions.iter() {
+        if !paper.fold(inst) {
+            break;
+        }
+        frames.push(render(&paper));
+    }
+    frames
+}
+
+/// Dots as `x,y`, one per line, then fold instructions. Blank lines are skipped, so the blank
+/// line between the two isn't needed, but every dot has to come before the first fold
+#[aoc_generator(day13)]
+pub(crate) fn parse_instructions(input: &str) -> Result<(Paper, Vec<FoldInstruction>), ParseError> {
+    let mut dots = Vec::new();
+    let mut instructions = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with("fold") {
+            let instruction = line.parse().map_err(|_| {
+                ParseError::at_line(i + 1, format!("invalid fold instruction {:?}", line))
+            })?;
+            instructions.push(instruction);
+            continue;
+        }
+
+        if !instructions.is_empty() {
+            return Err(ParseError::at_line(
+                i + 1,
+                format!("dot {:?} after the fold instructions", line),
+            ));
+        }
+        let dot = line
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .ok_or_else(|| ParseError::at_line(i + 1, format!("invalid dot {:?}", line)))?;
+        dots.push(dot);
+    }
+
+    Ok((Paper::with_dots(dots), instructions))
+}
+
+#[aoc(day13, part1)]
+pub(crate) fn part1((paper, instructions): &(Paper, Vec<FoldInstruction>)) -> Option<usize> {
+    let mut paper = paper.clone();
+    paper
+        .fold(instructions.first()?)
+        .then(|| paper.dots().len())
+}
+
+#[aoc(day13, part2)]
+pub(crate) fn part2((paper, instructions): &(Paper, Vec<FoldInstruction>)) -> Option<String> {
+    let mut paper = paper.clone();
+    if !instructions.iter().all(|inst| paper.fold(inst)) {
+        return None;
+    }
+    let max_x = paper.dots().iter().map(|&(x, _)| x).max()?;
+    let max_y = paper.dots().iter().map(|&(_, y)| y).max()?;
+
+    // Start on a new line so the letters line up when printed after a label
+    let mut code = String::new();
+    for y in 0..=max_y {
+        code.push('\n');
+        for x in 0..=max_x {
+            code.push(if paper.dots().contains(&(x, y)) {
+                '#'
+            } else {
+                '.'
+            });
+        }
+    }
+    Some(code)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day13.txt");
+
+    /// The example's dots, without its folds
+    fn example_dots() -> &'static str {
+        EXAMPLE.split_once("\n\n").unwrap().0
+    }
+
+    #[test]
+    fn code() {
+        let input = parse_instructions("0,0\n4,0\n1,1\n3,2\n\nfold along x=2\n").unwrap();
+        assert_eq!(part1(&input), Some(3));
+        assert_eq!(part2(&input), Some("\n#.\n.#\n.#".to_string()));
+
+        // Folding at x=1 would put the dot at x=4 off the left edge of the sheet
+        let input = parse_instructions("0,0\n4,0\n\nfold along x=1\n").unwrap();
+        assert_eq!(part1(&input), None);
+        assert_eq!(part2(&input), None);
+
+        let input = parse_instructions("0,0\n").unwrap();
+        assert_eq!(part1(&input), None);
+        assert_eq!(
+            parse_instructions("0,0\n1,x\n").err().map(|err| err.line()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn strict_folds() {
+        let fold = |s: &str| {
+            s.parse::<FoldInstruction>()
+                .map(|fold| (fold.direction, fold.line))
+        };
+        assert_eq!(
+            fold("fold along x=5"),
+            Ok((FoldDirection::Left, FoldLine::At(5)))
+        );
+        assert_eq!(
+            fold("  fold along y=7 "),
+            Ok((FoldDirection::Up, FoldLine::At(7)))
+        );
+        for bad in [
+            "fold along x=5 y=7",
+            "fold along z=5",
+            "fold along x=",
+            "fold along x=-5",
+            "fold along x=+5",
+            "fold along x=5a",
+            "fold x=5",
+            "please fold along x=5",
+            "fold along x==5",
+            "fold along xy=5",
+        ] {
+            assert_eq!(fold(bad), Err(ParseFoldError), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let line = |input| parse_instructions(input).err().map(|err| err.line());
+        assert_eq!(line("0,0\n1,1\n\nfold along x=5 y=7\n"), Some(4));
+        assert_eq!(line("0,0\n\nfold along y=1\nfold alng x=1\n"), Some(4));
+        assert_eq!(line("0,0\n1;1\n\nfold along x=1\n"), Some(2));
+        assert_eq!(line("0,0\n\nfold along y=1\n3,3\n"), Some(4));
+        assert_eq!(line("0,0\n,1\n"), Some(2));
+
+        // Without the blank line the first fold isn't lost
+        let (paper, folds) =
+            parse_instructions("0,0\n4,0\nfold along x=2\nfold along y=1").unwrap();
+        assert_eq!(paper.dots().len(), 2);
+        assert_eq!(folds.len(), 2);
+
+        // Folds only
+        let (paper, folds) = parse_instructions("fold along x=2\n").unwrap();
+        assert!(paper.dots().is_empty());
+        assert_eq!(folds.len(), 1);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = Paper::with_dots([(0, 0), (1, 0), (2, 2), (1, 0)]);
+        let b = Paper::with_dots([(2, 2), (3, 1), (0, 0)]);
+
+        assert_eq!(a.union(&b).dots(), [(0, 0), (1, 0), (2, 2), (3, 1)]);
+        assert_eq!(a.intersection(&b).dots(), [(0, 0), (2, 2)]);
+        assert_eq!(a.symmetric_difference(&b).dots(), [(1, 0), (3, 1)]);
+        assert_eq!(a.symmetric_difference(&a), Paper::with_dots([]));
+
+        // Folding the bottom half up is the same as overlaying it flipped
+        let (paper, instructions) =
+            parse_instructions("1,0\n2,1\n0,4\n2,3\n\nfold along y=2\n").unwrap();
+        let mut folded = paper.clone();
+        folded.fold(&instructions[0]);
+        let top = Paper::with_dots(paper.dots().iter().copied().filter(|&(_, y)| y < 2));
+        let bottom = Paper::with_dots(
+            paper
+                .dots()
+                .iter()
+                .filter(|&&(_, y)| y > 2)
+                .map(|&(x, y)| (x, 4 - y)),
+        );
+        assert_eq!(folded, top.union(&bottom));
+        assert_eq!(top.intersection(&bottom).dots(), [(2, 1)]);
+    }
+
+    #[test]
+    fn relative_folds() {
+        let dots = example_dots();
+        let (mut explicit, explicit_folds) = parse_instructions(EXAMPLE).unwrap();
+        let (mut relative, relative_folds) = parse_instructions(&format!(
+            "{}\n\nfold in half horizontally\nfold in half vertically\n",
+            dots
+        ))
+        .unwrap();
+        assert_eq!(relative_folds.len(), 2);
+        assert_eq!(explicit.size(), (11, 15));
+
+        for (a, b) in explicit_folds.iter().zip(relative_folds.iter()) {
+            explicit.fold(a);
+            relative.fold(b);
+            assert_eq!(explicit, relative);
+        }
+        assert_eq!(relative.size(), (5, 7));
+        assert_eq!(relative.dots().len(), 16);
+
+        // Bare axes fold in half too
+        let (mut paper, folds) =
+            parse_instructions("0,0\n4,0\n1,1\n3,2\n\nfold along x\n").unwrap();
+        paper.fold(&folds[0]);
+        assert_eq!(paper.dots(), [(0, 0), (1, 1), (1, 2)]);
+        assert_eq!(paper.size(), (2, 3));
+
+        // A fold past the edge leaves the sheet the size it was, so folding in half after it
+        // folds along the same line
+        let (paper, folds) =
+            parse_instructions(&format!("{}\n\nfold along x=12\nfold along x\n", dots)).unwrap();
+        let mut past = paper.clone();
+        assert!(past.fold(&folds[0]));
+        assert_eq!(past, paper);
+        assert_eq!(past.size(), (11, 15));
+        past.fold(&folds[1]);
+        let mut alone = paper.clone();
+        alone.fold(&folds[1]);
+        assert_eq!(past, alone);
+        assert_eq!(past.size(), (5, 15));
+    }
+
+    #[test]
+    fn optimized_folds() {
+        let dots = example_dots();
+        let fold = |s: &str| s.parse::<FoldInstruction>().unwrap();
+
+        // Both of the example's folds are needed
+        let (paper, folds) = parse_instructions(EXAMPLE).unwrap();
+        let plan = optimize_folds(&paper, &folds).unwrap();
+        assert_eq!(plan.folds, folds);
+        assert_eq!(plan.redundant, []);
+        assert_eq!(plan.sheet, fold_all(&paper, &folds).unwrap());
+        assert_eq!(plan.sheet.extent(), (5, 5));
+
+        // The same folds repeated, and folds past every dot
+        let (paper, folds) = parse_instructions(&format!(
+            "{}\n\nfold along y=7\nfold along y=7\nfold along x=12\nfold along x=5\nfold along y=6\nfold along x=5\n",
+            dots
+        ))
+        .unwrap();
+        let plan = optimize_folds(&paper, &folds).unwrap();
+        assert_eq!(plan.folds, [fold("fold along y=7"), fold("fold along x=5")]);
+        assert_eq!(
+            plan.redundant,
+            [
+                (1, Redundancy::PastDots),
+                (2, Redundancy::PastDots),
+                (4, Redundancy::PastDots),
+                (5, Redundancy::PastDots),
+            ]
+        );
+        assert_eq!(plan.sheet.dots(), fold_all(&paper, &folds).unwrap().dots());
+
+        // Folding at x=3 alone does everything the three folds do, after which x=2 moves nothing
+        let paper = Paper::with_dots([(2, 2), (4, 1), (5, 0)]);
+        let folds = ["fold along x=4", "fold along x=3", "fold along x=2"].map(fold);
+        assert_ne!(
+            fold_all(&paper, &folds[..1]).unwrap(),
+            paper,
+            "every fold moves a dot"
+        );
+        let plan = optimize_folds(&paper, &folds).unwrap();
+        assert_eq!(plan.folds, [fold("fold along x=3")]);
+        assert_eq!(
+            plan.redundant,
+            [(0, Redundancy::Absorbed), (2, Redundancy::Absorbed)]
+        );
+        assert_eq!(plan.sheet.dots(), [(1, 0), (2, 1), (2, 2)]);
+
+        // Halfway folds are fixed to where they landed with every fold made
+        let (paper, folds) = parse_instructions(&format!(
+            "{}\n\nfold in half horizontally\nfold in half horizontally\nfold in half vertically\n",
+            dots
+        ))
+        .unwrap();
+        let plan = optimize_folds(&paper, &folds).unwrap();
+        assert_eq!(
+            plan.folds
+                .iter()
+                .map(|fold| fold.to_string())
+                .collect::<Vec<_>>(),
+            ["fold along y=7", "fold along y=3", "fold along x=5"]
+        );
+        assert_eq!(plan.sheet.dots(), fold_all(&paper, &folds).unwrap().dots());
+
+        // Folding in half after a fold past the edge folds the sheet as it was
+        let (paper, folds) = parse_instructions(&format!(
+            "{}\n\nfold along x=12\nfold in half vertically\n",
+            dots
+        ))
+        .unwrap();
+        let plan = optimize_folds(&paper, &folds).unwrap();
+        assert_eq!(plan.folds, [fold("fold along x=5")]);
+        assert_eq!(plan.redundant, [(0, Redundancy::PastDots)]);
+
+        // Folding a dot off the sheet can't be optimized
+        let (paper, folds) = parse_instructions("0,0\n4,0\n\nfold along x=1\n").unwrap();
+        assert_eq!(optimize_folds(&paper, &folds), None);
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn render_folds() {
+        let frames = render_frames(EXAMPLE);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].to_ascii(), "#####\n#...#\n#...#\n#...#\n#####\n");
+    }
+}
@@ -0,0 +1,371 @@
+use crate::counter::{Counter, Overflow};
+use crate::error::ParseError;
+use std::collections::{HashMap, HashSet};
+
+/// What gets inserted between a pair of elements
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Insertion {
+    Single(char),
+    Many(Vec<char>),
+}
+
+impl std::str::FromStr for Insertion {
+    type Err = std::convert::Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.chars().collect::<Vec<_>>();
+        match chars.as_slice() {
+            &[c] => Ok(Insertion::Single(c)),
+            _ => Ok(Insertion::Many(chars)),
+        }
+    }
+}
+
+pub type PolymerRules = HashMap<(char, char), Insertion>;
+
+/// When a pair has more than one rule, the first one listed is used. Pairs without a rule are left
+/// as they are
+#[aoc_generator(day14)]
+pub(crate) fn parse_polymer_template(input: &str) -> Result<(Vec<char>, PolymerRules), ParseError> {
+    let mut lines = input.lines().enumerate();
+    let template = lines
+        .next()
+        .map(|(_, line)| line.trim().chars().collect::<Vec<_>>())
+        .filter(|template| !template.is_empty())
+        .ok_or_else(|| ParseError::at_line(1, "missing the polymer template"))?;
+
+    let mut rules = HashMap::new();
+    for (i, (polymers, insert)) in lines.filter_map(|(i, line)| Some((i, line.split_once("->")?))) {
+        let pair = match polymers.trim().chars().collect::<Vec<_>>().as_slice() {
+            &[c1, c2] => (c1, c2),
+            _ => {
+                return Err(ParseError::at_line(
+                    i + 1,
+                    format!("expected a pair of elements, found {:?}", polymers.trim()),
+                ))
+            }
+        };
+        let Ok(insertion) = insert.trim().parse::<Insertion>();
+        rules.entry(pair).or_insert(insertion);
+    }
+
+    Ok((template, rules))
+}
+
+/// Number of each element in the polymer after `num_iter` steps
+fn element_counts(
+    num_iter: usize,
+    template: &[char],
+    rules: &PolymerRules,
+) -> Result<Counter<char>, Overflow> {
+    let mut final_count = Counter::<char>::new();
+    let mut pair_counts = Counter::new();
+
+    for &base in template.iter() {
+        final_count.increment(base)?;
+    }
+
+    for (a, b) in template.windows(2).map(|w| (w[0], w[1])) {
+        pair_counts.increment((a, b))?;
+    }
+
+    for _ in 0..num_iter {
+        let mut pair_counts_prev = Counter::new();
+        std::mem::swap(&mut pair_counts, &mut pair_counts_prev);
+
+        for (&pair, &count) in pair_counts_prev.iter() {
+            match rules.get(&pair) {
+                Some(&Insertion::Single(new)) => {
+                    final_count.add(new, count)?;
+                    pair_counts.add((pair.0, new), count)?;
+                    pair_counts.add((new, pair.1), count)?;
+                }
+                Some(Insertion::Many(new)) => {
+                    // The pair becomes the chain pair.0, new..., pair.1
+                    for &c in new.iter() {
+                        final_count.add(c, count)?;
+                    }
+                    let chain = std::iter::once(pair.0)
+                        .chain(new.iter().copied())
+                        .chain(std::iter::once(pair.1))
+                        .collect::<Vec<_>>();
+                    for w in chain.windows(2) {
+                        pair_counts.add((w[0], w[1]), count)?;
+                    }
+                }
+                None => pair_counts.add(pair, count)?,
+            }
+        }
+    }
+
+    Ok(final_count)
+}
+
+fn polymer_stats(
+    num_iter: usize,
+    template: &[char],
+    rules: &PolymerRules,
+) -> Result<u64, Overflow> {
+    let final_count = element_counts(num_iter, template, rules)?;
+    let ((_, min), (_, max)) = final_count.min_max().unwrap();
+    Ok(max - min)
+}
+
+/// Longest template `search_template` tries
+const MAX_SEARCH_LEN: usize = 12;
+
+/// Most elements `search_template` searches over, so the parities of their counts fit in a mask
+/// and there aren't too many of them
+const MAX_SEARCH_ELEMENTS: usize = 16;
+
+/// Bit `i` set if element `i` has an odd count
+fn parity(counts: &[u64]) -> u32 {
+    counts
+        .iter()
+        .enumerate()
+        .fold(0, |mask, (i, &count)| mask | ((count & 1) as u32) << i)
+}
+
+/// Depth first search over templates of one length, with the elements numbered
+struct TemplateSearch {
+    /// `inserted[a][b]` is how many of each element end up between a and b, or None if there are
+    /// too many to count
+    inserted: Vec<Vec<Option<Vec<u64>>>>,
+    /// `reachable[k][a]` holds the parities of the counts that appending k more elements after a
+    /// adds, including everything inserted between them
+    reachable: Vec<Vec<HashSet<u32>>>,
+    target: Vec<u64>,
+}
+
+impl TemplateSearch {
+    /// Try every way to finish `template` with `len` elements, returning true once one of them has
+    /// exactly the target counts. `counts` holds the counts for `template` as it is
+    fn extend(&self, template: &mut Vec<usize>, counts: &mut [u64], len: usize) -> bool {
+        // Counts only go up as the template gets longer
+        if counts.iter().zip(self.target.iter()).any(|(c, t)| c > t) {
+            return false;
+        }
+        let last = *template.last().unwrap();
+        let remaining = len - template.len();
+        let needed = parity(counts) ^ parity(&self.target);
+        if !self.reachable[remaining][last].contains(&needed) {
+            return false;
+        }
+        if remaining == 0 {
+            return counts == self.target;
+        }
+
+        for next in 0..self.target.len() {
+            let Some(inserted) = &self.inserted[last][next] else {
+                continue;
+            };
+            for (count, n) in counts.iter_mut().zip(inserted.iter()) {
+                *count = count.saturating_add(*n);
+            }
+            counts[next] += 1;
+            template.push(next);
+            if self.extend(template, counts, len) {
+                return true;
+            }
+            template.pop();
+            counts[next] -= 1;
+            for (count, n) in counts.iter_mut().zip(inserted.iter()) {
+                *count -= n;
+            }
+        }
+        false
+    }
+}
+
+/// Experimental: the shortest template of at most `MAX_SEARCH_LEN` elements that has exactly the
+/// `target` count of each element after `steps` steps, or None if there isn't one. Ties go to the
+/// template that comes first alphabetically.
+///
+/// After any number of steps the polymer's counts are the template's plus the counts inserted
+/// between each of its pairs, which the pair counting engine works out once per pair. A template is
+/// grown one element at a time and abandoned as soon as it has too many of some element, or when
+/// no way of finishing it can make the count of every element odd or even as in `target`
+pub fn search_template(
+    rules: &PolymerRules,
+    steps: usize,
+    target: &Counter<char>,
+) -> Option<Vec<char>> {
+    let mut elements = target.iter().map(|(&c, _)| c).collect::<Vec<_>>();
+    for (&(a, b), insertion) in rules.iter() {
+        elements.extend([a, b]);
+        match insertion {
+            Insertion::Single(c) => elements.push(*c),
+            Insertion::Many(chars) => elements.extend(chars),
+        }
+    }
+    elements.sort_unstable();
+    elements.dedup();
+    if elements.len() > MAX_SEARCH_ELEMENTS {
+        return None;
+    }
+
+    let inserted = elements
+        .iter()
+        .map(|&a| {
+            elements
+                .iter()
+                .map(|&b| {
+                    let counts = element_counts(steps, &[a, b], rules).ok()?;
+                    let ends = |c: char| (a == c) as u64 + (b == c) as u64;
+                    Some(
+                        elements
+                            .iter()
+                            .map(|&c| counts.get(&c) - ends(c))
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut reachable = vec![vec![HashSet::from([0]); elements.len()]];
+    for k in 1..MAX_SEARCH_LEN {
+        let next = (0..elements.len())
+            .map(|a| {
+                let mut parities = HashSet::new();
+                for (b, inserted) in inserted[a].iter().enumerate() {
+                    if let Some(inserted) = inserted {
+                        let step = parity(inserted) ^ (1 << b);
+                        parities.extend(reachable[k - 1][b].iter().map(|p| p ^ step));
+                    }
+                }
+                parities
+            })
+            .collect();
+        reachable.push(next);
+    }
+
+    let search = TemplateSearch {
+        inserted,
+        reachable,
+        target: elements.iter().map(|c| target.get(c)).collect(),
+    };
+    for len in 1..=MAX_SEARCH_LEN {
+        for first in 0..elements.len() {
+            let mut counts = vec![0; elements.len()];
+            counts[first] = 1;
+            let mut template = vec![first];
+            if search.extend(&mut template, &mut counts, len) {
+                return Some(template.into_iter().map(|i| elements[i]).collect());
+            }
+        }
+    }
+    None
+}
+
+#[aoc(day14, part1)]
+pub(crate) fn part1((chain, rules): &(Vec<char>, PolymerRules)) -> Option<u64> {
+    polymer_stats(10, chain, rules).ok()
+}
+
+#[aoc(day14, part2)]
+pub(crate) fn part2((chain, rules): &(Vec<char>, PolymerRules)) -> Option<u64> {
+    polymer_stats(40, chain, rules).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Apply the rules to the whole polymer, one step at a time
+    fn expand(num_iter: usize, template: &[char], rules: &PolymerRules) -> u64 {
+        let mut polymer = template.to_vec();
+        for _ in 0..num_iter {
+            let mut next = vec![polymer[0]];
+            for w in polymer.windows(2) {
+                match rules.get(&(w[0], w[1])) {
+                    Some(Insertion::Single(c)) => next.push(*c),
+                    Some(Insertion::Many(chars)) => next.extend(chars),
+                    None => {}
+                }
+                next.push(w[1]);
+            }
+            polymer = next;
+        }
+
+        let mut counts = Counter::<char>::new();
+        for c in polymer {
+            counts.increment(c).unwrap();
+        }
+        let ((_, min), (_, max)) = counts.min_max().unwrap();
+        max - min
+    }
+
+    #[test]
+    fn multi_char_insertion() {
+        let (template, rules) =
+            parse_polymer_template("ABC\n\nAB -> CA\nBA -> B\nCA -> \nAC -> BBA\nCC -> A\n")
+                .unwrap();
+        assert_eq!(rules[&('A', 'B')], Insertion::Many(vec!['C', 'A']));
+        assert_eq!(rules[&('B', 'A')], Insertion::Single('B'));
+        assert_eq!(rules[&('C', 'A')], Insertion::Many(vec![]));
+
+        for steps in 0..8 {
+            assert_eq!(
+                polymer_stats(steps, &template, &rules).unwrap(),
+                expand(steps, &template, &rules),
+                "after {} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn find_template() {
+        let (template, rules) =
+            parse_polymer_template(include_str!("../../examples/2021/day14.txt")).unwrap();
+        let target = element_counts(10, &template, &rules).unwrap();
+        assert_eq!(target.get(&'B'), 1749);
+        // BCCB polymerizes to the same counts as NNCB, and comes first
+        let found = search_template(&rules, 10, &target).unwrap();
+        assert_eq!(found, ['B', 'C', 'C', 'B']);
+        assert_eq!(element_counts(10, &found, &rules).unwrap(), target);
+
+        let target = element_counts(3, &template, &rules).unwrap();
+        let found = search_template(&rules, 3, &target).unwrap();
+        assert_eq!(element_counts(3, &found, &rules).unwrap(), target);
+
+        // One more element than any template can make
+        let mut target = element_counts(10, &template, &rules).unwrap();
+        target.increment('H').unwrap();
+        assert_eq!(search_template(&rules, 10, &target), None);
+
+        // Without any rules the template is just its counts, in order
+        let mut target = Counter::new();
+        target.add('B', 2).unwrap();
+        target.add('A', 1).unwrap();
+        assert_eq!(
+            search_template(&HashMap::new(), 5, &target),
+            Some(vec!['A', 'B', 'B'])
+        );
+    }
+
+    #[test]
+    fn first_rule_wins() {
+        let (template, rules) = parse_polymer_template("AB\n\nAB -> A\nAB -> BB\n").unwrap();
+        assert_eq!(rules[&('A', 'B')], Insertion::Single('A'));
+        assert_eq!(polymer_stats(1, &template, &rules), Ok(1));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(
+            parse_polymer_template("").err().map(|err| err.line()),
+            Some(1)
+        );
+        assert_eq!(
+            parse_polymer_template("AB\n\nAB -> C\nABC -> D\n")
+                .err()
+                .map(|err| err.line()),
+            Some(4)
+        );
+
+        // A template with no rules just stays as it is
+        let input = parse_polymer_template("ABB").unwrap();
+        assert_eq!(part1(&input), Some(1));
+    }
+}
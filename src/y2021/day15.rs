@@ -0,0 +1,872 @@
+use crate::error::ParseError;
+use crate::grid::BitGrid;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::{Ord, Ordering, PartialOrd};
+use std::collections::BinaryHeap;
+
+type CaveMap = Vec<Vec<i32>>;
+
+/// Which neighbors the search may step to from a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// Up, down, left and right
+    Orthogonal,
+    /// The orthogonal neighbors and the four diagonal ones. A diagonal step costs the risk of the
+    /// cell entered, the same as any other step
+    Diagonal,
+}
+
+/// What the search keeps about cells it has reached but not yet settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frontier {
+    /// Nothing: every neighbor is pushed onto the queue, and copies of a cell that come off it
+    /// after the cell is settled are skipped
+    Lazy,
+    /// The lowest risk found so far for each cell, so a push that doesn't improve on it is skipped.
+    /// Costs 4 bytes a cell, but the queue holds far fewer stale copies
+    Pruned,
+}
+
+#[derive(Eq)]
+struct PathNode {
+    pub risk: i32,
+    pub pos: (usize, usize),
+    pub from: (usize, usize),
+}
+
+impl Ord for PathNode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.risk.cmp(&self.risk)
+    }
+}
+
+impl PartialOrd for PathNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PathNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.risk == other.risk
+    }
+}
+
+/// Risk of (row, col) on the map tiled `repeats` times. Each tile to the right or below has its
+/// risk increased by 1, wrapping back around to 1 after 9
+fn expanded_risk(map: &CaveMap, row: usize, col: usize) -> i32 {
+    let map_rows = map.len();
+    let map_cols = map[0].len();
+    wrap_risk(
+        map[row % map_rows][col % map_cols] + (row / map_rows) as i32 + (col / map_cols) as i32,
+    )
+}
+
+/// Risks past 9 wrap back around to 1
+fn wrap_risk(risk: i32) -> i32 {
+    if risk > 9 {
+        risk - (9 * ((risk - 1) / 9))
+    } else {
+        risk
+    }
+}
+
+/// The map tiled `repeats` times, flattened row by row. Rows are filled in parallel with the
+/// `parallel` feature
+fn expand(map: &CaveMap, repeats: usize) -> Vec<u8> {
+    let cols = map[0].len() * repeats;
+    let mut expanded = vec![0; map.len() * repeats * cols];
+    #[cfg(feature = "parallel")]
+    let rows = expanded.par_chunks_mut(cols);
+    #[cfg(not(feature = "parallel"))]
+    let rows = expanded.chunks_mut(cols);
+    rows.enumerate().for_each(|(row, risks)| {
+        for (col, risk) in risks.iter_mut().enumerate() {
+            *risk = expanded_risk(map, row, col) as u8;
+        }
+    });
+
+    expanded
+}
+
+/// Positions above, below, left and right of pos that are inside a rows x cols map, followed by
+/// the diagonal ones if `movement` allows them
+fn adjacent(
+    (r, c): (usize, usize),
+    rows: usize,
+    cols: usize,
+    movement: Movement,
+) -> Vec<(usize, usize)> {
+    let mut adjacent = Vec::with_capacity(8);
+    if r > 0 {
+        adjacent.push((r - 1, c));
+    }
+    if r < rows - 1 {
+        adjacent.push((r + 1, c));
+    }
+    if c > 0 {
+        adjacent.push((r, c - 1));
+    }
+    if c < cols - 1 {
+        adjacent.push((r, c + 1));
+    }
+    if movement == Movement::Diagonal {
+        for (dr, dc) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+            match (r.checked_add_signed(dr), c.checked_add_signed(dc)) {
+                (Some(nr), Some(nc)) if nr < rows && nc < cols => adjacent.push((nr, nc)),
+                _ => {}
+            }
+        }
+    }
+    adjacent
+}
+
+/// Dijkstra from the top left to the bottom right corner. `settle` is called with each position
+/// and the position it was reached from as soon as its lowest risk is known
+fn search(
+    map: &CaveMap,
+    repeats: usize,
+    movement: Movement,
+    frontier: Frontier,
+    settle: impl FnMut((usize, usize), (usize, usize)),
+) -> i32 {
+    search_by(
+        map.len() * repeats,
+        map[0].len() * repeats,
+        movement,
+        frontier,
+        |row, col| expanded_risk(map, row, col),
+        settle,
+    )
+}
+
+/// Dijkstra over a max_rows x max_cols map, where `compute_risk` gives the risk of entering a cell
+fn search_by(
+    max_rows: usize,
+    max_cols: usize,
+    movement: Movement,
+    frontier: Frontier,
+    compute_risk: impl Fn(usize, usize) -> i32,
+    mut settle: impl FnMut((usize, usize), (usize, usize)),
+) -> i32 {
+    let dest = (max_rows - 1, max_cols - 1);
+    let mut path_queue = BinaryHeap::new();
+    path_queue.push(PathNode {
+        risk: 0,
+        pos: (0, 0),
+        from: (0, 0),
+    });
+
+    let mut visited = BitGrid::new(max_rows, max_cols);
+    let mut dist = match frontier {
+        Frontier::Lazy => None,
+        Frontier::Pruned => Some(vec![i32::MAX; max_rows * max_cols]),
+    };
+    while let Some(PathNode {
+        pos: (r, c),
+        risk,
+        from,
+    }) = path_queue.pop()
+    {
+        if !visited.insert((r, c)) {
+            continue;
+        }
+
+        settle((r, c), from);
+        if (r, c) == dest {
+            return risk;
+        }
+
+        for (nr, nc) in adjacent((r, c), max_rows, max_cols, movement) {
+            let next_risk = risk + compute_risk(nr, nc);
+            if let Some(dist) = dist.as_mut() {
+                let best = &mut dist[nr * max_cols + nc];
+                if next_risk >= *best {
+                    continue;
+                }
+                *best = next_risk;
+            }
+            path_queue.push(PathNode {
+                risk: next_risk,
+                pos: (nr, nc),
+                from: (r, c),
+            });
+        }
+    }
+
+    panic!("Did not make it to the end");
+}
+
+fn find_lowest_risk_path(map: &CaveMap, repeats: usize) -> i32 {
+    lowest_risk(map, repeats, Movement::Orthogonal)
+}
+
+/// Lowest total risk from the top left to the bottom right of the map tiled `repeats` times
+pub fn lowest_risk(map: &CaveMap, repeats: usize, movement: Movement) -> i32 {
+    lowest_risk_with(map, repeats, movement, Frontier::Lazy)
+}
+
+/// Same as `lowest_risk`, choosing what the search keeps about the cells it has reached
+pub fn lowest_risk_with(
+    map: &CaveMap,
+    repeats: usize,
+    movement: Movement,
+    frontier: Frontier,
+) -> i32 {
+    search(map, repeats, movement, frontier, |_, _| {})
+}
+
+/// The map tiled `repeats` times and kept expanded, along with the lowest risk of reaching every
+/// cell from the top left. Editing a cell with `set_risk` only searches again from the cells whose
+/// lowest risk it can change, so the map can be tweaked and queried over and over without paying
+/// for the expansion or a full search each time
+pub struct CaveMapExt {
+    rows: usize,
+    cols: usize,
+    movement: Movement,
+    risks: Vec<u8>,
+    /// Lowest total risk of reaching each cell
+    dist: Vec<i32>,
+    /// The cell each one is reached from on a lowest risk path, the top left pointing to itself
+    parent: Vec<usize>,
+}
+
+impl CaveMapExt {
+    pub fn new(map: &CaveMap, repeats: usize, movement: Movement) -> Self {
+        let rows = map.len() * repeats;
+        let cols = map[0].len() * repeats;
+        let mut cave = CaveMapExt {
+            rows,
+            cols,
+            movement,
+            risks: expand(map, repeats),
+            dist: vec![i32::MAX; rows * cols],
+            parent: vec![0; rows * cols],
+        };
+
+        cave.dist[0] = 0;
+        let mut queue = BinaryHeap::new();
+        queue.push(PathNode {
+            risk: 0,
+            pos: (0, 0),
+            from: (0, 0),
+        });
+        cave.relax(queue);
+        cave
+    }
+
+    pub fn risk(&self, row: usize, col: usize) -> i32 {
+        self.risks[row * self.cols + col] as i32
+    }
+
+    /// Lowest total risk from the top left to the bottom right
+    pub fn lowest_risk(&self) -> i32 {
+        self.dist[self.rows * self.cols - 1]
+    }
+
+    /// The cells on a lowest risk path, starting at (0, 0)
+    pub fn route(&self) -> Vec<(usize, usize)> {
+        let mut route = vec![self.rows * self.cols - 1];
+        while let Some(&cell) = route.last().filter(|&&cell| cell != 0) {
+            route.push(self.parent[cell]);
+        }
+        route.reverse();
+        route
+            .into_iter()
+            .map(|cell| (cell / self.cols, cell % self.cols))
+            .collect()
+    }
+
+    /// Change the risk of one cell of the expanded map and bring every lowest risk up to date.
+    ///
+    /// Every way into a cell costs its risk, so lowering it keeps the cell's parent and lowers it
+    /// and whatever it leads to by the same amount, which the search then spreads from the cell.
+    /// Raising it can only hurt the cells reached through it, so those have their risks forgotten
+    /// and are searched again, starting from the best of their neighbors that weren't
+    pub fn set_risk(&mut self, row: usize, col: usize, value: i32) {
+        assert!((0..=9).contains(&value), "Risk levels are a single digit");
+
+        let cell = row * self.cols + col;
+        let old = self.risks[cell] as i32;
+        self.risks[cell] = value as u8;
+        // Nothing is ever charged for the cell the path starts on
+        if cell == 0 || value == old {
+            return;
+        }
+
+        let mut queue = BinaryHeap::new();
+        if value < old {
+            self.dist[cell] -= old - value;
+            queue.push(self.node(cell, self.parent[cell]));
+        } else {
+            let affected = self.subtree(cell);
+            for &a in affected.iter() {
+                self.dist[a] = i32::MAX;
+            }
+            for &a in affected.iter() {
+                let (r, c) = (a / self.cols, a % self.cols);
+                for (nr, nc) in adjacent((r, c), self.rows, self.cols, self.movement) {
+                    let n = nr * self.cols + nc;
+                    if self.dist[n] == i32::MAX {
+                        continue;
+                    }
+                    let risk = self.dist[n] + self.risks[a] as i32;
+                    if risk < self.dist[a] {
+                        self.dist[a] = risk;
+                        self.parent[a] = n;
+                    }
+                }
+                if self.dist[a] != i32::MAX {
+                    queue.push(self.node(a, self.parent[a]));
+                }
+            }
+        }
+        self.relax(queue);
+    }
+
+    fn node(&self, cell: usize, from: usize) -> PathNode {
+        PathNode {
+            risk: self.dist[cell],
+            pos: (cell / self.cols, cell % self.cols),
+            from: (from / self.cols, from % self.cols),
+        }
+    }
+
+    /// `cell` and every cell whose lowest risk path runs through it
+    fn subtree(&self, cell: usize) -> Vec<usize> {
+        let mut subtree = vec![cell];
+        let mut i = 0;
+        while let Some(&next) = subtree.get(i) {
+            let (r, c) = (next / self.cols, next % self.cols);
+            subtree.extend(
+                adjacent((r, c), self.rows, self.cols, self.movement)
+                    .into_iter()
+                    .map(|(nr, nc)| nr * self.cols + nc)
+                    .filter(|&n| n != 0 && self.parent[n] == next),
+            );
+            i += 1;
+        }
+        subtree
+    }
+
+    /// Dijkstra from the cells in `queue`, lowering the risk of every cell it finds a better way
+    /// into. Copies of a cell left behind by a later improvement are skipped
+    fn relax(&mut self, mut queue: BinaryHeap<PathNode>) {
+        while let Some(PathNode {
+            pos: (r, c), risk, ..
+        }) = queue.pop()
+        {
+            let cell = r * self.cols + c;
+            if risk > self.dist[cell] {
+                continue;
+            }
+
+            for (nr, nc) in adjacent((r, c), self.rows, self.cols, self.movement) {
+                let n = nr * self.cols + nc;
+                let next_risk = risk + self.risks[n] as i32;
+                if n == 0 || next_risk >= self.dist[n] {
+                    continue;
+                }
+                self.dist[n] = next_risk;
+                self.parent[n] = cell;
+                queue.push(self.node(n, cell));
+            }
+        }
+    }
+}
+
+/// Same as `lowest_risk` with orthogonal movement, but with the expanded map built up front instead
+/// of working out the risk of a cell every time it is pushed onto the queue. The map is already a
+/// byte a cell, so pruning pushes costs little more and keeps the queue small
+pub fn lowest_risk_materialized(map: &CaveMap, repeats: usize) -> i32 {
+    let cols = map[0].len() * repeats;
+    let expanded = expand(map, repeats);
+    search_by(
+        map.len() * repeats,
+        cols,
+        Movement::Orthogonal,
+        Frontier::Pruned,
+        |row, col| expanded[row * cols + col] as i32,
+        |_, _| {},
+    )
+}
+
+const FORWARD: usize = 0;
+const BACKWARD: usize = 1;
+
+/// Dijkstra run from both corners at once, always expanding the side with the lower risk at the
+/// top of its queue. Entering a cell costs its risk, so going backwards the step from a cell to its
+/// neighbor costs the risk of the cell being left.
+///
+/// Every time one side reaches a cell the other has a tentative risk for, the combined risk is a
+/// candidate path. Once the tops of the two queues add up to at least the best candidate, no
+/// unexplored path can be cheaper and the search stops.
+fn find_lowest_risk_path_bidirectional(map: &CaveMap, repeats: usize) -> i32 {
+    let max_rows = map.len() * repeats;
+    let max_cols = map[0].len() * repeats;
+    let start = (0, 0);
+    let dest = (max_rows - 1, max_cols - 1);
+    if start == dest {
+        return 0;
+    }
+
+    let mut risks = [
+        vec![vec![i32::MAX; max_cols]; max_rows],
+        vec![vec![i32::MAX; max_cols]; max_rows],
+    ];
+    let mut settled = [
+        BitGrid::new(max_rows, max_cols),
+        BitGrid::new(max_rows, max_cols),
+    ];
+    let mut queues = [BinaryHeap::new(), BinaryHeap::new()];
+    for (side, pos) in [(FORWARD, start), (BACKWARD, dest)] {
+        risks[side][pos.0][pos.1] = 0;
+        queues[side].push(PathNode {
+            risk: 0,
+            pos,
+            from: pos,
+        });
+    }
+
+    let mut best = i32::MAX;
+    loop {
+        let side = match (queues[FORWARD].peek(), queues[BACKWARD].peek()) {
+            (Some(fwd), Some(bwd)) if fwd.risk.saturating_add(bwd.risk) < best => {
+                if fwd.risk <= bwd.risk {
+                    FORWARD
+                } else {
+                    BACKWARD
+                }
+            }
+            _ => break,
+        };
+        let other = 1 - side;
+
+        let PathNode {
+            pos: (r, c), risk, ..
+        } = queues[side].pop().unwrap();
+        if !settled[side].insert((r, c)) {
+            continue;
+        }
+
+        for (nr, nc) in adjacent((r, c), max_rows, max_cols, Movement::Orthogonal) {
+            let step = if side == FORWARD {
+                expanded_risk(map, nr, nc)
+            } else {
+                expanded_risk(map, r, c)
+            };
+
+            let next_risk = risk + step;
+            if next_risk < risks[side][nr][nc] {
+                risks[side][nr][nc] = next_risk;
+                queues[side].push(PathNode {
+                    risk: next_risk,
+                    pos: (nr, nc),
+                    from: (r, c),
+                });
+            }
+
+            if risks[other][nr][nc] != i32::MAX {
+                best = best.min(next_risk + risks[other][nr][nc]);
+            }
+        }
+    }
+
+    assert_ne!(best, i32::MAX, "Did not make it to the end");
+    best
+}
+
+/// A* towards the bottom right corner, guessing the lowest risk in the tiled map for every step
+/// still to go. The guess never drops by more than the risk of the step taken, so positions come off the
+/// queue in order of their risk plus guess, and the queue can be a bucket per total instead of a
+/// heap. A position can be in the queue more than once, in which case the later copies are stale
+fn find_lowest_risk_path_astar(map: &CaveMap, repeats: usize) -> i32 {
+    let rows = map.len() * repeats;
+    let cols = map[0].len() * repeats;
+    let dest = (rows - 1, cols - 1);
+    // Tiles further right and down add up to 2 * (repeats - 1) to each risk, which may wrap around
+    let min_risk = (0..2 * repeats as i32 - 1)
+        .flat_map(|added| {
+            map.iter()
+                .flatten()
+                .map(move |&risk| wrap_risk(risk + added))
+        })
+        .min()
+        .unwrap_or(0);
+    let guess = |(r, c): (usize, usize)| min_risk * (dest.0 - r + dest.1 - c) as i32;
+
+    let mut risks = vec![i32::MAX; rows * cols];
+    risks[0] = 0;
+    let mut buckets = vec![Vec::new(); guess((0, 0)) as usize + 1];
+    buckets[guess((0, 0)) as usize].push((0, 0));
+
+    let mut total = 0;
+    while total < buckets.len() {
+        while let Some((r, c)) = buckets[total].pop() {
+            let risk = risks[r * cols + c];
+            if (risk + guess((r, c))) as usize != total {
+                continue;
+            }
+            if (r, c) == dest {
+                return risk;
+            }
+
+            for (nr, nc) in adjacent((r, c), rows, cols, Movement::Orthogonal) {
+                let next_risk = risk + expanded_risk(map, nr, nc);
+                if next_risk < risks[nr * cols + nc] {
+                    risks[nr * cols + nc] = next_risk;
+                    let next_total = (next_risk + guess((nr, nc))) as usize;
+                    if next_total >= buckets.len() {
+                        buckets.resize(next_total + 1, Vec::new());
+                    }
+                    buckets[next_total].push((nr, nc));
+                }
+            }
+        }
+        total += 1;
+    }
+
+    panic!("Did not make it to the end");
+}
+
+/// Returns the lowest total risk along with the positions on that path, starting at (0, 0)
+#[cfg(feature = "viz")]
+fn find_lowest_risk_route(map: &CaveMap, repeats: usize) -> (i32, Vec<(usize, usize)>) {
+    let mut came_from = vec![vec![(0, 0); map[0].len() * repeats]; map.len() * repeats];
+    let risk = search(
+        map,
+        repeats,
+        Movement::Orthogonal,
+        Frontier::Lazy,
+        |(r, c), from| came_from[r][c] = from,
+    );
+
+    let mut route = vec![(came_from.len() - 1, came_from[0].len() - 1)];
+    while let Some(&(r, c)) = route.last().filter(|&&pos| pos != (0, 0)) {
+        route.push(came_from[r][c]);
+    }
+    route.reverse();
+
+    (risk, route)
+}
+
+#[cfg(feature = "viz")]
+fn render(map: &CaveMap, repeats: usize) -> crate::viz::Canvas {
+    use crate::viz::{gray, Canvas, RED};
+
+    let rows = map.len() * repeats;
+    let cols = map[0].len() * repeats;
+    let mut canvas = Canvas::new(cols, rows);
+    for row in 0..rows {
+        for col in 0..cols {
+            let risk = expanded_risk(map, row, col);
+            let c = std::char::from_digit(risk as u32, 10).unwrap();
+            canvas.set(col, row, c, gray(risk as usize, 12));
+        }
+    }
+
+    let (_, route) = find_lowest_risk_route(map, repeats);
+    for (row, col) in route {
+        let (_, color) = canvas.get(col, row);
+        canvas.set(col, row, '*', [RED[0], color[1] / 2, color[2] / 2]);
+    }
+    canvas
+}
+
+/// The optimal path drawn over the map, then over the map expanded 5 times
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    let map = match cave_map(input) {
+        Ok(map) if !map.is_empty() => map,
+        _ => return Vec::new(),
+    };
+    vec![render(&map, 1), render(&map, 5)]
+}
+
+/// Rows of risk levels, one digit per cell. The digits in a row may be separated by whitespace or
+/// commas, and every row must have as many as the first
+#[aoc_generator(day15)]
+pub(crate) fn cave_map(input: &str) -> Result<CaveMap, ParseError> {
+    let mut map = CaveMap::new();
+    for (i, line) in input.lines().enumerate() {
+        let row = line
+            .chars()
+            .filter(|&c| !c.is_whitespace() && c != ',')
+            .map(|c| {
+                c.to_digit(10).map(|risk| risk as i32).ok_or_else(|| {
+                    ParseError::at_line(i + 1, format!("invalid risk level {:?} in {:?}", c, line))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if row.is_empty() {
+            continue;
+        }
+
+        if let Some(first) = map.first().filter(|first| first.len() != row.len()) {
+            return Err(ParseError::at_line(
+                i + 1,
+                format!(
+                    "expected {} risk levels, found {} in {:?}",
+                    first.len(),
+                    row.len(),
+                    line
+                ),
+            ));
+        }
+        map.push(row);
+    }
+
+    Ok(map)
+}
+
+#[aoc(day15, part1)]
+pub(crate) fn part1(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| find_lowest_risk_path(map, 1))
+}
+
+/// Building the 25x larger map up front is cheap next to the search, and takes the wrap-around
+/// arithmetic out of the hot loop, so it beats `OnTheFly` on a map the size of the real input. See
+/// `benches/day15.rs`
+#[aoc(day15, part2)]
+pub(crate) fn part2(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| lowest_risk_materialized(map, 5))
+}
+
+#[aoc(day15, part2, OnTheFly)]
+fn part2_on_the_fly(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| find_lowest_risk_path(map, 5))
+}
+
+#[aoc(day15, part1, Bidirectional)]
+fn part1_bidirectional(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| find_lowest_risk_path_bidirectional(map, 1))
+}
+
+#[aoc(day15, part2, Bidirectional)]
+fn part2_bidirectional(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| find_lowest_risk_path_bidirectional(map, 5))
+}
+
+#[aoc(day15, part1, AStar)]
+fn part1_astar(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| find_lowest_risk_path_astar(map, 1))
+}
+
+#[aoc(day15, part2, AStar)]
+fn part2_astar(map: &CaveMap) -> Option<i32> {
+    (!map.is_empty()).then(|| find_lowest_risk_path_astar(map, 5))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day15.txt");
+
+    /// The first five rows of the example
+    fn example_top_half() -> CaveMap {
+        cave_map(&EXAMPLE.lines().take(5).collect::<Vec<_>>().join("\n")).unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let input = cave_map(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), Some(40));
+        assert_eq!(part2(&input), Some(315));
+        assert_eq!(part2_on_the_fly(&input), Some(315));
+        assert_eq!(part1_bidirectional(&input), Some(40));
+        assert_eq!(part2_bidirectional(&input), Some(315));
+    }
+
+    #[test]
+    fn bidirectional_random_maps() {
+        let mut rng = Xorshift::default();
+
+        for _ in 0..50 {
+            let rows = 1 + rng.next_u32() as usize % 12;
+            let cols = 1 + rng.next_u32() as usize % 12;
+            let map = (0..rows)
+                .map(|_| (0..cols).map(|_| 1 + (rng.next_u32() % 9) as i32).collect())
+                .collect::<CaveMap>();
+
+            for repeats in [1, 2, 5] {
+                assert_eq!(
+                    lowest_risk_materialized(&map, repeats),
+                    find_lowest_risk_path(&map, repeats)
+                );
+                assert_eq!(
+                    find_lowest_risk_path_bidirectional(&map, repeats),
+                    find_lowest_risk_path(&map, repeats),
+                    "{:?} x{}",
+                    map,
+                    repeats
+                );
+                assert_eq!(
+                    find_lowest_risk_path_astar(&map, repeats),
+                    find_lowest_risk_path(&map, repeats),
+                    "{:?} x{}",
+                    map,
+                    repeats
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn diagonal() {
+        let map = cave_map("199\n919\n991").unwrap();
+        assert_eq!(lowest_risk(&map, 1, Movement::Orthogonal), 20);
+        assert_eq!(lowest_risk(&map, 1, Movement::Diagonal), 2);
+
+        let map = example_top_half();
+        for repeats in [1, 5] {
+            assert!(
+                lowest_risk(&map, repeats, Movement::Diagonal)
+                    < lowest_risk(&map, repeats, Movement::Orthogonal)
+            );
+            for movement in [Movement::Orthogonal, Movement::Diagonal] {
+                assert_eq!(
+                    lowest_risk_with(&map, repeats, movement, Frontier::Pruned),
+                    lowest_risk(&map, repeats, movement)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn edits() {
+        let map = cave_map("111\n191\n111").unwrap();
+        let mut cave = CaveMapExt::new(&map, 1, Movement::Orthogonal);
+        assert_eq!(cave.lowest_risk(), 4);
+
+        // Blocking the top sends the path around the bottom, and opening the middle lets it cut
+        // through
+        cave.set_risk(0, 1, 5);
+        assert_eq!(cave.lowest_risk(), 4);
+        assert_eq!(cave.route(), [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]);
+        cave.set_risk(2, 1, 5);
+        assert_eq!(cave.lowest_risk(), 8);
+        cave.set_risk(1, 1, 1);
+        assert_eq!(cave.lowest_risk(), 4);
+        assert_eq!(cave.route(), [(0, 0), (1, 0), (1, 1), (1, 2), (2, 2)]);
+        cave.set_risk(1, 1, 9);
+        assert_eq!(cave.lowest_risk(), 8);
+        // The start is never charged for
+        cave.set_risk(0, 0, 9);
+        assert_eq!(cave.lowest_risk(), 8);
+
+        let example = example_top_half();
+        assert_eq!(
+            CaveMapExt::new(&example, 5, Movement::Orthogonal).lowest_risk(),
+            lowest_risk(&example, 5, Movement::Orthogonal)
+        );
+
+        let mut rng = Xorshift::new(0x5f3759df);
+        for movement in [Movement::Orthogonal, Movement::Diagonal] {
+            for repeats in [1, 2] {
+                let mut cave = CaveMapExt::new(&example, repeats, movement);
+                for _ in 0..200 {
+                    let row = rng.next_u32() as usize % cave.rows;
+                    let col = rng.next_u32() as usize % cave.cols;
+                    cave.set_risk(row, col, (rng.next_u32() % 10) as i32);
+
+                    // The same as searching the edited map from scratch
+                    let edited = (0..cave.rows)
+                        .map(|row| (0..cave.cols).map(|col| cave.risk(row, col)).collect())
+                        .collect::<CaveMap>();
+                    let fresh = CaveMapExt::new(&edited, 1, movement);
+                    assert_eq!(cave.dist, fresh.dist);
+                    assert_eq!(cave.lowest_risk(), lowest_risk(&edited, 1, movement));
+                    assert_eq!(
+                        cave.route()
+                            .iter()
+                            .skip(1)
+                            .map(|&(row, col)| cave.risk(row, col))
+                            .sum::<i32>(),
+                        cave.lowest_risk()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let map = cave_map("116\n138\n213").unwrap();
+        assert_eq!(cave_map("1 1 6\n1,3,8\n 2, 1, 3 \n\n"), Ok(map.clone()));
+        assert_eq!(cave_map("116\r\n138\r\n213\r\n"), Ok(map));
+        assert_eq!(cave_map(""), Ok(Vec::new()));
+        // An empty map has no corners to find a path between
+        assert_eq!(part1(&Vec::new()), None);
+
+        let err = cave_map("116\n13\n213").unwrap_err();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.message(), "expected 3 risk levels, found 2 in \"13\"");
+
+        let err = cave_map("116\n138\n2134").unwrap_err();
+        assert_eq!(err.line(), 3);
+
+        let err = cave_map("116\n1;8\n213").unwrap_err();
+        assert_eq!(err.line(), 2);
+        assert_eq!(err.message(), "invalid risk level ';' in \"1;8\"");
+
+        assert_eq!(
+            crate::solve(2021, 15, 1, "116\n13\n213"),
+            Err(crate::SolveError::BadInput(
+                "line 2: expected 3 risk levels, found 2 in \"13\"".to_string()
+            ))
+        );
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn route() {
+        let map = cave_map("116\n138\n213").unwrap();
+        let (risk, route) = find_lowest_risk_route(&map, 1);
+        assert_eq!(risk, 7);
+        assert_eq!(route, [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(
+            route.iter().skip(1).map(|&(r, c)| map[r][c]).sum::<i32>(),
+            risk
+        );
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn render_route() {
+        let frames = render_frames("116\n138\n213");
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].to_ascii(), "*16\n*38\n***\n");
+        assert_eq!(frames[1].width(), 15);
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day15.txt");
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(
+            cave_map(EXAMPLE).unwrap(),
+            part1,
+            part1_bidirectional,
+            part1_astar
+        );
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(
+            cave_map(EXAMPLE).unwrap(),
+            part2,
+            part2_on_the_fly,
+            part2_bidirectional,
+            part2_astar
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::error::ParseError;
+
+#[derive(Debug)]
+pub struct TargetArea {
+    top_left: (i64, i64),
+    bot_right: (i64, i64),
+}
+
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pos: (i64, i64),
+    vel: (i64, i64),
+}
+
+/// How a probe launched at some velocity does against the target area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shot {
+    /// Inside the target after `steps` steps, having reached a height of `apex` on the way
+    Hit { steps: usize, apex: i64 },
+    /// Flew past the target, or dropped through it between two steps
+    Overshoot,
+    /// Fell below the target before reaching it
+    Undershoot,
+}
+
+impl Probe {
+    pub fn with_vel(vel: (i64, i64)) -> Self {
+        Probe { pos: (0, 0), vel }
+    }
+
+    /// Every position the probe passes through, starting with the one after the first step. It
+    /// never ends, since the probe keeps falling
+    pub fn trajectory(mut self) -> impl Iterator<Item = (i64, i64)> {
+        std::iter::repeat_with(move || {
+            self.step();
+            self.pos
+        })
+    }
+
+    fn x(&self) -> i64 {
+        self.pos.0
+    }
+
+    fn y(&self) -> i64 {
+        self.pos.1
+    }
+
+    fn dx(&self) -> i64 {
+        self.vel.0
+    }
+
+    fn step(&mut self) {
+        self.pos.0 += self.vel.0;
+        self.pos.1 += self.vel.1;
+        self.vel.0 -= self.vel.0.signum();
+        self.vel.1 -= 1;
+    }
+}
+
+impl TargetArea {
+    fn contains(&self, probe: &Probe) -> bool {
+        (self.top_left.0..=self.bot_right.0).contains(&probe.x())
+            && (self.bot_right.1..=self.top_left.1).contains(&probe.y())
+    }
+
+    fn can_hit(&self, probe: &Probe) -> bool {
+        ((probe.x() <= self.bot_right.0 && probe.dx() >= 0)
+            || (probe.x() >= self.top_left.0 && probe.dx() <= 0))
+            && (probe.y() >= self.bot_right.1)
+    }
+
+    pub fn evaluate(&self, vel: (i64, i64)) -> Shot {
+        let mut probe = Probe::with_vel(vel);
+        let mut apex = 0;
+        let mut steps = 0;
+        while self.can_hit(&probe) {
+            probe.step();
+            steps += 1;
+            apex = apex.max(probe.y());
+
+            if self.contains(&probe) {
+                return Shot::Hit { steps, apex };
+            }
+        }
+
+        // Short of the near edge of the target, whichever side of the launch it's on
+        let short = if self.top_left.0 > 0 {
+            probe.x() < self.top_left.0
+        } else {
+            self.bot_right.0 < 0 && probe.x() > self.bot_right.0
+        };
+        if short {
+            Shot::Undershoot
+        } else {
+            Shot::Overshoot
+        }
+    }
+
+    /// How many steps a probe launched at `vel` spends inside the target, following it past the
+    /// first step inside until it can't come back
+    pub fn dwell(&self, vel: (i64, i64)) -> usize {
+        let mut probe = Probe::with_vel(vel);
+        let mut inside = 0;
+        while self.can_hit(&probe) {
+            probe.step();
+            if self.contains(&probe) {
+                inside += 1;
+            }
+        }
+        inside
+    }
+}
+
+/// The fastest (x, y) launch worth trying, or None if a probe launched that fast could end up
+/// further away than an i64 holds
+fn max_velocities(target_area: &TargetArea) -> Option<(i64, i64)> {
+    // Probes only pass the far edge by one step, so x never gets past twice the target's reach,
+    // and the highest shot peaks at max_y(max_y + 1) / 2
+    let max_y = target_area.bot_right.1.checked_abs()?;
+    max_y.checked_mul(max_y)?.checked_add(max_y)?;
+    target_area.top_left.0.checked_mul(2)?;
+    target_area.bot_right.0.checked_mul(2)?;
+    Some((target_area.bot_right.0, max_y))
+}
+
+/// The x velocities that could reach the target. Drag stops a probe launched at dx after
+/// dx(dx + 1) / 2, so it needs at least about √(2·near) to get to the near edge, and anything past
+/// the far edge overshoots on the first step. A target above or below the launch can be reached
+/// from any x velocity that starts out inside it
+fn x_velocities(target_area: &TargetArea) -> std::ops::RangeInclusive<i64> {
+    let (left, right) = (target_area.top_left.0, target_area.bot_right.0);
+    let slowest = |near: u64| ((2. * near as f64).sqrt().floor() as i64 - 1).max(0);
+    if left > 0 {
+        slowest(left.unsigned_abs())..=right
+    } else if right < 0 {
+        left..=-slowest(right.unsigned_abs())
+    } else {
+        left..=right
+    }
+}
+
+/// Every velocity that could reach the target with the step count and apex of those that do, or
+/// None if the target is too far away to search
+fn find_all_hits(
+    target_area: &TargetArea,
+) -> Option<impl Iterator<Item = ((i64, i64), usize, i64)> + '_> {
+    let (_, max_y) = max_velocities(target_area)?;
+
+    let hits = x_velocities(target_area)
+        .flat_map(move |dx| (-max_y..=max_y).map(move |dy| (dx, dy)))
+        .filter_map(|vel| match target_area.evaluate(vel) {
+            Shot::Hit { steps, apex } => Some((vel, steps, apex)),
+            Shot::Overshoot | Shot::Undershoot => None,
+        });
+    Some(hits)
+}
+
+/// The hitting velocity whose probe spends the most steps inside the target, with the number of
+/// steps. Ties go to the smallest velocity, comparing x before y
+pub fn longest_dwell(target_area: &TargetArea) -> Option<((i64, i64), usize)> {
+    find_all_hits(target_area)?
+        .map(|(vel, _, _)| (vel, target_area.dwell(vel)))
+        .min_by_key(|&(vel, inside)| (std::cmp::Reverse(inside), vel))
+}
+
+/// `min..max`, or None if it isn't a range of integers with min no greater than max
+fn split_range_str(range: &str) -> Option<(i64, i64)> {
+    let (min_str, max_str) = range.split_once("..")?;
+    let (min, max) = (min_str.parse::<i64>().ok()?, max_str.parse::<i64>().ok()?);
+    (min <= max).then_some((min, max))
+}
+
+#[aoc_generator(day17)]
+pub(crate) fn target_area(input: &str) -> Result<TargetArea, ParseError> {
+    let ranges = input
+        .trim()
+        .strip_prefix("target area: ")
+        .and_then(|ranges| ranges.split_once(','))
+        .and_then(|(x_range, y_range)| {
+            let x = split_range_str(x_range.trim().strip_prefix("x=")?)?;
+            let y = split_range_str(y_range.trim().strip_prefix("y=")?)?;
+            Some((x, y))
+        });
+    let ((x_min, x_max), (y_min, y_max)) = ranges.ok_or_else(|| {
+        ParseError::at_line(
+            1,
+            format!("expected a target area, found {:?}", input.trim()),
+        )
+    })?;
+
+    Ok(TargetArea {
+        top_left: (x_min, y_max),
+        bot_right: (x_max, y_min),
+    })
+}
+
+#[aoc(day17, part1)]
+pub(crate) fn part1(target_area: &TargetArea) -> Option<i64> {
+    // Find the highest Y-position that is reachable while still hitting the target area
+    let apex = find_all_hits(target_area)?
+        .map(|(_, _, apex)| apex)
+        .max()
+        .unwrap_or(0);
+    Some(apex)
+}
+
+#[aoc(day17, part2)]
+pub(crate) fn part2(target_area: &TargetArea) -> Option<i64> {
+    Some(find_all_hits(target_area)?.count() as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trajectory() {
+        let path = Probe::with_vel((7, 2))
+            .trajectory()
+            .take(8)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            path,
+            [
+                (7, 2),
+                (13, 3),
+                (18, 3),
+                (22, 2),
+                (25, 0),
+                (27, -3),
+                (28, -7),
+                (28, -12)
+            ]
+        );
+    }
+
+    #[test]
+    fn shots() {
+        let target = target_area("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!(target.evaluate((7, 2)), Shot::Hit { steps: 7, apex: 3 });
+        assert_eq!(
+            target.evaluate((6, 9)),
+            Shot::Hit {
+                steps: 20,
+                apex: 45
+            }
+        );
+        assert_eq!(target.evaluate((30, -10)), Shot::Hit { steps: 1, apex: 0 });
+
+        // Flies straight past
+        assert_eq!(target.evaluate((17, -4)), Shot::Overshoot);
+        // Falls so fast it goes from above the target to below it in one step
+        assert_eq!(target.evaluate((25, -20)), Shot::Overshoot);
+        assert_eq!(target.evaluate((6, 10)), Shot::Overshoot);
+
+        // Stalls before the near edge
+        assert_eq!(target.evaluate((5, 0)), Shot::Undershoot);
+        assert_eq!(target.evaluate((7, -10)), Shot::Undershoot);
+
+        // A target behind the launch
+        let target = target_area("target area: x=-30..-20, y=-10..-5").unwrap();
+        assert_eq!(target.evaluate((-7, 2)), Shot::Hit { steps: 7, apex: 3 });
+        assert_eq!(target.evaluate((-5, 0)), Shot::Undershoot);
+
+        // The mirror image of the example has the same hits, mirrored
+        let mut hits = find_all_hits(&target)
+            .unwrap()
+            .map(|((dx, dy), steps, apex)| ((-dx, dy), steps, apex))
+            .collect::<Vec<_>>();
+        hits.sort_unstable();
+        let example = target_area("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!(hits, find_all_hits(&example).unwrap().collect::<Vec<_>>());
+        assert_eq!((part1(&target), part2(&target)), (Some(45), Some(112)));
+
+        // Straight up or down, with a target across the launch
+        let target = target_area("target area: x=-2..1, y=-10..-5").unwrap();
+        assert_eq!(target.evaluate((0, 3)), Shot::Hit { steps: 9, apex: 6 });
+        assert!(find_all_hits(&target)
+            .unwrap()
+            .all(|((dx, _), _, _)| (-2..=1).contains(&dx)));
+        assert_eq!(part1(&target), Some(45));
+
+        // Too far away for the probe's position to fit an i64
+        for far in [
+            "target area: x=1..2, y=-9223372036854775808..0",
+            "target area: x=1..2, y=-9223372036854775807..0",
+            "target area: x=1..9223372036854775807, y=-10..-5",
+            "target area: x=-9223372036854775808..-1, y=-10..-5",
+        ] {
+            let target = target_area(far).unwrap();
+            assert_eq!((part1(&target), part2(&target)), (None, None), "{}", far);
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(target_area("target area: x=20..30").is_err());
+        assert!(target_area("target area: x=20..30, y=-5..-10").is_err());
+        assert!(target_area("x=20..30, y=-10..-5").is_err());
+        assert!(target_area("target area: x=20..30, y=-10..-5\n").is_ok());
+    }
+
+    #[test]
+    fn dwell() {
+        let target = target_area("target area: x=20..30, y=-10..-5").unwrap();
+        // x stalls at 28 and the probe drops through y = -6 and -10
+        assert_eq!(target.dwell((7, 0)), 2);
+        assert_eq!(target.dwell((7, 2)), 1);
+        assert_eq!(target.dwell((17, -4)), 0);
+
+        for (vel, _, _) in find_all_hits(&target).unwrap() {
+            let inside = Probe::with_vel(vel)
+                .trajectory()
+                .take(100)
+                .filter(|&(x, y)| (20..=30).contains(&x) && (-10..=-5).contains(&y))
+                .count();
+            assert_eq!(target.dwell(vel), inside, "{:?}", vel);
+        }
+
+        // Several velocities spend two steps inside. (6, 1) is the first, reaching (20, -5) and
+        // then (21, -9)
+        assert_eq!(longest_dwell(&target), Some(((6, 1), 2)));
+    }
+}
@@ -0,0 +1,408 @@
+use crate::algo::snailfish::{add_numbers, magnitude_bound};
+pub use crate::algo::snailfish::{parse_pairs, ArenaNumber, LeafWeight, Number, ParseNumberError};
+use crate::error::ParseError;
+
+/// One number per line, with at least one line to add up
+#[aoc_generator(day18)]
+pub(crate) fn fish_math(input: &str) -> Result<Vec<Number>, ParseError> {
+    let numbers = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            parse_pairs(line).map_err(|err| ParseError::at_line(i + 1, err.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if numbers.is_empty() {
+        return Err(ParseError::at_line(1, "no numbers to add"));
+    }
+    Ok(numbers)
+}
+
+/// None if a regular number or the magnitude gets too large for an i64
+#[aoc(day18, part1)]
+pub(crate) fn part1(numbers: &[Number]) -> Option<i64> {
+    let mut result = numbers[0].clone();
+    for num in &numbers[1..] {
+        result = add_numbers(result, num.clone())?;
+    }
+    result.magnitude()
+}
+
+/// None without two numbers to add, or if any sum overflows an i64
+#[aoc(day18, part2)]
+pub(crate) fn part2(numbers: &[Number]) -> Option<i64> {
+    let mut max_magnitude = None;
+    for i in 0..numbers.len() {
+        for j in 0..numbers.len() {
+            if i == j {
+                continue;
+            }
+
+            let mag = add_numbers(numbers[i].clone(), numbers[j].clone())?.magnitude()?;
+            max_magnitude = max_magnitude.max(Some(mag));
+        }
+    }
+    max_magnitude
+}
+
+#[aoc(day18, part1, Arena)]
+pub(crate) fn part1_arena(numbers: &[Number]) -> Option<i64> {
+    let numbers = numbers.iter().map(ArenaNumber::from).collect::<Vec<_>>();
+    numbers[1..]
+        .iter()
+        .try_fold(numbers[0].clone(), |sum, num| sum.checked_add(num))?
+        .magnitude()
+}
+
+#[aoc(day18, part2, Arena)]
+pub(crate) fn part2_arena(numbers: &[Number]) -> Option<i64> {
+    let numbers = numbers.iter().map(ArenaNumber::from).collect::<Vec<_>>();
+    let mut max_magnitude = None;
+    for (i, lhs) in numbers.iter().enumerate() {
+        for (j, rhs) in numbers.iter().enumerate() {
+            if i != j {
+                let magnitude = lhs.clone().checked_add(rhs)?.magnitude()?;
+                max_magnitude = max_magnitude.max(Some(magnitude));
+            }
+        }
+    }
+    max_magnitude
+}
+
+/// `part2_arena`, trying the pairs with the largest magnitude before reducing first and skipping
+/// any whose `magnitude_bound` can't beat the best so far. The magnitude before reducing only
+/// orders the search: splits raise the magnitude, so it isn't a bound on the reduced sum
+#[aoc(day18, part2, Pruned)]
+fn part2_pruned(numbers: &[Number]) -> Option<i64> {
+    let numbers = numbers.iter().map(ArenaNumber::from).collect::<Vec<_>>();
+    // Widened so the ordering of numbers too large for an i64 doesn't overflow
+    let magnitudes = numbers
+        .iter()
+        .map(|number| number.magnitude().map_or(i128::MAX / 8, i128::from))
+        .collect::<Vec<_>>();
+    let totals = numbers.iter().map(ArenaNumber::total).collect::<Vec<_>>();
+
+    let n = numbers.len();
+    let mut pairs = (0..n)
+        .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|&(i, j)| std::cmp::Reverse(3 * magnitudes[i] + 2 * magnitudes[j]));
+
+    let mut max_magnitude = None;
+    for (i, j) in pairs {
+        // A regular number can't grow past the total, so a sum whose total fits can't overflow
+        if let Some(total) = totals[i].checked_add(totals[j]) {
+            if Some(magnitude_bound(total)) <= max_magnitude {
+                continue;
+            }
+        }
+        let magnitude = numbers[i].clone().checked_add(&numbers[j])?.magnitude()?;
+        max_magnitude = max_magnitude.max(Some(magnitude));
+    }
+    max_magnitude
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day18.txt");
+
+    fn result(input: &str) -> String {
+        let nums = fish_math(input).unwrap();
+        let mut result = nums[0].clone();
+        for num in &nums[1..] {
+            println!("\n\nAdd: {}, {}", result, num);
+            result = add_numbers(result, num.clone()).unwrap();
+        }
+
+        result.to_string()
+    }
+
+    #[test]
+    fn small_examples() {
+        assert_eq!(
+            result("[1,1]\n[2,2]\n[3,3]\n[4,4]"),
+            "[[[[1,1],[2,2]],[3,3]],[4,4]]"
+        );
+        assert_eq!(
+            result("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]"),
+            "[[[[3,0],[5,3]],[4,4]],[5,5]]"
+        );
+        assert_eq!(
+            result("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]"),
+            "[[[[5,0],[7,4]],[5,5]],[6,6]]"
+        );
+        assert_eq!(
+            result("[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]"),
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+        );
+        assert_eq!(
+            result("[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]\n[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]"),
+            "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]"
+        );
+        assert_eq!(result(
+            "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]\n[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]"),
+            "[[[[6,7],[6,7]],[[7,7],[0,7]]],[[[8,7],[7,7]],[[8,8],[8,0]]]]");
+
+        //[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]
+        //[7,[5,[[3,8],[1,4]]]]
+        //[[2,[2,2]],[8,[8,1]]]
+        //[2,9]
+        //[1,[[[9,3],9],[[9,0],[0,7]]]]
+        //[[[5,[7,4]],7],1]
+        //[[[[4,2],2],6],[8,7]]
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(parse_pairs("[1,2]").unwrap().to_string(), "[1,2]");
+        assert_eq!(parse_pairs("[1,2").err(), Some(ParseNumberError(5)));
+        assert_eq!(parse_pairs("[1,2]]").err(), Some(ParseNumberError(6)));
+        assert_eq!(parse_pairs("[[1,2]").err(), Some(ParseNumberError(7)));
+        assert_eq!(parse_pairs("[1;2]").err(), Some(ParseNumberError(3)));
+        assert_eq!(parse_pairs("").err(), Some(ParseNumberError(1)));
+
+        // Nested too deep, failing at the first pair past the limit
+        use crate::algo::snailfish::MAX_NESTING;
+        let nested = |depth| format!("{}1{}", "[1,".repeat(depth), "]".repeat(depth));
+        assert!(parse_pairs(&nested(MAX_NESTING)).is_ok());
+        assert_eq!(
+            parse_pairs(&nested(MAX_NESTING + 1)).err(),
+            Some(ParseNumberError(3 * MAX_NESTING + 1))
+        );
+        assert_eq!(
+            parse_pairs(&nested(100_000)).err(),
+            Some(ParseNumberError(3 * MAX_NESTING + 1))
+        );
+
+        let err = fish_math("[1,2]\n[3,x]").err().unwrap();
+        assert_eq!(err.line(), 2);
+        assert_eq!(fish_math("").err().map(|err| err.line()), Some(1));
+    }
+
+    #[test]
+    fn multi_digit() {
+        for number in [
+            "[13,2]",
+            "[[0,[5,10]],[123,7]]",
+            "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+        ] {
+            assert_eq!(parse_pairs(number).unwrap().to_string(), number);
+        }
+
+        // Too large to hold
+        assert_eq!(
+            parse_pairs("[1,99999999999999999999]").err(),
+            Some(ParseNumberError(4))
+        );
+
+        // Leading zeros
+        assert_eq!(parse_pairs("[01,2]").err(), Some(ParseNumberError(2)));
+        assert_eq!(parse_pairs("[1,[00,2]]").err(), Some(ParseNumberError(5)));
+    }
+
+    #[test]
+    fn overflow() {
+        // Too large a magnitude, and an explosion carrying into a regular number that can't take it
+        let max = i64::MAX;
+        let numbers = fish_math(&format!("[{},1]", max)).unwrap();
+        assert_eq!(part1(&numbers), None);
+        assert_eq!(part1_arena(&numbers), None);
+        let numbers = fish_math(&format!("[[[[[1,{max}],{max}],0],0],0]\n[1,1]")).unwrap();
+        assert_eq!(part1(&numbers), None);
+        assert_eq!(part1_arena(&numbers), None);
+        assert_eq!(part2(&numbers), None);
+        assert_eq!(part2_arena(&numbers), None);
+        assert_eq!(part2_pruned(&numbers), None);
+
+        // Nothing to add a single number to
+        let numbers = fish_math("[1,2]").unwrap();
+        assert_eq!(part1(&numbers), Some(7));
+        assert_eq!(part2(&numbers), None);
+        assert_eq!(part2_arena(&numbers), None);
+        assert_eq!(part2_pruned(&numbers), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let number = parse_pairs("[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]").unwrap();
+        let json = serde_json::to_string(&number).unwrap();
+        let number: Number = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            number.to_string(),
+            "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]"
+        );
+    }
+
+    #[test]
+    fn example() {
+        let input = EXAMPLE;
+        assert_eq!(
+            result(input),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+
+        assert_eq!(part1(&fish_math(input).unwrap()), Some(4140));
+        assert_eq!(part2(&fish_math(input).unwrap()), Some(3993));
+    }
+
+    #[test]
+    fn leaf_weights() {
+        let leaf = |path: &str, value, weight| LeafWeight {
+            path: path.to_string(),
+            value,
+            weight,
+        };
+
+        let number = parse_pairs("[[1,2],[[3,4],5]]").unwrap();
+        assert_eq!(
+            number.leaf_weights().unwrap(),
+            [
+                leaf("LL", 1, 9),
+                leaf("LR", 2, 6),
+                leaf("RLL", 3, 18),
+                leaf("RLR", 4, 12),
+                leaf("RR", 5, 4),
+            ]
+        );
+        assert_eq!(
+            number.top_contributors(2).unwrap(),
+            [leaf("RLL", 3, 18), leaf("RLR", 4, 12)]
+        );
+        assert_eq!(
+            parse_pairs("7").unwrap().leaf_weights().unwrap(),
+            [leaf("", 7, 1)]
+        );
+        // The 2 and the 3 both add 18, and the one on the left comes first
+        assert_eq!(
+            parse_pairs("[[2,3],[1,9]]")
+                .unwrap()
+                .top_contributors(3)
+                .unwrap(),
+            [leaf("RR", 9, 4), leaf("LL", 2, 9), leaf("LR", 3, 6)]
+        );
+
+        let sum = fish_math(EXAMPLE)
+            .unwrap()
+            .into_iter()
+            .reduce(|sum, num| add_numbers(sum, num).unwrap())
+            .unwrap();
+        let leaves = sum.leaf_weights().unwrap();
+        assert_eq!(
+            leaves
+                .iter()
+                .try_fold(0i64, |total, leaf| total.checked_add(leaf.contribution()?)),
+            sum.magnitude()
+        );
+        // Fully reduced, so no leaf is deeper than four pairs, where the weights are 3s and 2s
+        assert!(leaves.iter().all(|leaf| leaf.path.len() <= 4));
+        assert_eq!(leaves[0], leaf("LLLL", 6, 81));
+        let top = sum.top_contributors(3).unwrap();
+        assert_eq!(top.len(), 3);
+        assert!(top
+            .windows(2)
+            .all(|w| w[0].contribution() >= w[1].contribution()));
+        assert!(leaves
+            .iter()
+            .all(|leaf| leaf.contribution() <= top[2].contribution() || top.contains(leaf)));
+
+        // Weights past 39 left turns and contributions of huge values don't fit in an i64
+        let deep = parse_pairs(&format!("{}1,1]{}", "[".repeat(40), ",1]".repeat(39))).unwrap();
+        assert_eq!(deep.leaf_weights(), None);
+        assert_eq!(deep.top_contributors(1), None);
+        let huge = parse_pairs(&format!("[{},1]", i64::MAX)).unwrap();
+        let leaves = huge.leaf_weights().unwrap();
+        assert_eq!(leaves[0].contribution(), None);
+        assert_eq!(leaves[1].contribution(), Some(2));
+        assert_eq!(huge.top_contributors(1), None);
+    }
+
+    #[test]
+    fn arena() {
+        let inputs = [
+            "[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]",
+            "[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]",
+            "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]\n[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+            EXAMPLE,
+        ];
+        for input in inputs {
+            let nums = fish_math(input).unwrap();
+            let mut boxed = nums[0].clone();
+            let mut arena = ArenaNumber::from(&nums[0]);
+            for num in &nums[1..] {
+                boxed = add_numbers(boxed, num.clone()).unwrap();
+                arena = arena + &ArenaNumber::from(num);
+                assert_eq!(arena.to_string(), boxed.to_string());
+                assert_eq!(arena.magnitude(), boxed.magnitude());
+            }
+        }
+
+        let number: ArenaNumber = "[[1,2],[[3,4],5]]".parse().unwrap();
+        assert_eq!(number.magnitude(), Some(143));
+        assert_eq!(part1_arena(&fish_math(EXAMPLE).unwrap()), Some(4140));
+        assert_eq!(part2_arena(&fish_math(EXAMPLE).unwrap()), Some(3993));
+    }
+
+    #[test]
+    fn pruned() {
+        let numbers = fish_math(EXAMPLE)
+            .unwrap()
+            .iter()
+            .map(ArenaNumber::from)
+            .collect::<Vec<_>>();
+        for lhs in numbers.iter() {
+            for rhs in numbers.iter() {
+                let sum = lhs.clone() + rhs;
+                assert!(sum.magnitude().unwrap() <= magnitude_bound(lhs.total() + rhs.total()));
+                assert!(sum.total() <= lhs.total() + rhs.total());
+            }
+        }
+
+        // The 2 exploding out of the pair four deep lands on the heavier 8 to its right, which splits
+        let lhs: ArenaNumber = "[7,8]".parse().unwrap();
+        let rhs: ArenaNumber = "[[[0,[0,2]],8],0]".parse().unwrap();
+        assert_eq!(
+            3 * lhs.magnitude().unwrap() + 2 * rhs.magnitude().unwrap(),
+            351
+        );
+        assert_eq!((lhs + &rhs).magnitude(), Some(411));
+
+        assert_eq!(magnitude_bound(0), 0);
+        assert_eq!(magnitude_bound(9), 81 * 9);
+        assert_eq!(magnitude_bound(1000), 9 * 625);
+
+        assert_eq!(part2_pruned(&fish_math(EXAMPLE).unwrap()), Some(3993));
+        for input in [
+            "[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]",
+            "[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]",
+            "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]\n[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+        ] {
+            let numbers = fish_math(input).unwrap();
+            assert_eq!(part2_pruned(&numbers), part2(&numbers), "{}", input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day18.txt");
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(fish_math(EXAMPLE).unwrap(), part1, part1_arena);
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(
+            fish_math(EXAMPLE).unwrap(),
+            part2,
+            part2_arena,
+            part2_pruned
+        );
+    }
+}
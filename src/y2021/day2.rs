@@ -0,0 +1,625 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Up(u32),
+    Down(u32),
+    Forward(u32),
+    /// Turn anticlockwise by this many quarter turns
+    Left(u32),
+    /// Turn clockwise by this many quarter turns
+    Right(u32),
+}
+
+/// How up and down commands move the submarine: straight away in part 1, or by changing its aim
+/// in part 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Steering {
+    Direct,
+    Aim,
+}
+
+/// Something off about a course, with the index of the command where it happens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CourseWarning {
+    /// The submarine ends up above the water
+    AboveSurface { step: usize, depth: i64 },
+    /// The submarine goes deeper than allowed
+    TooDeep { step: usize, depth: i64 },
+    /// The submarine goes further than an i64 can count, so the course can't be followed past here
+    OutOfRange { step: usize },
+}
+
+impl std::fmt::Display for CourseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CourseWarning::AboveSurface { step, depth } => {
+                write!(f, "command {} surfaces at depth {}", step + 1, depth)
+            }
+            CourseWarning::TooDeep { step, depth } => {
+                write!(f, "command {} dives to depth {}", step + 1, depth)
+            }
+            CourseWarning::OutOfRange { step } => {
+                write!(f, "command {} goes out of range", step + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CourseWarning {}
+
+/// Where the submarine is, and which way it's pointed when steering by aim. `horiz` is along the
+/// heading it starts on and `lateral` is to the right of that, so a course that never turns only
+/// moves `horiz`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub horiz: i64,
+    pub lateral: i64,
+    pub depth: i64,
+    pub aim: i64,
+    /// Quarter turns clockwise from the starting heading, from 0 to 3
+    pub heading: u32,
+}
+
+/// How a command moves the submarine under each kind of steering. Each gives None if the move
+/// takes the submarine further than an i64 can count
+pub trait Maneuver {
+    fn execute(&self, pos: &mut Position) -> Option<()>;
+
+    fn execute_with_aim(&self, pos: &mut Position) -> Option<()>;
+}
+
+impl Maneuver for Command {
+    fn execute(&self, pos: &mut Position) -> Option<()> {
+        match *self {
+            Command::Up(x) => pos.depth = pos.depth.checked_sub(x as i64)?,
+            Command::Down(x) => pos.depth = pos.depth.checked_add(x as i64)?,
+            Command::Forward(x) => pos.advance(x as i64)?,
+            Command::Left(n) => pos.turn(4 - n % 4),
+            Command::Right(n) => pos.turn(n),
+        }
+        Some(())
+    }
+
+    fn execute_with_aim(&self, pos: &mut Position) -> Option<()> {
+        match *self {
+            Command::Up(x) => pos.aim = pos.aim.checked_sub(x as i64)?,
+            Command::Down(x) => pos.aim = pos.aim.checked_add(x as i64)?,
+            Command::Forward(x) => {
+                pos.advance(x as i64)?;
+                pos.depth = pos.depth.checked_add((x as i64).checked_mul(pos.aim)?)?;
+            }
+            Command::Left(n) => pos.turn(4 - n % 4),
+            Command::Right(n) => pos.turn(n),
+        }
+        Some(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCommandError;
+
+/// The verbs a course can use, each with what to make of `<verb> <amount>`. New verbs are
+/// registered here rather than added to the parser
+pub struct CommandRegistry<C> {
+    verbs: HashMap<String, fn(u32) -> C>,
+}
+
+impl<C> CommandRegistry<C> {
+    /// A registry that knows no verbs at all
+    pub fn empty() -> Self {
+        CommandRegistry {
+            verbs: HashMap::new(),
+        }
+    }
+
+    /// Parse `verb` with `constructor`, replacing whatever it was parsed as before
+    pub fn register(&mut self, verb: &str, constructor: fn(u32) -> C) -> &mut Self {
+        self.verbs.insert(verb.to_string(), constructor);
+        self
+    }
+
+    /// A line of the course, as whatever its verb was registered to make
+    pub fn parse(&self, input: &str) -> Result<C, ParseCommandError> {
+        let (verb, amount) = input.trim().split_once(' ').ok_or(ParseCommandError)?;
+        let constructor = self.verbs.get(verb).ok_or(ParseCommandError)?;
+        let amount = amount.parse().map_err(|_| ParseCommandError)?;
+        Ok(constructor(amount))
+    }
+}
+
+/// The puzzle's up, down and forward, along with left and right turns
+impl<C: From<Command>> Default for CommandRegistry<C> {
+    fn default() -> Self {
+        let mut registry = CommandRegistry::empty();
+        registry
+            .register("up", |x| C::from(Command::Up(x)))
+            .register("down", |x| C::from(Command::Down(x)))
+            .register("forward", |x| C::from(Command::Forward(x)))
+            .register("left", |x| C::from(Command::Left(x)))
+            .register("right", |x| C::from(Command::Right(x)));
+        registry
+    }
+}
+
+impl std::str::FromStr for Command {
+    type Err = ParseCommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // Built on the first parse and shared by every one after it
+        static REGISTRY: OnceLock<CommandRegistry<Command>> = OnceLock::new();
+        REGISTRY.get_or_init(CommandRegistry::default).parse(input)
+    }
+}
+
+impl Position {
+    pub fn new() -> Self {
+        Position {
+            horiz: 0,
+            lateral: 0,
+            depth: 0,
+            aim: 0,
+            heading: 0,
+        }
+    }
+
+    /// Move `dist` along the heading, without changing depth
+    fn advance(&mut self, dist: i64) -> Option<()> {
+        match self.heading {
+            0 => self.horiz = self.horiz.checked_add(dist)?,
+            1 => self.lateral = self.lateral.checked_add(dist)?,
+            2 => self.horiz = self.horiz.checked_sub(dist)?,
+            _ => self.lateral = self.lateral.checked_sub(dist)?,
+        }
+        Some(())
+    }
+
+    /// Turn clockwise by `quarters` quarter turns
+    fn turn(&mut self, quarters: u32) {
+        self.heading = (self.heading + quarters % 4) % 4;
+    }
+
+    pub fn execute_command(mut self, command: &(impl Maneuver + ?Sized)) -> Option<Self> {
+        command.execute(&mut self)?;
+        Some(self)
+    }
+
+    pub fn execute_command_with_aim(mut self, command: &(impl Maneuver + ?Sized)) -> Option<Self> {
+        command.execute_with_aim(&mut self)?;
+        Some(self)
+    }
+
+    fn steer(self, command: &(impl Maneuver + ?Sized), steering: Steering) -> Option<Self> {
+        match steering {
+            Steering::Direct => self.execute_command(command),
+            Steering::Aim => self.execute_command_with_aim(command),
+        }
+    }
+}
+
+/// Check a course for commands that take the submarine above the surface, or below `max_depth`
+/// if there is one. Each warning is raised when the submarine first leaves the safe range, not for
+/// every command it stays out of it. A command that goes out of range ends the checking
+pub fn validate_course(
+    commands: &[impl Maneuver],
+    steering: Steering,
+    max_depth: Option<i64>,
+) -> Vec<CourseWarning> {
+    let check = |step: usize, depth: i64| {
+        if depth < 0 {
+            Some(CourseWarning::AboveSurface { step, depth })
+        } else if max_depth.is_some_and(|max| depth > max) {
+            Some(CourseWarning::TooDeep { step, depth })
+        } else {
+            None
+        }
+    };
+
+    let mut warnings = Vec::new();
+    let mut pos = Position::new();
+    let mut previous = None;
+    for (step, command) in commands.iter().enumerate() {
+        let Some(next) = pos.steer(command, steering) else {
+            warnings.push(CourseWarning::OutOfRange { step });
+            break;
+        };
+        pos = next;
+        let warning = check(step, pos.depth);
+        let kind = warning.as_ref().map(std::mem::discriminant);
+        if kind != previous {
+            warnings.extend(warning);
+        }
+        previous = kind;
+    }
+
+    warnings
+}
+
+/// Final horizontal position times depth, which always fits an i128. When `strict`, the first
+/// warning `validate_course` would give is an error instead
+pub fn navigate(
+    commands: &[impl Maneuver],
+    steering: Steering,
+    strict: bool,
+    max_depth: Option<i64>,
+) -> Result<i128, CourseWarning> {
+    if strict {
+        if let Some(warning) = validate_course(commands, steering, max_depth)
+            .into_iter()
+            .next()
+        {
+            return Err(warning);
+        }
+    }
+
+    let (horiz, depth) = replay(commands, steering)?;
+    Ok(horiz as i128 * depth as i128)
+}
+
+/// Final (horizontal position, depth) after following the course
+pub fn replay(commands: &[impl Maneuver], steering: Steering) -> Result<(i64, i64), CourseWarning> {
+    let pos = course_end(commands, steering)?;
+    Ok((pos.horiz, pos.depth))
+}
+
+/// Where the submarine is after following the course, including how far it has drifted sideways
+/// and which way it ends up facing. Errs with the command that goes out of range, if one does
+pub fn course_end(
+    commands: &[impl Maneuver],
+    steering: Steering,
+) -> Result<Position, CourseWarning> {
+    commands
+        .iter()
+        .enumerate()
+        .try_fold(Position::new(), |pos, (step, command)| {
+            pos.steer(command, steering)
+                .ok_or(CourseWarning::OutOfRange { step })
+        })
+}
+
+/// A shorter course to the same place. Each run of forward commands becomes one command, each run
+/// of up and down commands becomes the one command for its net change, and each run of turns
+/// becomes the one turn for its net change, or nothing if they cancel out. A move too long for one
+/// command is split over as many as it takes. Commands that move by 0 are dropped.
+///
+/// Only the order of forward moves against up and down moves and against turns matters to where
+/// the submarine ends up with `Steering::Aim`, and that order is kept, so the compressed course ends
+/// in the same place with either steering. Turns don't change depth, so a run of turns and vertical
+/// moves is written as the vertical move first
+pub fn compress(commands: &[Command]) -> Vec<Command> {
+    // The forward run waiting to be written, then the net vertical move and net turn after it
+    let mut compressed = Vec::new();
+    let mut forward = 0u64;
+    let mut vertical = 0i64;
+    let mut turn = 0;
+    let split = |compressed: &mut Vec<Command>, mut total: u64, command: fn(u32) -> Command| {
+        while total > 0 {
+            let amount = total.min(u32::MAX as u64) as u32;
+            compressed.push(command(amount));
+            total -= amount as u64;
+        }
+    };
+    let flush =
+        |compressed: &mut Vec<Command>, forward: &mut u64, vertical: &mut i64, turn: &mut u32| {
+            split(compressed, *forward, Command::Forward);
+            if *vertical < 0 {
+                split(compressed, vertical.unsigned_abs(), Command::Up);
+            } else {
+                split(compressed, *vertical as u64, Command::Down);
+            }
+            match *turn {
+                0 => {}
+                3 => compressed.push(Command::Left(1)),
+                quarters => compressed.push(Command::Right(quarters)),
+            }
+            *forward = 0;
+            *vertical = 0;
+            *turn = 0;
+        };
+
+    for command in commands {
+        match *command {
+            Command::Forward(0) => {}
+            Command::Forward(x) => {
+                // Moves that cancel out leave the forward runs either side of them to be merged
+                if vertical != 0 || turn != 0 {
+                    flush(&mut compressed, &mut forward, &mut vertical, &mut turn);
+                }
+                forward += x as u64;
+            }
+            Command::Up(x) => vertical -= x as i64,
+            Command::Down(x) => vertical += x as i64,
+            Command::Left(n) => turn = (turn + 4 - n % 4) % 4,
+            Command::Right(n) => turn = (turn + n % 4) % 4,
+        }
+    }
+
+    flush(&mut compressed, &mut forward, &mut vertical, &mut turn);
+    compressed
+}
+
+#[aoc_generator(day2)]
+pub(crate) fn commands(input: &str) -> Vec<Command> {
+    let registry = CommandRegistry::default();
+    input
+        .lines()
+        .filter_map(|command| registry.parse(command).ok())
+        .collect()
+}
+
+#[aoc(day2, part1)]
+pub(crate) fn part1(commands: &[Command]) -> Option<i128> {
+    navigate(commands, Steering::Direct, false, None).ok()
+}
+
+#[aoc(day2, part2)]
+pub(crate) fn part2(commands: &[Command]) -> Option<i128> {
+    navigate(commands, Steering::Aim, false, None).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    #[test]
+    fn validate() {
+        let course = commands("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2");
+        assert!(validate_course(&course, Steering::Direct, None).is_empty());
+        assert_eq!(
+            validate_course(&course, Steering::Aim, Some(50)),
+            [CourseWarning::TooDeep { step: 5, depth: 60 }]
+        );
+
+        // Surfacing is only flagged once until the submarine dives again
+        let course = commands("down 2\nup 3\nup 1\ndown 5\nup 6");
+        assert_eq!(
+            validate_course(&course, Steering::Direct, None),
+            [
+                CourseWarning::AboveSurface { step: 1, depth: -1 },
+                CourseWarning::AboveSurface { step: 4, depth: -3 },
+            ]
+        );
+
+        // With aim, going up only matters once the submarine moves forward
+        assert!(validate_course(&course, Steering::Aim, None).is_empty());
+        let course = commands("up 1\nforward 2");
+        assert_eq!(
+            validate_course(&course, Steering::Aim, None),
+            [CourseWarning::AboveSurface { step: 1, depth: -2 }]
+        );
+    }
+
+    #[test]
+    fn strict() {
+        let course = commands("forward 4\nup 1\ndown 3");
+        assert_eq!(navigate(&course, Steering::Direct, false, None), Ok(8));
+        assert_eq!(
+            navigate(&course, Steering::Direct, true, None),
+            Err(CourseWarning::AboveSurface { step: 1, depth: -1 })
+        );
+        assert_eq!(navigate(&course, Steering::Aim, true, Some(1)), Ok(0));
+
+        // Aim multiplies forward moves into depths past what an i64 holds
+        let max = u32::MAX;
+        let course = [
+            Command::Down(max),
+            Command::Forward(max / 2),
+            Command::Forward(max / 2),
+        ];
+        assert_eq!(
+            validate_course(&course, Steering::Aim, None),
+            [CourseWarning::OutOfRange { step: 2 }]
+        );
+        assert_eq!(
+            navigate(&course, Steering::Aim, false, None),
+            Err(CourseWarning::OutOfRange { step: 2 })
+        );
+        assert_eq!(part2(&course), None);
+        // The product of a position that does fit is past an i64 too
+        let course = [
+            Command::Down(max),
+            Command::Down(max),
+            Command::Forward(max),
+        ];
+        assert_eq!(part1(&course), Some(2 * max as i128 * max as i128));
+    }
+
+    /// The puzzle's commands, plus backing up and moving forward without diving
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Extended {
+        Base(Command),
+        Reverse(u32),
+        Hold(u32),
+    }
+
+    impl From<Command> for Extended {
+        fn from(command: Command) -> Self {
+            Extended::Base(command)
+        }
+    }
+
+    impl Maneuver for Extended {
+        fn execute(&self, pos: &mut Position) -> Option<()> {
+            match self {
+                Extended::Base(command) => return command.execute(pos),
+                Extended::Reverse(x) => pos.horiz = pos.horiz.checked_sub(*x as i64)?,
+                Extended::Hold(x) => pos.horiz = pos.horiz.checked_add(*x as i64)?,
+            }
+            Some(())
+        }
+
+        fn execute_with_aim(&self, pos: &mut Position) -> Option<()> {
+            match self {
+                Extended::Base(command) => return command.execute_with_aim(pos),
+                Extended::Reverse(x) => pos.horiz = pos.horiz.checked_sub(*x as i64)?,
+                Extended::Hold(x) => pos.horiz = pos.horiz.checked_add(*x as i64)?,
+            }
+            Some(())
+        }
+    }
+
+    #[test]
+    fn registry() {
+        let registry = CommandRegistry::<Command>::default();
+        assert_eq!(registry.parse("down 4"), Ok(Command::Down(4)));
+        assert_eq!(registry.parse(" forward 12\n"), Ok(Command::Forward(12)));
+        assert_eq!(registry.parse("left 1"), Ok(Command::Left(1)));
+        assert_eq!(registry.parse("reverse 2"), Err(ParseCommandError));
+        assert_eq!(registry.parse("up"), Err(ParseCommandError));
+        assert_eq!(registry.parse("up -1"), Err(ParseCommandError));
+        assert_eq!(
+            CommandRegistry::<Command>::empty().parse("up 1"),
+            Err(ParseCommandError)
+        );
+
+        let mut registry = CommandRegistry::<Extended>::default();
+        registry
+            .register("reverse", Extended::Reverse)
+            .register("hold", Extended::Hold);
+        let course = "forward 5\ndown 5\nhold 8\nreverse 3\nup 1\nforward 2"
+            .lines()
+            .map(|line| registry.parse(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(course[0], Extended::Base(Command::Forward(5)));
+        assert_eq!(course[3], Extended::Reverse(3));
+
+        assert_eq!(replay(&course, Steering::Direct), Ok((12, 4)));
+        // Holding keeps the depth even with aim, so only the last move forward dives
+        assert_eq!(replay(&course, Steering::Aim), Ok((12, 8)));
+        assert_eq!(navigate(&course, Steering::Aim, true, Some(8)), Ok(96));
+        assert_eq!(
+            validate_course(&course, Steering::Aim, Some(7)),
+            [CourseWarning::TooDeep { step: 5, depth: 8 }]
+        );
+
+        // Registering a verb again replaces it
+        registry.register("up", Extended::Hold);
+        assert_eq!(registry.parse("up 1"), Ok(Extended::Hold(1)));
+    }
+
+    #[test]
+    fn compressed() {
+        let course = commands("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2");
+        assert_eq!(
+            compress(&course),
+            [
+                Command::Forward(5),
+                Command::Down(5),
+                Command::Forward(8),
+                Command::Down(5),
+                Command::Forward(2),
+            ]
+        );
+        let course = commands("down 3\nforward 0\nup 3\nforward 1\nforward 2\nup 0");
+        assert_eq!(compress(&course), [Command::Forward(3)]);
+
+        // Too far for one command
+        let max = u32::MAX;
+        let course = [
+            Command::Forward(max),
+            Command::Forward(2),
+            Command::Up(max),
+            Command::Up(max),
+            Command::Down(1),
+        ];
+        assert_eq!(
+            compress(&course),
+            [
+                Command::Forward(max),
+                Command::Forward(2),
+                Command::Up(max),
+                Command::Up(max - 1),
+            ]
+        );
+        for steering in [Steering::Direct, Steering::Aim] {
+            assert_eq!(
+                course_end(&compress(&course), steering),
+                course_end(&course, steering)
+            );
+        }
+
+        let mut rng = Xorshift::default();
+
+        for _ in 0..200 {
+            let turns = rng.next_u32() % 2 == 0;
+            let course = (0..rng.next_u32() % 50)
+                .map(|_| match rng.next_u32() % if turns { 5 } else { 3 } {
+                    0 => Command::Up(rng.next_u32() % 10),
+                    1 => Command::Down(rng.next_u32() % 10),
+                    2 => Command::Forward(rng.next_u32() % 10),
+                    3 => Command::Left(rng.next_u32() % 6),
+                    _ => Command::Right(rng.next_u32() % 6),
+                })
+                .collect::<Vec<_>>();
+
+            let compressed = compress(&course);
+            assert!(compressed.len() <= course.len());
+            assert_eq!(compress(&compressed), compressed);
+            for steering in [Steering::Direct, Steering::Aim] {
+                assert_eq!(
+                    course_end(&compressed, steering),
+                    course_end(&course, steering)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn turns() {
+        // Around a square, diving on the way, and back to where it started
+        let square = "forward 5\nright 1\ndown 2\nforward 5\nright 1\nforward 5\nright 5\nforward 5\nright 1";
+        let course = commands(square);
+        let end = course_end(&course, Steering::Direct).unwrap();
+        assert_eq!(
+            (end.horiz, end.lateral, end.depth, end.heading),
+            (0, 0, 2, 0)
+        );
+        let end = course_end(&course, Steering::Aim).unwrap();
+        assert_eq!(
+            (end.horiz, end.lateral, end.depth, end.heading),
+            (0, 0, 30, 0)
+        );
+
+        // The same square the other way round, ending up facing left
+        let course = commands(
+            "forward 3\nleft 1\nforward 3\nleft 2\nright 1\nforward 3\nleft 1\nforward 3\nleft 2",
+        );
+        let end = course_end(&course, Steering::Direct).unwrap();
+        assert_eq!((end.horiz, end.lateral, end.heading), (0, 0, 3));
+        let mut halfway = Position::new();
+        for command in &course[..3] {
+            halfway = halfway.execute_command(command).unwrap();
+        }
+        assert_eq!(
+            (halfway.horiz, halfway.lateral, halfway.heading),
+            (3, -3, 3)
+        );
+
+        // Without turns, nothing moves sideways and the puzzle's answers don't change
+        let course = commands("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2");
+        for steering in [Steering::Direct, Steering::Aim] {
+            let end = course_end(&course, steering).unwrap();
+            assert_eq!((end.lateral, end.heading), (0, 0));
+            assert_eq!(Ok((end.horiz, end.depth)), replay(&course, steering));
+        }
+        assert_eq!((part1(&course), part2(&course)), (Some(150), Some(900)));
+
+        // Turning back on itself undoes forward moves, which compression then merges
+        let course = commands(
+            "forward 4\nright 2\nforward 1\nleft 6\nforward 2\nleft 1\nright 1\nforward 3",
+        );
+        assert_eq!(
+            compress(&course),
+            [
+                Command::Forward(4),
+                Command::Right(2),
+                Command::Forward(1),
+                Command::Right(2),
+                Command::Forward(5),
+            ]
+        );
+        assert_eq!(course_end(&course, Steering::Direct).unwrap().horiz, 8);
+    }
+}
@@ -0,0 +1,602 @@
+pub use crate::algo::dirac::quantum_wins_dense;
+use crate::algo::dirac::ROLLS;
+use crate::error::ParseError;
+use crate::progress::{Context, Silent};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+enum Turn {
+    Player1,
+    Player2,
+}
+
+impl Turn {
+    fn pass(&self) -> Self {
+        match self {
+            Turn::Player1 => Turn::Player2,
+            Turn::Player2 => Turn::Player1,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct Player {
+    position: u64,
+    score: u64,
+}
+
+impl Player {
+    fn starting_at(position: u64) -> Self {
+        Player { position, score: 0 }
+    }
+
+    fn move_by(&mut self, roll: u64) {
+        let next = (self.position + roll) % 10;
+        if next == 0 {
+            self.position = 10;
+        } else {
+            self.position = next;
+        }
+        self.score += self.position;
+    }
+}
+
+/// Universe can be identified by the current scores and the turn of the player
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct Universe {
+    player1: Player,
+    player2: Player,
+    turn: Turn,
+}
+
+impl Universe {
+    fn with_players(player1: Player, player2: Player) -> Self {
+        Universe {
+            player1,
+            player2,
+            turn: Turn::Player1,
+        }
+    }
+
+    fn parallel_universe(&self) -> Self {
+        let mut parallel_universe = self.clone();
+        std::mem::swap(
+            &mut parallel_universe.player1,
+            &mut parallel_universe.player2,
+        );
+        parallel_universe.turn = self.turn.pass();
+        parallel_universe
+    }
+
+    fn next_universe(&self, roll: u64) -> Self {
+        let mut next_universe = self.clone();
+        match self.turn {
+            Turn::Player1 => {
+                next_universe.player1.move_by(roll);
+                next_universe.turn = Turn::Player2;
+            }
+            Turn::Player2 => {
+                next_universe.player2.move_by(roll);
+                next_universe.turn = Turn::Player1;
+            }
+        }
+
+        next_universe
+    }
+}
+
+/// Most turns a game can last. Every move scores at least a point, so each player wins within 21
+/// of their own turns
+const MAX_TURNS: usize = 42;
+
+/// How the games played out from some universe onwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Outcomes {
+    wins: (u64, u64),
+    /// Wins for each player by the number of turns taken from the universe, less one
+    wins_by_turns: ([u64; MAX_TURNS], [u64; MAX_TURNS]),
+    universes: u64,
+}
+
+impl Default for Outcomes {
+    fn default() -> Self {
+        Outcomes {
+            wins: (0, 0),
+            wins_by_turns: ([0; MAX_TURNS], [0; MAX_TURNS]),
+            universes: 0,
+        }
+    }
+}
+
+/// Map of universes to the outcomes of the games played from them
+type UniverseCache = HashMap<Universe, Outcomes>;
+
+/// An exact fraction in lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    pub num: u128,
+    pub denom: u128,
+}
+
+impl Ratio {
+    fn new(num: u128, denom: u128) -> Self {
+        let gcd = gcd(num, denom).max(1);
+        Ratio {
+            num: num / gcd,
+            denom: denom / gcd,
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.denom as f64
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A player's share of the universes they win in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WinProbability {
+    pub exact: Ratio,
+    pub approx: f64,
+}
+
+#[aoc_generator(day21)]
+pub(crate) fn starting_positions(input: &str) -> Result<(u64, u64), ParseError> {
+    let mut positions = input.lines().enumerate().map(|(i, line)| {
+        line.rsplit_once(": ")
+            .and_then(|(_, position)| position.trim().parse::<u64>().ok())
+            .filter(|position| (1..=10).contains(position))
+            .ok_or_else(|| ParseError::at_line(i + 1, format!("bad starting position {:?}", line)))
+    });
+
+    match (positions.next(), positions.next()) {
+        (Some(p1), Some(p2)) => Ok((p1?, p2?)),
+        _ => Err(ParseError::at_line(
+            1,
+            "expected a starting position for both players",
+        )),
+    }
+}
+
+fn start_quantum_game(p1: u64, p2: u64, ctx: &mut Context) -> Outcomes {
+    let mut universe_cache = UniverseCache::new();
+    let universe = Universe::with_players(Player::starting_at(p1), Player::starting_at(p2));
+    play_quantum_game(universe, &mut universe_cache, ctx)
+}
+
+fn play_quantum_game(
+    universe: Universe,
+    universe_cache: &mut UniverseCache,
+    ctx: &mut Context,
+) -> Outcomes {
+    if let Some(outcomes) = universe_cache.get(&universe) {
+        return *outcomes;
+    }
+
+    let mut outcomes = Outcomes::default();
+    for &(roll, times) in ROLLS.iter() {
+        let next_universe = universe.next_universe(roll);
+
+        let max_wins = 20;
+        if next_universe.player1.score > max_wins {
+            outcomes.wins.0 += times;
+            outcomes.wins_by_turns.0[0] += times;
+            outcomes.universes += times;
+        } else if next_universe.player2.score > max_wins {
+            outcomes.wins.1 += times;
+            outcomes.wins_by_turns.1[0] += times;
+            outcomes.universes += times;
+        } else {
+            let next = play_quantum_game(next_universe, universe_cache, ctx);
+            outcomes.wins.0 += times * next.wins.0;
+            outcomes.wins.1 += times * next.wins.1;
+            for turns in 1..MAX_TURNS {
+                outcomes.wins_by_turns.0[turns] += times * next.wins_by_turns.0[turns - 1];
+                outcomes.wins_by_turns.1[turns] += times * next.wins_by_turns.1[turns - 1];
+            }
+            outcomes.universes += times * next.universes;
+        }
+    }
+    // insert parallel universe, one where player2 and player1 are swapped
+    let parallel = Outcomes {
+        wins: (outcomes.wins.1, outcomes.wins.0),
+        wins_by_turns: (outcomes.wins_by_turns.1, outcomes.wins_by_turns.0),
+        ..outcomes
+    };
+    universe_cache.insert(universe.parallel_universe(), parallel);
+    universe_cache.insert(universe, outcomes);
+    ctx.tick("universes");
+
+    outcomes
+}
+
+/// Universes each player wins in, found by following every game from its start
+pub fn quantum_wins(p1: u64, p2: u64) -> (u64, u64) {
+    start_quantum_game(p1, p2, &mut Context::new(21, &mut Silent)).wins
+}
+
+/// For each player, the fraction of the universes split off by the Dirac die that they win in
+pub fn win_probabilities(p1: u64, p2: u64) -> [WinProbability; 2] {
+    let outcomes = start_quantum_game(p1, p2, &mut Context::new(21, &mut Silent));
+    [outcomes.wins.0, outcomes.wins.1].map(|wins| {
+        let exact = Ratio::new(wins as u128, outcomes.universes as u128);
+        WinProbability {
+            exact,
+            approx: exact.to_f64(),
+        }
+    })
+}
+
+/// For each player, the number of universes they win in after each number of turns. Turns are
+/// counted for both players, so player 1 only wins after an odd number and player 2 after an even
+/// number. Numbers of turns no game is won after are left out
+pub fn wins_by_turns(p1: u64, p2: u64) -> [BTreeMap<usize, u64>; 2] {
+    let outcomes = start_quantum_game(p1, p2, &mut Context::new(21, &mut Silent));
+    [outcomes.wins_by_turns.0, outcomes.wins_by_turns.1].map(|wins| {
+        wins.iter()
+            .enumerate()
+            .filter(|&(_, &universes)| universes > 0)
+            .map(|(turns, &universes)| (turns + 1, universes))
+            .collect()
+    })
+}
+
+/// One turn of a game played with the deterministic die
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TracedTurn {
+    /// Index of the player into the starting positions
+    pub player: usize,
+    pub rolls: [u64; 3],
+    pub position: u64,
+    pub score: u64,
+}
+
+/// Every turn of a game in the order they were taken, so it can be replayed and checked
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameTrace {
+    pub turns: Vec<TracedTurn>,
+}
+
+/// Rolls of the deterministic die three at a time. The die counts 1 to 100 and wraps around
+fn deterministic_die() -> impl Iterator<Item = [u64; 3]> {
+    (0..)
+        .step_by(3)
+        .map(|n: u64| [n, n + 1, n + 2].map(|n| 1 + (n % 100)))
+}
+
+/// Score that wins the game with the deterministic die
+const TARGET: u64 = 1000;
+
+/// Players take turns in order until one reaches `target` points, recording each turn in `trace`
+/// if given. Returns (scores, num_rolls)
+fn play_game(
+    starting: &[u64],
+    target: u64,
+    mut die: impl Iterator<Item = [u64; 3]>,
+    mut trace: Option<&mut GameTrace>,
+) -> (Vec<u64>, u64) {
+    let mut players = starting
+        .iter()
+        .map(|&position| Player::starting_at(position))
+        .collect::<Vec<_>>();
+
+    for (turn, num_rolls) in (0..).zip((3..).step_by(3)) {
+        let current = turn % players.len();
+        let rolls = die.next().unwrap();
+        players[current].move_by(rolls.iter().sum());
+
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.turns.push(TracedTurn {
+                player: current,
+                rolls,
+                position: players[current].position,
+                score: players[current].score,
+            });
+        }
+
+        if players[current].score >= target {
+            return (players.iter().map(|p| p.score).collect(), num_rolls);
+        }
+    }
+
+    unreachable!();
+}
+
+/// The same game as `play_game` with the deterministic die, skipping ahead over whole cycles
+/// rather than playing every turn, so a target in the trillions takes no longer than 1000.
+/// Returns (scores, num_rolls).
+///
+/// The die is back where it started every 100 turns, and whose turn it is every `starting.len()`,
+/// so the game only depends on the turn modulo 100 times the number of players and on where each
+/// player is. Once that comes round again every turn after it repeats, with each player scoring
+/// what they did the last time round. As many rounds as leave every score short of the target are
+/// added on in one go, and the rest of the game is played out
+pub fn deterministic_game(starting: &[u64], target: u64) -> (Vec<u64>, u64) {
+    let mut players = starting
+        .iter()
+        .map(|&position| Player::starting_at(position))
+        .collect::<Vec<_>>();
+    let cycle = 100 * players.len() as u64;
+
+    // (turn modulo the cycle, positions) to the turn they were first seen on and the scores then
+    let mut seen = HashMap::<(u64, Vec<u64>), (u64, Vec<u64>)>::new();
+    let mut skipped = false;
+    let mut turn = 0;
+    loop {
+        if !skipped {
+            let state = (
+                turn % cycle,
+                players.iter().map(|p| p.position).collect::<Vec<_>>(),
+            );
+            match seen.get(&state) {
+                Some((first, scores)) => {
+                    let gains = players
+                        .iter()
+                        .zip(scores)
+                        .map(|(player, score)| player.score - score)
+                        .collect::<Vec<_>>();
+                    let rounds = players
+                        .iter()
+                        .zip(&gains)
+                        .map(|(player, gain)| target.saturating_sub(player.score + 1) / gain)
+                        .min()
+                        .unwrap_or(0);
+                    for (player, gain) in players.iter_mut().zip(&gains) {
+                        player.score += rounds * gain;
+                    }
+                    turn += rounds * (turn - first);
+                    skipped = true;
+                }
+                None => {
+                    let scores = players.iter().map(|p| p.score).collect();
+                    seen.insert(state, (turn, scores));
+                }
+            }
+        }
+
+        let current = (turn % players.len() as u64) as usize;
+        let roll = (0..3).map(|i| 1 + (3 * turn + i) % 100).sum();
+        players[current].move_by(roll);
+        turn += 1;
+
+        if players[current].score >= target {
+            return (players.iter().map(|p| p.score).collect(), 3 * turn);
+        }
+    }
+}
+
+#[aoc(day21, part1)]
+pub(crate) fn part1(&(p1, p2): &(u64, u64)) -> u64 {
+    let (scores, num_rolls) = play_game(&[p1, p2], TARGET, deterministic_die(), None);
+    let loser = scores.iter().min().unwrap();
+    loser * num_rolls
+}
+
+#[aoc(day21, part1, FastForward)]
+fn part1_fast_forward(&(p1, p2): &(u64, u64)) -> u64 {
+    let (scores, num_rolls) = deterministic_game(&[p1, p2], TARGET);
+    let loser = scores.iter().min().unwrap();
+    loser * num_rolls
+}
+
+/// Every turn of the game part 1 plays with the deterministic die
+pub fn part1_trace(&(p1, p2): &(u64, u64)) -> GameTrace {
+    let mut trace = GameTrace::default();
+    play_game(&[p1, p2], TARGET, deterministic_die(), Some(&mut trace));
+    trace
+}
+
+#[aoc(day21, part2)]
+pub(crate) fn part2(positions: &(u64, u64)) -> u64 {
+    part2_progress(positions, &mut Context::new(21, &mut Silent))
+}
+
+#[aoc(day21, part2, Dense)]
+fn part2_dense(&(p1, p2): &(u64, u64)) -> u64 {
+    let (p1_wins, p2_wins) = quantum_wins_dense(p1, p2);
+    p1_wins.max(p2_wins)
+}
+
+pub(crate) fn part2_progress(&(p1, p2): &(u64, u64), ctx: &mut Context) -> u64 {
+    let (p1_wins, p2_wins) = start_quantum_game(p1, p2, ctx).wins;
+    p1_wins.max(p2_wins)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn more_players() {
+        assert_eq!(
+            play_game(&[4, 8, 1], TARGET, deterministic_die(), None),
+            (vec![1001, 987, 855], 1380)
+        );
+        assert_eq!(
+            play_game(&[1, 2, 3, 4], TARGET, deterministic_die(), None),
+            (vec![916, 1005, 907, 817], 2178)
+        );
+    }
+
+    #[test]
+    fn fast_forward() {
+        assert_eq!(deterministic_game(&[4, 8], TARGET), (vec![1000, 745], 993));
+
+        let mut starts = (1..=10)
+            .flat_map(|p1| (1..=10).map(move |p2| vec![p1, p2]))
+            .collect::<Vec<_>>();
+        starts.extend([
+            vec![4, 8, 1],
+            vec![1, 2, 3, 4],
+            vec![7],
+            vec![3, 3, 3, 3, 3],
+        ]);
+        for starting in &starts {
+            for target in [0, 1, 10, 21, 1000, 4321] {
+                assert_eq!(
+                    deterministic_game(starting, target),
+                    play_game(starting, target, deterministic_die(), None),
+                    "{:?} to {}",
+                    starting,
+                    target
+                );
+            }
+        }
+
+        // Far too many turns to play one by one. Only the winner has reached the target, within
+        // one move of it
+        let target = 1_000_000_000_000;
+        let (scores, num_rolls) = deterministic_game(&[4, 8], target);
+        assert_eq!(num_rolls % 3, 0);
+        let winner = ((num_rolls / 3 - 1) % 2) as usize;
+        assert!(scores[winner] >= target && scores[winner] < target + 10);
+        assert!(scores[1 - winner] < target);
+        // From this start, a turn scores 5 to 6 points on average
+        let per_turn = scores.iter().sum::<u64>() as f64 / (num_rolls / 3) as f64;
+        assert!((5.0..6.0).contains(&per_turn), "{}", per_turn);
+    }
+
+    #[test]
+    fn trace() {
+        let turn = |player, rolls, position, score| TracedTurn {
+            player,
+            rolls,
+            position,
+            score,
+        };
+        let trace = part1_trace(&(4, 8));
+        assert_eq!(trace.turns.len(), 993 / 3);
+        assert_eq!(
+            trace.turns[..8],
+            [
+                turn(0, [1, 2, 3], 10, 10),
+                turn(1, [4, 5, 6], 3, 3),
+                turn(0, [7, 8, 9], 4, 14),
+                turn(1, [10, 11, 12], 6, 9),
+                turn(0, [13, 14, 15], 6, 20),
+                turn(1, [16, 17, 18], 7, 16),
+                turn(0, [19, 20, 21], 6, 26),
+                turn(1, [22, 23, 24], 6, 22),
+            ]
+        );
+        assert_eq!(
+            trace.turns[trace.turns.len() - 4..],
+            [
+                turn(1, [82, 83, 84], 6, 742),
+                turn(0, [85, 86, 87], 4, 990),
+                turn(1, [88, 89, 90], 3, 745),
+                turn(0, [91, 92, 93], 10, 1000),
+            ]
+        );
+
+        // The die wraps around after 100
+        assert!(trace.turns.iter().any(|turn| turn.rolls == [100, 1, 2]));
+    }
+
+    #[test]
+    fn probabilities() {
+        let (p1_wins, p2_wins) = (444356092776315u128, 341960390180808u128);
+        let outcomes = start_quantum_game(4, 8, &mut Context::new(21, &mut Silent));
+        assert_eq!(outcomes.wins, (p1_wins as u64, p2_wins as u64));
+        assert_eq!(outcomes.universes as u128, p1_wins + p2_wins);
+
+        let [p1, p2] = win_probabilities(4, 8);
+        let total = p1_wins + p2_wins;
+        assert_eq!(p1.exact.num * total, p1_wins * p1.exact.denom);
+        assert_eq!(p2.exact.num * total, p2_wins * p2.exact.denom);
+        assert_eq!(gcd(p1.exact.num, p1.exact.denom), 1);
+        assert_eq!(p1.exact.denom, p2.exact.denom);
+        assert_eq!(p1.exact.num + p2.exact.num, p1.exact.denom);
+        assert!((p1.approx - 0.565111).abs() < 1e-6);
+        assert!((p1.approx + p2.approx - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn turn_histogram() {
+        // Play every universe forwards a turn at a time, without the cache
+        let play = |p1: u64, p2: u64| {
+            let mut wins = [BTreeMap::new(), BTreeMap::new()];
+            let mut universes = HashMap::from([(
+                Universe::with_players(Player::starting_at(p1), Player::starting_at(p2)),
+                1u64,
+            )]);
+            for turns in 1.. {
+                if universes.is_empty() {
+                    break;
+                }
+                let mut next = HashMap::new();
+                for (universe, count) in universes {
+                    for &(roll, times) in ROLLS.iter() {
+                        let next_universe = universe.next_universe(roll);
+                        if next_universe.player1.score > 20 {
+                            *wins[0].entry(turns).or_default() += count * times;
+                        } else if next_universe.player2.score > 20 {
+                            *wins[1].entry(turns).or_default() += count * times;
+                        } else {
+                            *next.entry(next_universe).or_default() += count * times;
+                        }
+                    }
+                }
+                universes = next;
+            }
+            wins
+        };
+
+        for (p1, p2) in [(4, 8), (1, 1), (10, 3), (7, 2)] {
+            let histogram = wins_by_turns(p1, p2);
+            assert_eq!(histogram, play(p1, p2), "{} {}", p1, p2);
+
+            let outcomes = start_quantum_game(p1, p2, &mut Context::new(21, &mut Silent));
+            assert_eq!(histogram[0].values().sum::<u64>(), outcomes.wins.0);
+            assert_eq!(histogram[1].values().sum::<u64>(), outcomes.wins.1);
+            assert!(histogram[0].keys().all(|turns| turns % 2 == 1));
+            assert!(histogram[1].keys().all(|turns| turns % 2 == 0));
+        }
+
+        // Two turns of 10 points aren't enough, so nobody wins before their third turn
+        let [p1, p2] = wins_by_turns(4, 8);
+        assert_eq!(p1.keys().next(), Some(&5));
+        assert_eq!(p2.keys().next(), Some(&6));
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day21.txt");
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(
+            starting_positions(EXAMPLE).unwrap(),
+            part1,
+            part1_fast_forward
+        );
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(starting_positions(EXAMPLE).unwrap(), part2, part2_dense);
+    }
+
+    #[test]
+    fn other_starts() {
+        for (p1, p2) in [(1, 1), (10, 3), (7, 2), (5, 10)] {
+            assert_eq!(
+                quantum_wins_dense(p1, p2),
+                quantum_wins(p1, p2),
+                "{} {}",
+                p1,
+                p2
+            );
+        }
+    }
+}
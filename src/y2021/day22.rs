@@ -0,0 +1,1458 @@
+pub use crate::algo::cuboid::Cuboid;
+use crate::error::ParseError;
+use crate::progress::{Context, Silent};
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+pub struct ReactorCore {
+    cubes: RegionTrie,
+}
+
+/// The state of a reactor as a list of disjoint cuboids that are on, everything else being off
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReactorSnapshot {
+    pub on: Vec<Cuboid>,
+}
+
+/// Struct to model a region. Regions alternate on-off. i.e. the root regions will all be on, their
+/// children will be off, grandchildren on, etc.
+#[derive(Clone)]
+struct Region {
+    on: bool,
+    xr: RangeInclusive<i64>,
+    yr: RangeInclusive<i64>,
+    zr: RangeInclusive<i64>,
+    sub_regions: Vec<Region>,
+}
+
+impl std::cmp::PartialEq for Region {
+    fn eq(&self, other: &Self) -> bool {
+        self.xr.start() == other.xr.start()
+            && self.yr.start() == other.yr.start()
+            && self.zr.start() == other.zr.start()
+            && self.xr.end() == other.xr.end()
+            && self.yr.end() == other.yr.end()
+            && self.zr.end() == other.zr.end()
+    }
+}
+
+#[track_caller]
+fn assert_disjoint(regions: &[Region]) {
+    let mut found_overlap = false;
+    for a in 0..regions.len() {
+        for b in 0..a {
+            if regions[a].intersects(&regions[b]) || regions[b].intersects(&regions[a]) {
+                println!("Overlapping regions:\n{:?}, {:?}", regions[a], regions[b]);
+                found_overlap = true;
+            }
+        }
+    }
+
+    assert!(!found_overlap);
+}
+
+impl std::fmt::Debug for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for node in self.iter_tree() {
+            writeln!(f, "{}", node)?;
+        }
+        Ok(())
+    }
+}
+
+impl Region {
+    fn world() -> RangeInclusive<i64> {
+        i64::MIN..=i64::MAX
+    }
+
+    fn is_world(&self) -> bool {
+        self.xr == Region::world() && self.yr == Region::world() && self.zr == Region::world()
+    }
+
+    fn from_command(command: &Command) -> Self {
+        Region::from_cuboid(&command.cuboid(), command.action == Action::On)
+    }
+
+    fn from_cuboid(cuboid: &Cuboid, on: bool) -> Self {
+        Region::new(
+            cuboid.x.0..=cuboid.x.1,
+            cuboid.y.0..=cuboid.y.1,
+            cuboid.z.0..=cuboid.z.1,
+            on,
+        )
+    }
+
+    fn cuboid(&self) -> Cuboid {
+        Cuboid {
+            x: (*self.xr.start(), *self.xr.end()),
+            y: (*self.yr.start(), *self.yr.end()),
+            z: (*self.zr.start(), *self.zr.end()),
+        }
+    }
+
+    fn new(
+        xr: RangeInclusive<i64>,
+        yr: RangeInclusive<i64>,
+        zr: RangeInclusive<i64>,
+        on: bool,
+    ) -> Self {
+        Region {
+            on,
+            xr,
+            yr,
+            zr,
+            sub_regions: Vec::new(),
+        }
+    }
+
+    fn split(&self, other: &Region) -> (Vec<Region>, Vec<Region>) {
+        // There's nothing before or after the overlap when it reaches the end of the i64s
+        let find_subregions = |a: &RangeInclusive<i64>, b: &RangeInclusive<i64>| {
+            let (start, end) = (max(*a.start(), *b.start()), min(*a.end(), *b.end()));
+            let before = start
+                .checked_sub(1)
+                .map(|last| min(*a.start(), *b.start())..=last);
+            let after = end
+                .checked_add(1)
+                .map(|first| first..=max(*a.end(), *b.end()));
+            [before, Some(start..=end), after]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+        };
+
+        let xr_regions = find_subregions(&self.xr, &other.xr);
+        let yr_regions = find_subregions(&self.yr, &other.yr);
+        let zr_regions = find_subregions(&self.zr, &other.zr);
+
+        let mut other_regions = Vec::new();
+        let mut self_regions = Vec::new();
+        for xr in &xr_regions {
+            for yr in &yr_regions {
+                for zr in &zr_regions {
+                    let mut new_region = Region::new(xr.clone(), yr.clone(), zr.clone(), false);
+                    if other_regions.contains(&new_region)
+                        || self_regions.contains(&new_region)
+                        || new_region.is_empty()
+                    {
+                        continue;
+                    }
+
+                    if other.contains(&new_region) {
+                        // Sub-Region is in the newly added one, set it to the same state
+                        new_region.on = other.on;
+                        other_regions.push(new_region);
+                    } else if self.contains(&new_region) {
+                        // Sub-Region is in the old region, same state as old
+                        new_region.on = self.on;
+                        self_regions.push(new_region);
+                    }
+                }
+            }
+        }
+        assert!(
+            self.volume() == self_regions.iter().map(|r| r.volume()).sum::<i64>()
+                || other.volume() == other_regions.iter().map(|r| r.volume()).sum::<i64>()
+        );
+
+        (self_regions, other_regions)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.xr.is_empty() || self.yr.is_empty() || self.zr.is_empty()
+    }
+
+    /// Returns true if other is completely contained within self
+    /// x0                x1
+    /// +------------------+ y1
+    /// |       self       |
+    /// |                  |
+    /// |   a0       a1    |
+    /// |   +---------+ b1 |
+    /// |   |         |    |
+    /// |   |  other  |    |
+    /// |   |         |    |
+    /// |   +---------+ b0 |
+    /// |                  |
+    /// +------------------+ y0
+    fn contains(&self, other: &Region) -> bool {
+        other.xr.start() >= self.xr.start()
+            && other.xr.end() <= self.xr.end()
+            && other.yr.start() >= self.yr.start()
+            && other.yr.end() <= self.yr.end()
+            && other.zr.start() >= self.zr.start()
+            && other.zr.end() <= self.zr.end()
+    }
+
+    /// Returns true if self splits other into overlapping and non-overlapping regions:
+    /// x0        x1
+    /// +----------+ y1
+    /// |   self   |
+    /// |          |
+    /// |     a0   |   a1
+    /// |     +----:----+ b1
+    /// |     |    :    |
+    /// |     |  other  |
+    /// |     |    :    |
+    /// |     +----:----+ b0
+    /// |          |
+    /// +----------+ y0
+    ///
+    /// x0        x1
+    /// +----------+ y1
+    /// |   self   |
+    /// |          |
+    /// |          |
+    /// |     a0   |   a1
+    /// |     + - -+----+ b1
+    /// |     :    :    |
+    /// +-----+- - + y0 |
+    ///       |  other  |
+    ///       |         |
+    ///       +---------+ b0
+    fn intersects(&self, other: &Region) -> bool {
+        self.xr.start() <= other.xr.end()
+            && self.xr.end() >= other.xr.start()
+            && self.yr.start() <= other.yr.end()
+            && self.yr.end() >= other.yr.start()
+            && self.zr.start() <= other.zr.end()
+            && self.zr.end() >= other.zr.start()
+    }
+
+    fn add_region(&mut self, other: Region) {
+        // Find the sub regions that contain this region (at least partially). Split them up, and
+        // add them back, then repeat the process with the remaining regions
+        let mut regions = vec![other];
+        while let Some(new_region) = regions.pop() {
+            // Remove any sub-regions completely contained by this one. They are now the value of this
+            // new region
+            self.sub_regions.retain(|r| !new_region.contains(r));
+
+            let intersected_region = self
+                .sub_regions
+                .iter()
+                .enumerate()
+                .find(|(_, r)| r.intersects(&new_region));
+
+            if let Some((i, intersected_region)) = intersected_region {
+                // Split the other region into sub-regions to be added, and try to add them
+                let (mut self_regions, mut other_regions) = intersected_region.split(&new_region);
+                regions.append(&mut other_regions);
+                self.sub_regions.append(&mut self_regions);
+
+                // Erase the old element from the array of sub regions
+                self.sub_regions.remove(i);
+            } else if new_region.on != self.on {
+                // Simple case, no intersections
+                self.sub_regions.push(new_region);
+            }
+        }
+    }
+
+    fn self_volume(&self) -> i64 {
+        (self.xr.end() - self.xr.start() + 1)
+            * (self.yr.end() - self.yr.start() + 1)
+            * (self.zr.end() - self.zr.start() + 1)
+    }
+
+    fn volume(&self) -> i64 {
+        if self.is_world() {
+            return 0;
+        }
+
+        let self_volume = self.self_volume();
+        let child_volume = self.sub_regions.iter().map(|r| r.volume()).sum::<i64>();
+
+        self_volume - child_volume
+    }
+
+    /// This region and everything under it, parents before their children
+    fn iter_tree(&self) -> RegionTree<'_> {
+        RegionTree {
+            stack: vec![(0, self)],
+        }
+    }
+}
+
+/// One region of the trie, as seen by [`ReactorCore::iter_tree`]. `depth` is 0 for the regions
+/// directly under the (unbounded, off) root, and the volumes are what the region covers on its own
+/// and what its children carve out of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionNode {
+    pub depth: usize,
+    pub on: bool,
+    pub bounds: Cuboid,
+    pub self_volume: i64,
+    pub child_volume: i64,
+}
+
+impl std::fmt::Display for RegionNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let Cuboid { x, y, z } = self.bounds;
+        write!(
+            f,
+            "{:indent$}{} ({}..={}, {}..={}, {}..={}) -- {} - {}",
+            "",
+            if self.on { "on" } else { "off" },
+            x.0,
+            x.1,
+            y.0,
+            y.1,
+            z.0,
+            z.1,
+            self.self_volume,
+            self.child_volume,
+            indent = 2 * self.depth,
+        )
+    }
+}
+
+/// Depth-first walk over a region and its sub-regions
+struct RegionTree<'a> {
+    stack: Vec<(usize, &'a Region)>,
+}
+
+impl Iterator for RegionTree<'_> {
+    type Item = RegionNode;
+
+    fn next(&mut self) -> Option<RegionNode> {
+        let (depth, region) = self.stack.pop()?;
+        self.stack
+            .extend(region.sub_regions.iter().rev().map(|r| (depth + 1, r)));
+
+        Some(RegionNode {
+            depth,
+            on: region.on,
+            bounds: region.cuboid(),
+            self_volume: region.self_volume(),
+            child_volume: region.sub_regions.iter().map(|r| r.volume()).sum::<i64>(),
+        })
+    }
+}
+
+struct RegionTrie {
+    root: Region,
+}
+
+impl RegionTrie {
+    fn new() -> Self {
+        RegionTrie {
+            root: Region::new(Region::world(), Region::world(), Region::world(), false),
+        }
+    }
+
+    fn add_region(&mut self, new_region: Region) {
+        self.root.add_region(new_region);
+        assert_disjoint(self.regions());
+    }
+
+    /// Flip every cube in `cuboid`. The parts of it that are off are found by cutting each region
+    /// that is on out of it, then the whole cuboid is turned off and those parts turned on
+    fn toggle(&mut self, cuboid: &Cuboid) {
+        let mut off = vec![*cuboid];
+        for region in self.regions() {
+            let on = region.cuboid();
+            off = off.iter().flat_map(|piece| piece.difference(&on)).collect();
+        }
+
+        self.add_region(Region::from_cuboid(cuboid, false));
+        for piece in off.iter() {
+            self.add_region(Region::from_cuboid(piece, true));
+        }
+    }
+
+    fn count_on(&self) -> i64 {
+        self.regions().iter().map(|r| r.volume()).sum::<i64>()
+    }
+
+    fn regions(&self) -> &[Region] {
+        &self.root.sub_regions
+    }
+
+    /// Every region below the root. The root covers all of space, so it has no volume to report
+    fn iter_tree(&self) -> impl Iterator<Item = RegionNode> + '_ {
+        self.regions().iter().flat_map(Region::iter_tree)
+    }
+}
+
+impl Default for ReactorCore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReactorCore {
+    pub fn new() -> Self {
+        ReactorCore {
+            cubes: RegionTrie::new(),
+        }
+    }
+
+    pub fn execute_command(&mut self, command: &Command) {
+        match command.action {
+            Action::On | Action::Off => self.cubes.add_region(Region::from_command(command)),
+            Action::Toggle => self.cubes.toggle(&command.cuboid()),
+        }
+    }
+
+    pub fn count_on(&self) -> i64 {
+        self.cubes.count_on()
+    }
+
+    /// The regions the reactor is tracking, parents before their children
+    pub fn iter_tree(&self) -> impl Iterator<Item = RegionNode> + '_ {
+        self.cubes.iter_tree()
+    }
+
+    pub fn to_snapshot(&self) -> ReactorSnapshot {
+        let on = self.cubes.regions().iter().map(Region::cuboid).collect();
+
+        ReactorSnapshot { on }
+    }
+
+    /// Faces between a cube that is on and one that is off, counting those on the inside of holes.
+    /// Each pair of on-cuboids is checked for a shared face, so this is quadratic in the number of
+    /// them
+    pub fn surface_area(&self) -> i64 {
+        let on = self.to_snapshot().on;
+        let shared = on
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| on[..i].iter().map(move |b| a.shared_face(b)))
+            .sum::<i64>();
+        on.iter().map(Cuboid::surface_area).sum::<i64>() - 2 * shared
+    }
+
+    /// The smallest cuboid containing every cube that is on, or None if they are all off
+    pub fn bounding_box(&self) -> Option<Cuboid> {
+        self.cubes
+            .regions()
+            .iter()
+            .map(Region::cuboid)
+            .reduce(|bound, cuboid| bound.bound(&cuboid))
+    }
+
+    /// Index the cubes that are on, for point and box queries
+    pub fn index(&self) -> ReactorIndex {
+        ReactorIndex::new(self.to_snapshot().on)
+    }
+
+    /// Rebuild a reactor from a snapshot. The cuboids are replayed as commands, so a snapshot with
+    /// overlapping cuboids still produces the right state
+    pub fn from_snapshot(snapshot: &ReactorSnapshot) -> Self {
+        let mut core = ReactorCore::new();
+        for cuboid in snapshot.on.iter() {
+            core.execute_command(&Command {
+                xr: cuboid.x,
+                yr: cuboid.y,
+                zr: cuboid.z,
+                action: Action::On,
+            });
+        }
+        core
+    }
+}
+
+/// One line per region, indented by its depth
+impl std::fmt::Display for ReactorCore {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for node in self.iter_tree() {
+            writeln!(f, "{}", node)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bounding volume hierarchy over disjoint "on" cuboids. Each node covers a run of `cuboids` and
+/// knows how many cubes are on inside it, so box counts only descend into nodes that straddle the
+/// edge of the box
+pub struct ReactorIndex {
+    cuboids: Vec<Cuboid>,
+    nodes: Vec<IndexNode>,
+}
+
+struct IndexNode {
+    bounds: Cuboid,
+    count_on: i64,
+    cuboids: std::ops::Range<usize>,
+    children: Option<(usize, usize)>,
+}
+
+const LEAF_SIZE: usize = 4;
+
+impl ReactorIndex {
+    /// Build an index over `cuboids`, which must not overlap
+    pub fn new(mut cuboids: Vec<Cuboid>) -> Self {
+        let mut nodes = Vec::new();
+        if !cuboids.is_empty() {
+            let len = cuboids.len();
+            ReactorIndex::build(&mut cuboids, 0..len, &mut nodes);
+        }
+        ReactorIndex { cuboids, nodes }
+    }
+
+    /// Push the node for `range` and its children, returning the node's index
+    fn build(
+        cuboids: &mut [Cuboid],
+        range: std::ops::Range<usize>,
+        nodes: &mut Vec<IndexNode>,
+    ) -> usize {
+        let slice = &mut cuboids[range.clone()];
+        let bounds = slice[1..].iter().fold(slice[0], |b, c| b.bound(c));
+        let count_on = slice.iter().map(|c| c.volume()).sum::<i64>();
+
+        let id = nodes.len();
+        nodes.push(IndexNode {
+            bounds,
+            count_on,
+            cuboids: range.clone(),
+            children: None,
+        });
+        if slice.len() <= LEAF_SIZE {
+            return id;
+        }
+
+        // Split at the median along the widest axis of the bounds
+        let extent = |r: (i64, i64)| r.1 - r.0;
+        let axis: fn(&Cuboid) -> (i64, i64) =
+            if extent(bounds.x) >= extent(bounds.y) && extent(bounds.x) >= extent(bounds.z) {
+                |c| c.x
+            } else if extent(bounds.y) >= extent(bounds.z) {
+                |c| c.y
+            } else {
+                |c| c.z
+            };
+        let mid = slice.len() / 2;
+        slice.select_nth_unstable_by_key(mid, |c| axis(c).0 + axis(c).1);
+
+        let split = range.start + mid;
+        let left = ReactorIndex::build(cuboids, range.start..split, nodes);
+        let right = ReactorIndex::build(cuboids, split..range.end, nodes);
+        nodes[id].children = Some((left, right));
+        id
+    }
+
+    pub fn is_on(&self, x: i64, y: i64, z: i64) -> bool {
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let Some(node) = self.nodes.get(id) else {
+                break;
+            };
+            if !node.bounds.contains_point(x, y, z) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend([left, right]),
+                None => {
+                    if self.cuboids[node.cuboids.clone()]
+                        .iter()
+                        .any(|c| c.contains_point(x, y, z))
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Number of cubes inside `area` that are on
+    pub fn count_on_in(&self, area: &Cuboid) -> i64 {
+        let mut count = 0;
+        let mut stack = vec![0];
+        while let Some(id) = stack.pop() {
+            let Some(node) = self.nodes.get(id) else {
+                break;
+            };
+            if area.contains(&node.bounds) {
+                count += node.count_on;
+            } else if area.intersection(&node.bounds).is_some() {
+                match node.children {
+                    Some((left, right)) => stack.extend([left, right]),
+                    None => {
+                        count += self.cuboids[node.cuboids.clone()]
+                            .iter()
+                            .filter_map(|c| c.intersection(area))
+                            .map(|c| c.volume())
+                            .sum::<i64>()
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+/// What a command does to the cubes in its cuboid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    On,
+    Off,
+    /// Turn the cubes that are on off, and the cubes that are off on
+    Toggle,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    xr: (i64, i64),
+    yr: (i64, i64),
+    zr: (i64, i64),
+    action: Action,
+}
+
+const CLAMP: i64 = 50;
+fn clamp_50(r: (i64, i64)) -> (i64, i64) {
+    (r.0.clamp(-CLAMP, CLAMP), r.1.clamp(-CLAMP, CLAMP))
+}
+
+impl Command {
+    fn restrict(&self) -> Self {
+        Command {
+            xr: clamp_50(self.xr),
+            yr: clamp_50(self.yr),
+            zr: clamp_50(self.zr),
+            action: self.action,
+        }
+    }
+
+    fn cuboid(&self) -> Cuboid {
+        Cuboid {
+            x: self.xr,
+            y: self.yr,
+            z: self.zr,
+        }
+    }
+
+    fn inside_init(&self) -> bool {
+        let inside = |r: (i64, i64)| (r.0 >= -50 && r.0 <= 50) || (r.1 >= -50 && r.1 <= 50);
+        inside(self.xr) && inside(self.yr) && inside(self.zr)
+    }
+}
+
+/// One `on`, `off` or `toggle` command per line, each with an x, y and z range
+#[aoc_generator(day22)]
+pub fn parse_commands(input: &str) -> Result<Vec<Command>, ParseError> {
+    let range_re = regex::Regex::new(r"^\w=(-?\d+)\.\.(-?\d+)$").unwrap();
+
+    let mut commands = Vec::new();
+    for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let bad_line = || ParseError::at_line(i + 1, format!("invalid command {:?}", line));
+        let (action_str, cubes) = line.split_once(' ').ok_or_else(bad_line)?;
+        let action = match action_str {
+            "on" => Action::On,
+            "off" => Action::Off,
+            "toggle" => Action::Toggle,
+            _ => return Err(bad_line()),
+        };
+
+        let ranges = cubes
+            .split(',')
+            .map(|range| {
+                let captures = range_re.captures(range.trim())?;
+                let begin = captures[1].parse::<i64>().ok()?;
+                let end = captures[2].parse::<i64>().ok()?;
+                (begin <= end).then_some((begin, end))
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|ranges| ranges.len() == 3)
+            .ok_or_else(bad_line)?;
+
+        commands.push(Command {
+            xr: ranges[0],
+            yr: ranges[1],
+            zr: ranges[2],
+            action,
+        });
+    }
+
+    Ok(commands)
+}
+
+/// The number of cubes on after each command, so `count_on_prefixes(commands)[i]` is the count
+/// after running `commands[..=i]`. Keeps a multiset of signed cuboids whose volumes sum to the
+/// count: each command cancels its overlap with everything so far, and an "on" command then adds
+/// itself, so the count only has to be updated by the change. A toggle cancels its overlap twice,
+/// taking the cubes that were on to -1, and then adds itself to bring them to 0 and the rest to 1
+pub fn count_on_prefixes(commands: &[Command]) -> Vec<i64> {
+    let mut signed: HashMap<Cuboid, i64> = HashMap::new();
+    let mut count = 0;
+    let mut counts = Vec::with_capacity(commands.len());
+    for command in commands {
+        let cuboid = command.cuboid();
+        let cancel = match command.action {
+            Action::On | Action::Off => 1,
+            Action::Toggle => 2,
+        };
+
+        let mut changes: HashMap<Cuboid, i64> = HashMap::new();
+        for (other, &sign) in signed.iter() {
+            if let Some(overlap) = cuboid.intersection(other) {
+                *changes.entry(overlap).or_default() -= cancel * sign;
+            }
+        }
+        if command.action != Action::Off {
+            *changes.entry(cuboid).or_default() += 1;
+        }
+
+        for (cuboid, sign) in changes {
+            count += sign * cuboid.volume();
+            let entry = signed.entry(cuboid).or_default();
+            *entry += sign;
+            if *entry == 0 {
+                signed.remove(&cuboid);
+            }
+        }
+        counts.push(count);
+    }
+
+    counts
+}
+
+#[aoc(day22, part1)]
+pub(crate) fn part1(commands: &[Command]) -> i64 {
+    let mut core = ReactorCore::new();
+    for command in commands {
+        if command.inside_init() {
+            core.execute_command(&command.restrict());
+        }
+    }
+    core.count_on()
+}
+
+/// Whether the box around every command has a volume that fits in an i64. Every region the reactor
+/// tracks is inside it, and so is every cube that's on, so none of their volumes can overflow
+fn volumes_fit(commands: &[Command]) -> bool {
+    let bounds = commands.iter().map(Command::cuboid).reduce(|a, b| Cuboid {
+        x: (a.x.0.min(b.x.0), a.x.1.max(b.x.1)),
+        y: (a.y.0.min(b.y.0), a.y.1.max(b.y.1)),
+        z: (a.z.0.min(b.z.0), a.z.1.max(b.z.1)),
+    });
+    bounds.is_none_or(|bounds| bounds.checked_volume().is_some())
+}
+
+/// None if the reactor is too big to count in an i64
+#[aoc(day22, part2)]
+pub(crate) fn part2(commands: &[Command]) -> Option<i64> {
+    part2_progress(commands, &mut Context::new(22, &mut Silent))
+}
+
+pub(crate) fn part2_progress(commands: &[Command], ctx: &mut Context) -> Option<i64> {
+    if !volumes_fit(commands) {
+        return None;
+    }
+
+    let mut core = ReactorCore::new();
+    for (i, command) in commands.iter().enumerate() {
+        core.execute_command(command);
+        ctx.percent("rebooting", i + 1, commands.len());
+    }
+    Some(core.count_on())
+}
+
+#[aoc(day22, part1, InclusionExclusion)]
+fn part1_inclusion_exclusion(commands: &[Command]) -> i64 {
+    let commands = commands
+        .iter()
+        .filter(|command| command.inside_init())
+        .map(Command::restrict)
+        .collect::<Vec<_>>();
+    count_on_prefixes(&commands).last().copied().unwrap_or(0)
+}
+
+#[aoc(day22, part2, InclusionExclusion)]
+fn part2_inclusion_exclusion(commands: &[Command]) -> Option<i64> {
+    if !volumes_fit(commands) {
+        return None;
+    }
+    Some(count_on_prefixes(commands).last().copied().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day22.txt");
+
+    /// The first example in the puzzle, small enough to check cube by cube
+    const SMALL_EXAMPLE: &str = r"on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+
+    /// The larger example for part 1, where the last two steps are outside the region it counts
+    const LARGER_EXAMPLE: &str = r"on x=-20..26,y=-36..17,z=-47..7
+on x=-20..33,y=-21..23,z=-26..28
+on x=-22..28,y=-29..23,z=-38..16
+on x=-46..7,y=-6..46,z=-50..-1
+on x=-49..1,y=-3..46,z=-24..28
+on x=2..47,y=-22..22,z=-23..27
+on x=-27..23,y=-28..26,z=-21..29
+on x=-39..5,y=-6..47,z=-3..44
+on x=-30..21,y=-8..43,z=-13..34
+on x=-22..26,y=-27..20,z=-29..19
+off x=-48..-32,y=26..41,z=-47..-37
+on x=-12..35,y=6..50,z=-50..-2
+off x=-48..-32,y=-32..-16,z=-15..-5
+on x=-18..26,y=-33..15,z=-7..46
+off x=-40..-22,y=-38..-28,z=23..41
+on x=-16..35,y=-41..10,z=-47..6
+off x=-32..-23,y=11..30,z=-14..3
+on x=-49..-5,y=-3..45,z=-29..18
+off x=18..30,y=-20..-8,z=-3..13
+on x=-41..9,y=-7..43,z=-33..15
+on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
+on x=967..23432,y=45373..81175,z=27513..53682";
+
+    #[test]
+    fn small_test_center() {
+        let input = parse_commands("on x=-1..2,y=-1..1,z=-1..1\noff x=0..0,y=0..0,z=0..0").unwrap();
+        assert_eq!(part1(&input), 35);
+
+        let input = parse_commands(
+            r"on x=-1..2,y=-1..1,z=-1..1
+off x=0..0,y=0..0,z=0..0
+on x=0..0,y=0..0,z=0..0",
+        )
+        .unwrap();
+        assert_eq!(part1(&input), 36);
+
+        let input = parse_commands(
+            r"on x=-1..2,y=-1..1,z=-1..1
+off x=0..0,y=0..0,z=0..0
+on x=-1..2,y=-1..1,z=-1..1",
+        )
+        .unwrap();
+        assert_eq!(part1(&input), 36);
+    }
+
+    #[test]
+    fn small_test_overlap() {
+        let input = parse_commands("on x=-1..2,y=-1..1,z=-1..1\noff x=0..0,y=0..0,z=0..3").unwrap();
+        assert_eq!(part1(&input), 34);
+    }
+
+    #[test]
+    fn small_example() {
+        let input = parse_commands(SMALL_EXAMPLE).unwrap();
+
+        assert_eq!(part2(&input), Some(39));
+    }
+
+    #[test]
+    fn small_test_corner() {
+        // (0, 0, 0), (0, 0, 1),
+        // (0, 1, 0), (0, 1, 1),
+        // (1, 0, 0), (1, 0, 1),
+        // (1, 1, 0), (1, 1, 1),
+        let input = parse_commands("on x=-1..1,y=-1..1,z=-1..1\noff x=0..2,y=0..2,z=0..2").unwrap();
+        assert_eq!(part1(&input), 19);
+
+        let input = parse_commands("on x=-1..1,y=-1..1,z=-1..1\non x=0..2,y=0..2,z=0..2").unwrap();
+        assert_eq!(part1(&input), 46);
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let commands = parse_commands(
+            r"on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11",
+        )
+        .unwrap();
+
+        let mut core = ReactorCore::new();
+        for command in commands.iter() {
+            core.execute_command(command);
+        }
+
+        let snapshot = core.to_snapshot();
+        let mut resumed = ReactorCore::from_snapshot(&snapshot);
+        assert_eq!(resumed.count_on(), core.count_on());
+        assert_eq!(resumed.to_snapshot(), snapshot);
+
+        // Resuming and running the rest of the commands matches running them all in one go
+        let last = parse_commands("on x=10..10,y=10..10,z=10..10").unwrap();
+        resumed.execute_command(&last[0]);
+        assert_eq!(resumed.count_on(), 39);
+    }
+
+    #[test]
+    fn index_matches_grid() {
+        let mut rng = Xorshift::default();
+
+        const SIZE: i64 = 12;
+        let mut range = || {
+            let a = (rng.next_u32() % SIZE as u32) as i64;
+            let b = (rng.next_u32() % SIZE as u32) as i64;
+            (min(a, b), max(a, b))
+        };
+
+        for _ in 0..20 {
+            let mut grid = vec![false; (SIZE * SIZE * SIZE) as usize];
+            let cell = |x: i64, y: i64, z: i64| (x + SIZE * (y + SIZE * z)) as usize;
+
+            let mut core = ReactorCore::new();
+            for i in 0..15 {
+                let command = Command {
+                    xr: range(),
+                    yr: range(),
+                    zr: range(),
+                    action: if i % 3 == 2 { Action::Off } else { Action::On },
+                };
+                for z in command.zr.0..=command.zr.1 {
+                    for y in command.yr.0..=command.yr.1 {
+                        for x in command.xr.0..=command.xr.1 {
+                            grid[cell(x, y, z)] = command.action == Action::On;
+                        }
+                    }
+                }
+                core.execute_command(&command);
+            }
+
+            let index = core.index();
+            for z in -1..=SIZE {
+                for y in -1..=SIZE {
+                    for x in -1..=SIZE {
+                        let inside = [x, y, z].iter().all(|v| (0..SIZE).contains(v));
+                        assert_eq!(index.is_on(x, y, z), inside && grid[cell(x, y, z)]);
+                    }
+                }
+            }
+
+            for _ in 0..50 {
+                let area = Cuboid {
+                    x: range(),
+                    y: range(),
+                    z: range(),
+                };
+                let mut expected = 0;
+                for z in area.z.0..=area.z.1 {
+                    for y in area.y.0..=area.y.1 {
+                        for x in area.x.0..=area.x.1 {
+                            expected += grid[cell(x, y, z)] as i64;
+                        }
+                    }
+                }
+                assert_eq!(index.count_on_in(&area), expected);
+            }
+
+            let everything = Cuboid {
+                x: (-1, SIZE),
+                y: (-1, SIZE),
+                z: (-1, SIZE),
+            };
+            assert_eq!(index.count_on_in(&everything), core.count_on());
+        }
+    }
+
+    #[test]
+    fn toggle() {
+        let commands = parse_commands(
+            r"on x=0..2,y=0..2,z=0..2
+toggle x=1..3,y=1..3,z=1..3
+toggle x=1..3,y=1..3,z=1..3
+toggle x=1..3,y=1..3,z=1..3
+off x=0..0,y=0..3,z=0..3
+toggle x=-1..4,y=-1..4,z=-1..4",
+        )
+        .unwrap();
+        assert_eq!(commands[1].action, Action::Toggle);
+        assert_eq!(count_on_prefixes(&commands), [27, 38, 27, 38, 29, 187]);
+        for n in 1..=commands.len() {
+            assert_eq!(
+                part2(&commands[..n]),
+                Some(count_on_prefixes(&commands[..n])[n - 1])
+            );
+        }
+
+        let mut rng = Xorshift::default();
+
+        const SIZE: i64 = 10;
+        let mut range = || {
+            let a = (rng.next_u32() % SIZE as u32) as i64;
+            let b = (rng.next_u32() % SIZE as u32) as i64;
+            (min(a, b), max(a, b))
+        };
+
+        for _ in 0..20 {
+            let mut grid = vec![false; (SIZE * SIZE * SIZE) as usize];
+            let cell = |x: i64, y: i64, z: i64| (x + SIZE * (y + SIZE * z)) as usize;
+
+            let mut core = ReactorCore::new();
+            let mut commands = Vec::new();
+            for i in 0..15 {
+                let command = Command {
+                    xr: range(),
+                    yr: range(),
+                    zr: range(),
+                    action: [Action::On, Action::Toggle, Action::Off][i % 3],
+                };
+                for z in command.zr.0..=command.zr.1 {
+                    for y in command.yr.0..=command.yr.1 {
+                        for x in command.xr.0..=command.xr.1 {
+                            let cube = &mut grid[cell(x, y, z)];
+                            *cube = match command.action {
+                                Action::On => true,
+                                Action::Off => false,
+                                Action::Toggle => !*cube,
+                            };
+                        }
+                    }
+                }
+                core.execute_command(&command);
+                commands.push(command);
+
+                let expected = grid.iter().filter(|&&on| on).count() as i64;
+                assert_eq!(core.count_on(), expected);
+                assert_eq!(count_on_prefixes(&commands).last(), Some(&expected));
+            }
+
+            let index = core.index();
+            for z in 0..SIZE {
+                for y in 0..SIZE {
+                    for x in 0..SIZE {
+                        assert_eq!(index.is_on(x, y, z), grid[cell(x, y, z)]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tree() {
+        let commands = parse_commands(
+            r"on x=0..2,y=0..2,z=0..2
+off x=1..1,y=1..1,z=1..1
+on x=10..11,y=10..10,z=10..10",
+        )
+        .unwrap();
+        let mut core = ReactorCore::new();
+        for command in commands.iter() {
+            core.execute_command(command);
+        }
+
+        let nodes = core.iter_tree().collect::<Vec<_>>();
+        assert!(nodes.iter().all(|node| node.on && node.child_volume == 0));
+        assert_eq!(
+            nodes.iter().map(|node| node.self_volume).sum::<i64>(),
+            core.count_on()
+        );
+        let far = Cuboid {
+            x: (10, 11),
+            y: (10, 10),
+            z: (10, 10),
+        };
+        assert_eq!(nodes.iter().filter(|node| node.bounds == far).count(), 1);
+
+        let mut region = Region::from_cuboid(&far, true);
+        region
+            .sub_regions
+            .push(Region::new(11..=11, 10..=10, 10..=10, false));
+        let nodes = region.iter_tree().collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            [
+                RegionNode {
+                    depth: 0,
+                    on: true,
+                    bounds: far,
+                    self_volume: 2,
+                    child_volume: 1,
+                },
+                RegionNode {
+                    depth: 1,
+                    on: false,
+                    bounds: Cuboid {
+                        x: (11, 11),
+                        y: (10, 10),
+                        z: (10, 10),
+                    },
+                    self_volume: 1,
+                    child_volume: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            format!("{:?}", region),
+            "on (10..=11, 10..=10, 10..=10) -- 2 - 1\n  off (11..=11, 10..=10, 10..=10) -- 1 - 0\n"
+        );
+        assert_eq!(core.to_string().lines().count(), core.iter_tree().count());
+    }
+
+    #[test]
+    fn difference() {
+        let a = Cuboid {
+            x: (0, 4),
+            y: (0, 4),
+            z: (0, 4),
+        };
+        let b = Cuboid {
+            x: (1, 2),
+            y: (3, 6),
+            z: (-1, 1),
+        };
+        let pieces = a.difference(&b);
+        assert_eq!(
+            pieces.iter().map(Cuboid::volume).sum::<i64>(),
+            a.volume() - a.intersection(&b).unwrap().volume()
+        );
+        for (i, piece) in pieces.iter().enumerate() {
+            assert!(a.contains(piece));
+            assert_eq!(piece.intersection(&b), None);
+            assert!(pieces[..i].iter().all(|p| p.intersection(piece).is_none()));
+        }
+
+        let far = Cuboid { x: (10, 11), ..b };
+        assert_eq!(a.difference(&far), [a]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn surface() {
+        let run = |input: &str| {
+            let mut core = ReactorCore::new();
+            for command in parse_commands(input).unwrap().iter() {
+                core.execute_command(command);
+            }
+            core
+        };
+
+        let core = run("on x=0..0,y=0..0,z=0..0");
+        assert_eq!(core.surface_area(), 6);
+        assert_eq!(
+            core.bounding_box(),
+            Some(Cuboid {
+                x: (0, 0),
+                y: (0, 0),
+                z: (0, 0)
+            })
+        );
+
+        // Touching along x, and the same pair split up differently
+        let core = run("on x=0..0,y=0..0,z=0..0\non x=1..1,y=0..0,z=0..0");
+        assert_eq!(core.surface_area(), 10);
+        let core = run("on x=0..1,y=0..0,z=0..0\non x=1..1,y=0..0,z=0..0");
+        assert_eq!(core.surface_area(), 10);
+
+        // Touching only along an edge
+        let core = run("on x=0..0,y=0..0,z=0..0\non x=1..1,y=1..1,z=0..0");
+        assert_eq!(core.surface_area(), 12);
+
+        // The hole in the middle adds its six faces on the inside
+        let core = run("on x=-1..1,y=-1..1,z=-1..1\noff x=0..0,y=0..0,z=0..0");
+        assert_eq!(core.surface_area(), 54 + 6);
+        assert_eq!(
+            core.bounding_box(),
+            Some(Cuboid {
+                x: (-1, 1),
+                y: (-1, 1),
+                z: (-1, 1)
+            })
+        );
+
+        let core = run("on x=0..3,y=0..3,z=0..3\noff x=-1..4,y=-1..4,z=-1..4");
+        assert_eq!(core.surface_area(), 0);
+        assert_eq!(core.bounding_box(), None);
+
+        // Against a grid, counting on cubes next to off ones
+        let core = run(SMALL_EXAMPLE);
+        let index = core.index();
+        let mut faces = 0;
+        for z in 8..16 {
+            for y in 8..16 {
+                for x in 8..16 {
+                    if index.is_on(x, y, z) {
+                        faces += [(1, 0, 0), (0, 1, 0), (0, 0, 1)]
+                            .iter()
+                            .flat_map(|&(dx, dy, dz)| [(dx, dy, dz), (-dx, -dy, -dz)])
+                            .filter(|&(dx, dy, dz)| !index.is_on(x + dx, y + dy, z + dz))
+                            .count() as i64;
+                    }
+                }
+            }
+        }
+        assert_eq!(core.surface_area(), faces);
+        assert_eq!(
+            core.bounding_box(),
+            Some(Cuboid {
+                x: (10, 13),
+                y: (10, 13),
+                z: (10, 13)
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshot_json() {
+        let commands =
+            parse_commands("on x=-1..2,y=-1..1,z=-1..1\noff x=0..0,y=0..0,z=0..3").unwrap();
+        let mut core = ReactorCore::new();
+        for command in commands.iter() {
+            core.execute_command(command);
+        }
+
+        let json = serde_json::to_string(&core.to_snapshot()).unwrap();
+        let snapshot: ReactorSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(ReactorCore::from_snapshot(&snapshot).count_on(), 34);
+    }
+
+    #[test]
+    fn prefix_counts() {
+        let commands = parse_commands(SMALL_EXAMPLE).unwrap();
+        assert_eq!(count_on_prefixes(&commands), [27, 46, 38, 39]);
+
+        // Leaving out the last two, which are outside the region part 1 counts
+        let commands = &parse_commands(LARGER_EXAMPLE).unwrap()[..20];
+        let counts = count_on_prefixes(commands);
+        assert_eq!(counts.len(), commands.len());
+        for (n, &count) in counts.iter().enumerate() {
+            assert_eq!(Some(count), part2(&commands[..=n]));
+        }
+        assert_eq!(counts.last(), Some(&590784));
+    }
+
+    #[test]
+    fn test1() {
+        let input = parse_commands(LARGER_EXAMPLE).unwrap();
+
+        assert_eq!(part1(&input), 590784);
+    }
+
+    #[test]
+    fn test2() {
+        assert_eq!(
+            part2(&parse_commands(EXAMPLE).unwrap()),
+            Some(2758514936282235)
+        );
+
+        // Regions at the very ends of the i64s split without stepping past them
+        for edge in [
+            "on x=9223372036854775806..9223372036854775807,y=0..0,z=0..0\n\
+             off x=9223372036854775807..9223372036854775807,y=0..1,z=0..0",
+            "on x=-9223372036854775808..-9223372036854775807,y=0..0,z=0..0\n\
+             off x=-9223372036854775808..-9223372036854775808,y=0..1,z=0..0",
+        ] {
+            let commands = parse_commands(edge).unwrap();
+            assert_eq!(part2(&commands), Some(1));
+            assert_eq!(part2_inclusion_exclusion(&commands), Some(1));
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let err = |input| parse_commands(input).err().map(|err| err.line());
+        assert_eq!(
+            err("on x=0..1,y=0..1,z=0..1\nflip x=0..1,y=0..1,z=0..1"),
+            Some(2)
+        );
+        assert_eq!(err("on x=0..1,y=0..1"), Some(1));
+        assert_eq!(err("on x=1..0,y=0..1,z=0..1"), Some(1));
+        assert_eq!(err("on x=0..1,y=0..1,z=0..99999999999999999999"), Some(1));
+        assert_eq!(err("on"), Some(1));
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day22.txt");
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(
+            parse_commands(EXAMPLE).unwrap(),
+            part1,
+            part1_inclusion_exclusion
+        );
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(
+            parse_commands(EXAMPLE).unwrap(),
+            part2,
+            part2_inclusion_exclusion
+        );
+    }
+}
+
+/// Random commands in a box small enough to simulate one cube at a time, checked against the
+/// reactor after every command
+#[cfg(test)]
+mod differential {
+    use super::*;
+    use crate::testing::Xorshift;
+    use std::collections::HashSet;
+
+    const REACH: i64 = 15;
+
+    /// The cubes that are on after `commands`, turned on and off one by one
+    fn brute_force(commands: &[Command]) -> HashSet<(i64, i64, i64)> {
+        let mut cubes = HashSet::new();
+        for command in commands {
+            for x in command.xr.0..=command.xr.1 {
+                for y in command.yr.0..=command.yr.1 {
+                    for z in command.zr.0..=command.zr.1 {
+                        let cube = (x, y, z);
+                        match command.action {
+                            Action::On => {
+                                cubes.insert(cube);
+                            }
+                            Action::Off => {
+                                cubes.remove(&cube);
+                            }
+                            Action::Toggle => {
+                                if !cubes.remove(&cube) {
+                                    cubes.insert(cube);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        cubes
+    }
+
+    /// Whether the reactor's count differs from the brute force one after any of the commands
+    fn disagrees(commands: &[Command]) -> bool {
+        let mut core = ReactorCore::new();
+        (0..commands.len()).any(|i| {
+            core.execute_command(&commands[i]);
+            core.count_on() != brute_force(&commands[..=i]).len() as i64
+        })
+    }
+
+    /// Shrink a failing list of commands while it keeps failing: drop whole commands first, then
+    /// pull in the ends of their ranges, until nothing more can go
+    fn minimize(mut commands: Vec<Command>, fails: impl Fn(&[Command]) -> bool) -> Vec<Command> {
+        assert!(fails(&commands), "Nothing to minimize");
+
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for i in (0..commands.len()).rev() {
+                let removed = commands.remove(i);
+                if fails(&commands) {
+                    shrunk = true;
+                } else {
+                    commands.insert(i, removed);
+                }
+            }
+
+            for i in 0..commands.len() {
+                for axis in 0..3 {
+                    for end in 0..2 {
+                        loop {
+                            let mut candidate = commands.clone();
+                            let command = &mut candidate[i];
+                            let range = match axis {
+                                0 => &mut command.xr,
+                                1 => &mut command.yr,
+                                _ => &mut command.zr,
+                            };
+                            if range.0 == range.1 {
+                                break;
+                            }
+                            match end {
+                                0 => range.0 += 1,
+                                _ => range.1 -= 1,
+                            }
+                            if !fails(&candidate) {
+                                break;
+                            }
+                            commands = candidate;
+                            shrunk = true;
+                        }
+                    }
+                }
+            }
+        }
+        commands
+    }
+
+    /// The commands as puzzle input, to paste into a test
+    fn format_commands(commands: &[Command]) -> String {
+        commands
+            .iter()
+            .map(|command| {
+                let action = match command.action {
+                    Action::On => "on",
+                    Action::Off => "off",
+                    Action::Toggle => "toggle",
+                };
+                format!(
+                    "{} x={}..{},y={}..{},z={}..{}\n",
+                    action,
+                    command.xr.0,
+                    command.xr.1,
+                    command.yr.0,
+                    command.yr.1,
+                    command.zr.0,
+                    command.zr.1
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_commands() {
+        // Mostly small cuboids, so they overlap in many different ways without filling the box
+        fn range(rng: &mut Xorshift) -> (i64, i64) {
+            let start = (rng.next_u32() % (2 * REACH as u32 + 1)) as i64 - REACH;
+            let len = (rng.next_u32() % [3, 8, 31][rng.next_u32() as usize % 3]) as i64;
+            (start, (start + len).min(REACH))
+        }
+
+        let mut rng = Xorshift::new(0x6b43a9b5);
+        for case in 0..300 {
+            let len = 1 + rng.next_u32() as usize % 12;
+            let commands = (0..len)
+                .map(|_| Command {
+                    xr: range(&mut rng),
+                    yr: range(&mut rng),
+                    zr: range(&mut rng),
+                    action: [Action::On, Action::On, Action::Off, Action::Toggle]
+                        [rng.next_u32() as usize % 4],
+                })
+                .collect::<Vec<_>>();
+
+            if disagrees(&commands) {
+                let smallest = minimize(commands, disagrees);
+                panic!(
+                    "Case {} disagrees with the brute force count on:\n{}",
+                    case,
+                    format_commands(&smallest)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn minimizer() {
+        let commands = parse_commands(
+            r"on x=-5..5,y=-5..5,z=-5..5
+off x=-15..15,y=0..0,z=-15..15
+toggle x=0..3,y=0..3,z=0..3
+on x=10..12,y=10..10,z=10..10",
+        )
+        .unwrap();
+        assert_eq!(
+            parse_commands(&format_commands(&commands)).unwrap().len(),
+            4
+        );
+
+        // Leaves the one command needed to turn on the fewest cubes it takes
+        let smallest = minimize(commands, |commands| brute_force(commands).len() >= 2);
+        assert_eq!(smallest.len(), 1);
+        assert_eq!(brute_force(&smallest).len(), 2);
+        assert_eq!(smallest[0].action, Action::On);
+    }
+}
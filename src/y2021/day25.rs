@@ -0,0 +1,185 @@
+use crate::error::ParseError;
+use crate::grid::parse_rows;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SeaCucumber {
+    East,
+    South,
+    Empty,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeaFloor {
+    cucumbers: Vec<Vec<SeaCucumber>>,
+}
+
+impl std::fmt::Debug for SeaFloor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cucumbers.iter() {
+            writeln!(f)?;
+            for cucumber in row.iter() {
+                let c = match cucumber {
+                    SeaCucumber::East => '>',
+                    SeaCucumber::South => 'v',
+                    SeaCucumber::Empty => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SeaFloor {
+    pub fn with_cucumbers(cucumbers: Vec<Vec<SeaCucumber>>) -> Self {
+        SeaFloor { cucumbers }
+    }
+
+    /// Move the east facing herd, then the south facing herd. Returns true if any cucumber moved
+    pub fn step(&mut self) -> bool {
+        let moved_east = self.move_herd(SeaCucumber::East, 0, 1);
+        let moved_south = self.move_herd(SeaCucumber::South, 1, 0);
+        moved_east || moved_south
+    }
+
+    /// The floor followed by its state after each of the next `steps` steps
+    pub fn history(&self, steps: usize) -> Vec<SeaFloor> {
+        let mut floor = self.clone();
+        let mut history = Vec::with_capacity(steps + 1);
+        history.push(floor.clone());
+        for _ in 0..steps {
+            floor.step();
+            history.push(floor.clone());
+        }
+        history
+    }
+
+    /// Every cucumber of the herd looks at the space in front of it before any of them move, and
+    /// cucumbers that go off an edge wrap around to the other side
+    fn move_herd(&mut self, herd: SeaCucumber, drow: usize, dcol: usize) -> bool {
+        let rows = self.cucumbers.len();
+        let cols = self.cucumbers.first().map_or(0, |row| row.len());
+
+        let mut moves = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let next = ((row + drow) % rows, (col + dcol) % cols);
+                if self.cucumbers[row][col] == herd
+                    && self.cucumbers[next.0][next.1] == SeaCucumber::Empty
+                {
+                    moves.push(((row, col), next));
+                }
+            }
+        }
+
+        for &((row, col), (next_row, next_col)) in moves.iter() {
+            self.cucumbers[row][col] = SeaCucumber::Empty;
+            self.cucumbers[next_row][next_col] = herd;
+        }
+
+        !moves.is_empty()
+    }
+}
+
+#[aoc_generator(day25)]
+pub(crate) fn sea_floor(input: &str) -> Result<SeaFloor, ParseError> {
+    let cucumbers = parse_rows(input, |c| match c {
+        '>' => Ok(SeaCucumber::East),
+        'v' => Ok(SeaCucumber::South),
+        '.' => Ok(SeaCucumber::Empty),
+        _ => Err(format!("unknown sea floor tile {:?}", c)),
+    })?;
+
+    Ok(SeaFloor::with_cucumbers(cucumbers))
+}
+
+#[cfg(feature = "viz")]
+fn render(floor: &SeaFloor) -> crate::viz::Canvas {
+    use crate::viz::{Canvas, BLACK, RED, YELLOW};
+
+    let rows = floor.cucumbers.len();
+    let cols = floor.cucumbers.first().map_or(0, |row| row.len());
+    let mut canvas = Canvas::new(cols, rows);
+    for (row, cucumbers) in floor.cucumbers.iter().enumerate() {
+        for (col, cucumber) in cucumbers.iter().enumerate() {
+            match cucumber {
+                SeaCucumber::East => canvas.set(col, row, '>', RED),
+                SeaCucumber::South => canvas.set(col, row, 'v', YELLOW),
+                SeaCucumber::Empty => canvas.set(col, row, '.', BLACK),
+            }
+        }
+    }
+    canvas
+}
+
+/// One frame per floor, e.g. from `SeaFloor::history`
+#[cfg(feature = "viz")]
+pub fn render_history(history: &[SeaFloor]) -> Vec<crate::viz::Canvas> {
+    history.iter().map(render).collect()
+}
+
+/// One frame for the starting floor and one after each step, until the cucumbers stop moving
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    let mut floor = match sea_floor(input) {
+        Ok(floor) => floor,
+        Err(_) => return Vec::new(),
+    };
+    let mut frames = vec![render(&floor)];
+    while floor.step() {
+        frames.push(render(&floor));
+    }
+    frames
+}
+
+/// The first step on which no cucumber moves, or None if they go round in circles forever. Loops
+/// are found the way Brent's algorithm does, comparing against a saved floor that is replaced
+/// whenever the number of steps since it was saved reaches the next power of two
+#[aoc(day25, part1)]
+pub(crate) fn part1(floor: &SeaFloor) -> Option<usize> {
+    let mut floor = floor.clone();
+    let mut saved = floor.clone();
+    let mut since_saved = 0;
+    let mut power = 1;
+
+    let mut num_steps = 1;
+    while floor.step() {
+        num_steps += 1;
+        since_saved += 1;
+        if floor == saved {
+            return None;
+        }
+        if since_saved == power {
+            saved = floor.clone();
+            since_saved = 0;
+            power *= 2;
+        }
+    }
+
+    Some(num_steps)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn small_example() {
+        let input = sea_floor("...>>>>>...").unwrap();
+        let history = input.history(2);
+        assert_eq!(format!("{:?}", history[1]), "\n...>>>>.>..");
+        assert_eq!(format!("{:?}", history[2]), "\n...>>>.>.>.");
+    }
+
+    #[test]
+    fn never_stops() {
+        // Each herd goes round and round its own row or column
+        assert_eq!(part1(&sea_floor(">..\n...\n").unwrap()), None);
+        assert_eq!(part1(&sea_floor(".v\n..\n>.").unwrap()), None);
+        assert_eq!(part1(&sea_floor(">>").unwrap()), Some(1));
+        assert!(sea_floor(">.\n>").is_err());
+    }
+}
@@ -0,0 +1,362 @@
+use crate::error::ParseError;
+
+/// The diagnostic numbers from the submarine, each `width` bits wide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticReport {
+    pub numbers: Vec<u32>,
+    pub width: u32,
+}
+
+impl DiagnosticReport {
+    /// The most common value of each bit, highest bit first
+    pub fn gamma(&self) -> u32 {
+        (0..self.width)
+            .rev()
+            .map(|bit| most_common(&self.numbers, bit))
+            .fold(0, |v, n| (v << 1) + n)
+    }
+
+    /// The least common value of each bit, which is every bit of `gamma` flipped
+    pub fn epsilon(&self) -> u32 {
+        self.gamma() ^ ((1u64 << self.width) - 1) as u32
+    }
+
+    /// The number left by keeping the more common value of each bit, or None if there isn't one
+    pub fn oxygen_rating(&self) -> Option<u32> {
+        self.ratings(&[Rating::most_common()])[0]
+    }
+
+    /// The number left by keeping the less common value of each bit, or None if there isn't one
+    pub fn co2_rating(&self) -> Option<u32> {
+        self.ratings(&[Rating::least_common()])[0]
+    }
+
+    /// `rate` the numbers in the report
+    pub fn ratings(&self, ratings: &[Rating]) -> Vec<Option<u32>> {
+        rate(&self.numbers, self.width, ratings)
+    }
+}
+
+/// Which value of each bit a `Rating` keeps
+enum Keep {
+    MostCommon,
+    LeastCommon,
+    Custom(Box<dyn Fn(usize, usize) -> u32>),
+}
+
+/// How to pick a rating out of the diagnostic numbers: the value of each bit to keep, given how
+/// many of the numbers still left have it set
+pub struct Rating {
+    keep: Keep,
+    tie: Option<u32>,
+}
+
+impl Rating {
+    /// Keep the more common value, 1 when they're equally common. The oxygen generator rating
+    pub fn most_common() -> Self {
+        Rating {
+            keep: Keep::MostCommon,
+            tie: None,
+        }
+    }
+
+    /// Keep the less common value, 0 when they're equally common. The CO2 scrubber rating
+    pub fn least_common() -> Self {
+        Rating {
+            keep: Keep::LeastCommon,
+            tie: None,
+        }
+    }
+
+    /// Keep whichever value `keep` picks from the number of (ones, zeros) left. It's asked about
+    /// ties too, unless `on_tie` settles them. Any value but 0 or 1 rules out every number
+    pub fn custom(keep: impl Fn(usize, usize) -> u32 + 'static) -> Self {
+        Rating {
+            keep: Keep::Custom(Box::new(keep)),
+            tie: None,
+        }
+    }
+
+    /// Keep `value` whenever ones and zeros are equally common
+    pub fn on_tie(mut self, value: u32) -> Self {
+        self.tie = Some(value);
+        self
+    }
+
+    fn pick(&self, ones: usize, zeros: usize) -> u32 {
+        match (&self.keep, self.tie) {
+            (_, Some(tie)) if ones == zeros => tie,
+            (Keep::MostCommon, _) => (ones >= zeros) as u32,
+            (Keep::LeastCommon, _) => (ones < zeros) as u32,
+            (Keep::Custom(keep), _) => keep(ones, zeros),
+        }
+    }
+}
+
+#[aoc_generator(day3)]
+pub(crate) fn binary(input: &str) -> Result<DiagnosticReport, ParseError> {
+    let mut nums = Vec::new();
+    let mut width = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let expected_width = *width.get_or_insert(line.len());
+        if line.len() != expected_width {
+            return Err(ParseError::at_line(
+                i + 1,
+                format!("expected {} bits, found {:?}", expected_width, line),
+            ));
+        }
+        if line.len() > u32::BITS as usize {
+            return Err(ParseError::at_line(
+                i + 1,
+                format!("expected at most 32 bits, found {:?}", line),
+            ));
+        }
+
+        let num = u32::from_str_radix(line, 2).map_err(|err| {
+            ParseError::at_line(i + 1, format!("invalid binary number {:?} ({})", line, err))
+        })?;
+        nums.push(num);
+    }
+
+    Ok(DiagnosticReport {
+        numbers: nums,
+        width: width.unwrap_or(0) as u32,
+    })
+}
+
+fn ones_at(nums: &[u32], bit: u32) -> usize {
+    nums.iter().filter(|&n| ((n >> bit) & 1) == 1).count()
+}
+
+/// The more common value of `bit`, choosing 1 when they're equally common
+pub fn most_common(nums: &[u32], bit: u32) -> u32 {
+    (2 * ones_at(nums, bit) >= nums.len()) as u32
+}
+
+/// The less common value of `bit`, choosing 0 when they're equally common
+pub fn least_common(nums: &[u32], bit: u32) -> u32 {
+    (2 * ones_at(nums, bit) < nums.len()) as u32
+}
+
+/// Starting from the highest of `width` bits, keep only the numbers whose bit matches the value
+/// `criteria` picks for the numbers still left, until one number remains. `None` if the criteria
+/// rule out every number, or if several different numbers are left after the last bit
+pub fn filter_by_bit_criteria(
+    nums: &[u32],
+    width: u32,
+    criteria: impl Fn(&[u32], u32) -> u32,
+) -> Option<u32> {
+    let mut nums = nums.to_vec();
+    for bit in (0..width).rev() {
+        if nums.len() <= 1 {
+            break;
+        }
+
+        let keep = criteria(&nums, bit);
+        nums.retain(|n| ((n >> bit) & 1) == keep);
+    }
+
+    let &first = nums.first()?;
+    nums.iter().all(|&n| n == first).then_some(first)
+}
+
+/// Every rating of the numbers, found the way `filter_by_bit_criteria` finds one but all in the
+/// same pass over the bits. Only the low `width` bits of each number are looked at.
+///
+/// Once sorted, the numbers left for a rating share every bit above the one being checked, so they
+/// are a range with the ones at this bit after the zeros. Each rating narrows its range to one
+/// side of that split, found with a binary search instead of a count over the numbers
+pub fn rate(nums: &[u32], width: u32, ratings: &[Rating]) -> Vec<Option<u32>> {
+    let mask = ((1u64 << width) - 1) as u32;
+    let mut sorted = nums.iter().map(|&n| n & mask).collect::<Vec<_>>();
+    sorted.sort_unstable();
+
+    let mut ranges = vec![0..sorted.len(); ratings.len()];
+    for bit in (0..width).rev() {
+        for (rating, range) in ratings.iter().zip(ranges.iter_mut()) {
+            if range.len() <= 1 {
+                continue;
+            }
+
+            let split =
+                range.start + sorted[range.clone()].partition_point(|n| (n >> bit) & 1 == 0);
+            *range = match rating.pick(range.end - split, split - range.start) {
+                0 => range.start..split,
+                1 => split..range.end,
+                _ => split..split,
+            };
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| {
+            let left = &sorted[range];
+            let (&first, &last) = (left.first()?, left.last()?);
+            (first == last).then_some(first)
+        })
+        .collect()
+}
+
+#[aoc(day3, part1)]
+pub(crate) fn part1(report: &DiagnosticReport) -> u64 {
+    report.gamma() as u64 * report.epsilon() as u64
+}
+
+#[aoc(day3, part2)]
+pub(crate) fn part2(report: &DiagnosticReport) -> Option<u64> {
+    match report.ratings(&[Rating::most_common(), Rating::least_common()])[..] {
+        [Some(oxygen), Some(co2)] => Some(oxygen as u64 * co2 as u64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day03.txt");
+
+    #[test]
+    fn rates() {
+        let report = binary(EXAMPLE).unwrap();
+        assert_eq!(report.width, 5);
+        assert_eq!(report.numbers.len(), 12);
+        assert_eq!(report.gamma(), 22);
+        assert_eq!(report.epsilon(), 9);
+        assert_eq!(part1(&report), 198);
+
+        let report = DiagnosticReport {
+            numbers: vec![0b1100, 0b1010, 0b0011],
+            width: 4,
+        };
+        assert_eq!(report.gamma(), 0b1010);
+        assert_eq!(report.epsilon(), 0b0101);
+
+        // As wide as the numbers go, with an answer past a u32
+        let report = binary(&format!("{:032b}", 0xffff0000u32)).unwrap();
+        assert_eq!((report.gamma(), report.epsilon()), (0xffff0000, 0xffff));
+        assert_eq!(part1(&report), 0xffff0000 * 0xffff);
+        assert_eq!(part2(&report), Some(0xffff0000 * 0xffff0000));
+    }
+
+    #[test]
+    fn ratings() {
+        let report = binary(EXAMPLE).unwrap();
+        assert_eq!(report.oxygen_rating(), Some(23));
+        assert_eq!(report.co2_rating(), Some(10));
+        assert_eq!(part2(&report), Some(230));
+
+        let report = DiagnosticReport {
+            numbers: vec![0b11, 0b11, 0b10],
+            width: 2,
+        };
+        assert_eq!(report.oxygen_rating(), Some(0b11));
+        assert_eq!(report.co2_rating(), None);
+    }
+
+    #[test]
+    fn bad_line() {
+        let err = binary("00100\n11110\n10210\n10111").unwrap_err();
+        assert_eq!(err.line(), 3);
+        assert!(err.to_string().starts_with("line 3:"), "{}", err);
+
+        let err = binary("00100\n11110\n10111\n1011").unwrap_err();
+        assert_eq!(err.line(), 4);
+
+        // Leading zeros would still fit a u32, but the bits past 32 can't be checked
+        let err = binary(&format!("{:033b}", 1)).unwrap_err();
+        assert_eq!(err.line(), 1);
+    }
+
+    #[test]
+    fn ties() {
+        assert_eq!(most_common(&[0b10, 0b01], 0), 1);
+        assert_eq!(least_common(&[0b10, 0b01], 0), 0);
+        assert_eq!(most_common(&[0b10, 0b00, 0b11], 1), 1);
+        assert_eq!(least_common(&[0b10, 0b00, 0b11], 1), 0);
+
+        // An even split at every bit
+        let nums = [0b00, 0b01, 0b10, 0b11];
+        assert_eq!(filter_by_bit_criteria(&nums, 2, most_common), Some(0b11));
+        assert_eq!(filter_by_bit_criteria(&nums, 2, least_common), Some(0b00));
+
+        let nums = [0b110, 0b011, 0b101, 0b100];
+        assert_eq!(filter_by_bit_criteria(&nums, 3, most_common), Some(0b101));
+        assert_eq!(filter_by_bit_criteria(&nums, 3, least_common), Some(0b011));
+    }
+
+    #[test]
+    fn criteria() {
+        // Every number has the top bit set, so there's nothing with the less common value
+        let nums = [0b11, 0b11, 0b10];
+        assert_eq!(filter_by_bit_criteria(&nums, 2, least_common), None);
+        assert_eq!(filter_by_bit_criteria(&nums, 2, most_common), Some(0b11));
+
+        // Duplicates left at the end are still a single rating
+        assert_eq!(filter_by_bit_criteria(&[5, 5], 3, most_common), Some(5));
+        assert_eq!(filter_by_bit_criteria(&[], 3, most_common), None);
+
+        let always_zero = |_: &[u32], _: u32| 0;
+        assert_eq!(
+            filter_by_bit_criteria(&[0b101, 0b001, 0b000], 3, always_zero),
+            Some(0b000)
+        );
+    }
+
+    #[test]
+    fn custom_ratings() {
+        let report = binary(EXAMPLE).unwrap();
+        // Keep the ones whenever there are any, which only leaves the largest number
+        let largest = Rating::custom(|ones, _| (ones > 0) as u32);
+        assert_eq!(
+            report.ratings(&[Rating::most_common(), Rating::least_common(), largest]),
+            [Some(23), Some(10), report.numbers.iter().copied().max()]
+        );
+
+        // Ties settled the other way round, and by the predicate when nothing else settles them
+        let nums = [0b00, 0b01, 0b10, 0b11];
+        let ratings = [
+            Rating::most_common().on_tie(0),
+            Rating::least_common().on_tie(1),
+            Rating::custom(|ones, zeros| (ones <= zeros) as u32),
+            Rating::custom(|_, _| 0).on_tie(1),
+            Rating::custom(|_, _| 2),
+        ];
+        assert_eq!(
+            rate(&nums, 2, &ratings),
+            [Some(0b00), Some(0b11), Some(0b11), Some(0b11), None]
+        );
+        assert_eq!(rate(&[], 3, &ratings), [None; 5]);
+        assert_eq!(rate(&[5, 5], 3, &ratings[..1]), [Some(5)]);
+        assert!(rate(&nums, 2, &[]).is_empty());
+
+        let mut rng = Xorshift::new(0x19660d17);
+        for _ in 0..500 {
+            let width = 1 + rng.next_u32() % 8;
+            let nums = (0..rng.next_u32() % 20)
+                .map(|_| rng.next_u32() % (1 << width))
+                .collect::<Vec<_>>();
+            let ratings = rate(
+                &nums,
+                width,
+                &[Rating::most_common(), Rating::least_common()],
+            );
+            assert_eq!(
+                ratings,
+                [
+                    filter_by_bit_criteria(&nums, width, most_common),
+                    filter_by_bit_criteria(&nums, width, least_common)
+                ],
+                "{:?}",
+                nums
+            );
+        }
+    }
+}
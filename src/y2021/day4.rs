@@ -0,0 +1,870 @@
+use crate::error::ParseError;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum BingoTile {
+    Unmarked(u32),
+    Marked,
+}
+
+impl BingoTile {
+    pub fn with_num(num: u32) -> Self {
+        BingoTile::Unmarked(num)
+    }
+
+    pub fn marked() -> Self {
+        BingoTile::Marked
+    }
+
+    pub fn is_marked(&self) -> bool {
+        matches!(self, BingoTile::Marked)
+    }
+
+    pub fn is_num(&self, num: u32) -> bool {
+        match &self {
+            BingoTile::Unmarked(v) => *v == num,
+            _ => false,
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        match &self {
+            BingoTile::Unmarked(v) => *v,
+            _ => panic!("Called value on Marked tile!"),
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BingoBoard {
+    tiles: Vec<BingoTile>,
+    size: usize,
+    won: bool,
+    row_marks: Vec<usize>,
+    col_marks: Vec<usize>,
+}
+
+/// Map of number to every (board, tile) position it appears at, so a draw only has to visit the
+/// boards containing it
+pub type DrawIndex = HashMap<u32, Vec<(usize, usize)>>;
+
+impl BingoBoard {
+    pub fn with_tiles(nums: &[u32]) -> Self {
+        let size = (nums.len() as f64).sqrt() as usize;
+        BingoBoard {
+            tiles: nums.iter().map(|&n| BingoTile::with_num(n)).collect(),
+            size,
+            won: false,
+            row_marks: vec![0; size],
+            col_marks: vec![0; size],
+        }
+    }
+
+    pub fn is_winner(&self) -> bool {
+        self.won
+    }
+
+    /// Mark every tile holding num, returning true if that won the board
+    pub fn mark(&mut self, num: u32) -> bool {
+        let mut won = false;
+        for pos in 0..self.tiles.len() {
+            if self.tiles[pos].is_num(num) {
+                won |= self.mark_at(pos);
+            }
+        }
+        won
+    }
+
+    /// Mark the tile at pos, returning true if that won the board. A board that has already won
+    /// still has its tiles marked, so every tile holding the winning number is out of its score
+    pub fn mark_at(&mut self, pos: usize) -> bool {
+        if self.tiles[pos].is_marked() {
+            return false;
+        }
+
+        self.tiles[pos] = BingoTile::marked();
+
+        let (row, col) = (pos / self.size, pos % self.size);
+        self.row_marks[row] += 1;
+        self.col_marks[col] += 1;
+
+        let completed = self.row_marks[row] == self.size || self.col_marks[col] == self.size;
+        let won = completed && !self.won;
+        self.won |= completed;
+        won
+    }
+
+    /// Position and number of every tile still to be marked
+    fn unmarked(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| !tile.is_marked())
+            .map(|(pos, tile)| (pos, tile.value()))
+    }
+
+    pub fn unmarked_sum(&self) -> u64 {
+        self.tiles
+            .iter()
+            .filter(|tile| !tile.is_marked())
+            .map(|tile| tile.value() as u64)
+            .sum()
+    }
+
+    /// The score if `draw` has just completed the board, as a u128 so no board can overflow it
+    pub fn score(&self, draw: u32) -> u128 {
+        draw as u128 * self.unmarked_sum() as u128
+    }
+}
+
+impl std::fmt::Debug for BingoTile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&match &self {
+            BingoTile::Marked => " X ".to_string(),
+            BingoTile::Unmarked(v) => format!("{:2} ", v),
+        })
+    }
+}
+
+impl std::fmt::Debug for BingoBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tiles.chunks(self.size).for_each(|row| {
+            row.iter().for_each(|tile| {
+                tile.fmt(f).unwrap();
+            });
+            f.write_char('\n').unwrap();
+        });
+        Ok(())
+    }
+}
+
+fn parse_row(row: &str) -> Vec<u32> {
+    row.split(' ')
+        .filter_map(|n| n.parse::<u32>().ok())
+        .collect()
+}
+
+/// Index every tile still to be marked, so boards that come in partly marked can be played
+fn index_boards(boards: &[BingoBoard]) -> DrawIndex {
+    let mut index = DrawIndex::new();
+    for (board_idx, board) in boards.iter().enumerate() {
+        for (tile_idx, num) in board.unmarked() {
+            index.entry(num).or_default().push((board_idx, tile_idx));
+        }
+    }
+    index
+}
+
+/// Build a board from its rows, given with their line numbers. The board has to be square
+fn board_from_rows(rows: &[(usize, Vec<u32>)]) -> Result<BingoBoard, ParseError> {
+    let mut tiles = Vec::new();
+    for (line, row) in rows {
+        if row.len() != rows.len() {
+            return Err(ParseError::at_line(
+                *line,
+                format!(
+                    "expected {} numbers in the row, found {}",
+                    rows.len(),
+                    row.len()
+                ),
+            ));
+        }
+        tiles.extend(row);
+    }
+    Ok(BingoBoard::with_tiles(&tiles))
+}
+
+/// The numbers to draw on the first line, then the boards separated by blank lines
+#[aoc_generator(day4)]
+pub(crate) fn bingo(input: &str) -> Result<(Vec<u32>, Vec<BingoBoard>, DrawIndex), ParseError> {
+    let mut lines = input.lines().enumerate();
+    let draws = lines
+        .next()
+        .ok_or_else(|| ParseError::at_line(1, "missing the numbers to draw"))?
+        .1
+        .split(',')
+        .filter_map(|c| c.parse::<u32>().ok())
+        .collect();
+
+    let mut boards = Vec::new();
+    let mut rows = Vec::new();
+    for (i, line) in lines {
+        if line.trim().is_empty() {
+            if !rows.is_empty() {
+                boards.push(board_from_rows(&rows)?);
+                rows.clear();
+            }
+        } else {
+            rows.push((i + 1, parse_row(line)));
+        }
+    }
+    if !rows.is_empty() {
+        boards.push(board_from_rows(&rows)?);
+    }
+
+    let index = index_boards(&boards);
+    Ok((draws, boards, index))
+}
+
+/// Mark num on every board still in play, returning the boards it completed in board order
+fn mark_all(num: u32, boards: &mut [BingoBoard]) -> Vec<usize> {
+    boards
+        .iter_mut()
+        .enumerate()
+        .filter(|(_, b)| !b.is_winner())
+        .filter_map(|(i, board)| board.mark(num).then_some(i))
+        .collect()
+}
+
+/// Mark num through the index on every board still in play. The index lists positions in board
+/// order, so the winners are too, and each board's positions are next to each other
+fn mark_indexed(num: u32, boards: &mut [BingoBoard], index: &DrawIndex) -> Vec<usize> {
+    let positions = index.get(&num).map_or(&[][..], Vec::as_slice);
+    positions
+        .chunk_by(|a, b| a.0 == b.0)
+        .filter_map(|tiles| {
+            let board = &mut boards[tiles[0].0];
+            if board.is_winner() {
+                return None;
+            }
+            let won = tiles
+                .iter()
+                .fold(false, |won, &(_, tile_idx)| won | board.mark_at(tile_idx));
+            won.then_some(tiles[0].0)
+        })
+        .collect()
+}
+
+/// Naive draw: scans every tile of every board still in play
+fn call_num(num: u32, boards: &mut [BingoBoard]) -> Vec<&BingoBoard> {
+    let winners = mark_all(num, boards);
+    let boards: &[BingoBoard] = boards;
+    winners.into_iter().map(|i| &boards[i]).collect()
+}
+
+/// Indexed draw: only visits the tiles holding num
+fn call_num_indexed<'a>(
+    num: u32,
+    boards: &'a mut [BingoBoard],
+    index: &DrawIndex,
+) -> Vec<&'a BingoBoard> {
+    let winners = mark_indexed(num, boards, index);
+    let boards: &[BingoBoard] = boards;
+    winners.into_iter().map(|i| &boards[i]).collect()
+}
+
+/// First board to win. If several win on the same draw, the first of them in the input
+fn win_bingo(
+    nums: impl Iterator<Item = u32>,
+    boards: &mut [BingoBoard],
+    mut call: impl FnMut(u32, &mut [BingoBoard]) -> Vec<&BingoBoard>,
+) -> Option<(u32, BingoBoard)> {
+    for num in nums {
+        if let Some(&winner) = call(num, boards).first() {
+            return Some((num, winner.clone()));
+        }
+    }
+
+    None
+}
+
+/// Last board to win. If several win on the final draw, the last of them in the input
+fn lose_bingo(
+    nums: impl Iterator<Item = u32>,
+    boards: &mut [BingoBoard],
+    mut call: impl FnMut(u32, &mut [BingoBoard]) -> Vec<&BingoBoard>,
+) -> Option<(u32, BingoBoard)> {
+    let mut in_play = boards.len();
+    for num in nums {
+        let winners = call(num, boards);
+        in_play -= winners.len();
+        if in_play == 0 {
+            if let Some(&loser) = winners.last() {
+                return Some((num, loser.clone()));
+            }
+        }
+    }
+
+    None
+}
+
+/// Where a board finished: its position in the input, the number that completed it and its score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Standing {
+    pub board: usize,
+    pub draw: u32,
+    pub score: u128,
+}
+
+/// A board completing a row or column during a `BingoGame`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinEvent {
+    pub standing: Standing,
+    /// Boards still in play after this one won
+    pub in_play: usize,
+}
+
+/// How a board did over a whole game, from `BingoGame::play_all`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardResult {
+    /// Index into the draws of the number that completed the board, or None if it never won
+    pub won_at: Option<usize>,
+    /// The score when the board won
+    pub score: Option<u128>,
+    /// Order the board won in, starting from 1. Boards that win on the same draw are ranked in
+    /// input order, and boards that never win are ranked after every board that did
+    pub rank: usize,
+}
+
+/// A game played one draw at a time, for when the draws aren't all known up front
+pub struct BingoGame {
+    boards: Vec<BingoBoard>,
+    index: DrawIndex,
+    in_play: usize,
+}
+
+impl BingoGame {
+    pub fn new(boards: Vec<BingoBoard>) -> Self {
+        BingoGame {
+            index: index_boards(&boards),
+            in_play: boards.iter().filter(|board| !board.is_winner()).count(),
+            boards,
+        }
+    }
+
+    /// Mark num on every board, returning the boards it completed in board order
+    pub fn call(&mut self, num: u32) -> Vec<WinEvent> {
+        let winners = mark_indexed(num, &mut self.boards, &self.index);
+        winners
+            .into_iter()
+            .map(|board| {
+                self.in_play -= 1;
+                WinEvent {
+                    standing: Standing {
+                        board,
+                        draw: num,
+                        score: self.boards[board].score(num),
+                    },
+                    in_play: self.in_play,
+                }
+            })
+            .collect()
+    }
+
+    pub fn in_play(&self) -> usize {
+        self.in_play
+    }
+
+    /// Whether every board has won
+    pub fn is_over(&self) -> bool {
+        self.in_play == 0
+    }
+
+    pub fn boards(&self) -> &[BingoBoard] {
+        &self.boards
+    }
+
+    /// Call `draws` until every board has won or they run out, returning how each board did in
+    /// board order. Never returns if the draws go on forever and some board can't win
+    pub fn play_all(&mut self, draws: impl IntoIterator<Item = u32>) -> Vec<BoardResult> {
+        let mut results = vec![
+            BoardResult {
+                won_at: None,
+                score: None,
+                rank: 0,
+            };
+            self.boards.len()
+        ];
+
+        let mut rank = 0;
+        for (index, num) in draws.into_iter().enumerate() {
+            if self.is_over() {
+                break;
+            }
+            for event in self.call(num) {
+                rank += 1;
+                results[event.standing.board] = BoardResult {
+                    won_at: Some(index),
+                    score: Some(event.standing.score),
+                    rank,
+                };
+            }
+        }
+
+        for result in results.iter_mut().filter(|result| result.won_at.is_none()) {
+            rank += 1;
+            result.rank = rank;
+        }
+        results
+    }
+}
+
+/// Every board that wins, in the order they win. Boards that win on the same draw are in input
+/// order, and boards that never win are left out
+pub fn final_standings(
+    (nums, boards, _): &(Vec<u32>, Vec<BingoBoard>, DrawIndex),
+) -> Vec<Standing> {
+    let mut game = BingoGame::new(boards.to_vec());
+    let mut standings = Vec::new();
+    for &num in nums {
+        standings.extend(game.call(num).into_iter().map(|event| event.standing));
+        if game.is_over() {
+            break;
+        }
+    }
+    standings
+}
+
+/// What can be told about a board from the draws alone, without playing the game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardReport {
+    /// Numbers on more than one unmarked tile, in ascending order. A single draw marks all of them
+    pub duplicates: Vec<u32>,
+    /// Unmarked numbers that are never drawn, in ascending order
+    pub dead_tiles: Vec<u32>,
+    /// Rows and columns holding a dead tile, which can never be completed
+    pub dead_lines: usize,
+    /// Index into the draws of the number that completes the board, or None if it never wins. A
+    /// board that has already won is never completed again
+    pub win_turn: Option<usize>,
+}
+
+impl BoardReport {
+    /// A fair board has a number on each tile and can be won with the draws
+    pub fn is_fair(&self) -> bool {
+        self.duplicates.is_empty() && self.win_turn.is_some()
+    }
+}
+
+/// Check every board against the draws, in board order. A line is completed by whichever of its
+/// numbers is drawn last, so the board wins on the earliest of those over its rows and columns
+pub fn board_reports(draws: &[u32], boards: &[BingoBoard]) -> Vec<BoardReport> {
+    let mut first_drawn = HashMap::new();
+    for (turn, &num) in draws.iter().enumerate() {
+        first_drawn.entry(num).or_insert(turn);
+    }
+
+    boards
+        .iter()
+        .map(|board| {
+            let mut counts = HashMap::<u32, usize>::new();
+            let mut turns = vec![None; board.tiles.len()];
+            for (pos, num) in board.unmarked() {
+                *counts.entry(num).or_default() += 1;
+                turns[pos] = Some(first_drawn.get(&num).copied());
+            }
+
+            let mut duplicates = Vec::new();
+            let mut dead_tiles = Vec::new();
+            for (&num, &count) in &counts {
+                if count > 1 {
+                    duplicates.push(num);
+                }
+                if !first_drawn.contains_key(&num) {
+                    dead_tiles.push(num);
+                }
+            }
+            duplicates.sort_unstable();
+            dead_tiles.sort_unstable();
+
+            // Marked tiles don't need a draw. None for the whole line if a tile is never drawn
+            let size = board.size;
+            let line_turn = |tiles: &mut dyn Iterator<Item = usize>| {
+                let mut last = None;
+                for pos in tiles {
+                    match turns[pos] {
+                        Some(None) => return None,
+                        Some(turn) => last = last.max(turn),
+                        None => {}
+                    }
+                }
+                Some(last)
+            };
+            let lines = (0..size)
+                .map(|row| line_turn(&mut (row * size..(row + 1) * size)))
+                .chain((0..size).map(|col| line_turn(&mut (col..size * size).step_by(size))))
+                .collect::<Vec<_>>();
+
+            BoardReport {
+                duplicates,
+                dead_tiles,
+                dead_lines: lines.iter().filter(|line| line.is_none()).count(),
+                win_turn: if board.is_winner() {
+                    None
+                } else {
+                    lines.into_iter().flatten().flatten().min()
+                },
+            }
+        })
+        .collect()
+}
+
+#[aoc(day4, part1)]
+pub(crate) fn part1((nums, boards, _): &(Vec<u32>, Vec<BingoBoard>, DrawIndex)) -> Option<u128> {
+    let results = BingoGame::new(boards.to_vec()).play_all(nums.iter().copied());
+    results
+        .iter()
+        .filter(|result| result.won_at.is_some())
+        .min_by_key(|result| result.rank)
+        .and_then(|result| result.score)
+}
+
+#[aoc(day4, part2)]
+pub(crate) fn part2((nums, boards, _): &(Vec<u32>, Vec<BingoBoard>, DrawIndex)) -> Option<u128> {
+    let results = BingoGame::new(boards.to_vec()).play_all(nums.iter().copied());
+    results
+        .iter()
+        .filter(|result| result.won_at.is_some())
+        .max_by_key(|result| result.rank)
+        .and_then(|result| result.score)
+}
+
+#[aoc(day4, part1, Indexed)]
+fn part1_indexed((nums, boards, index): &(Vec<u32>, Vec<BingoBoard>, DrawIndex)) -> Option<u128> {
+    let mut boards: Vec<_> = boards.to_vec();
+    let (winning_num, winning_board) =
+        win_bingo(nums.iter().copied(), &mut boards, |num, boards| {
+            call_num_indexed(num, boards, index)
+        })?;
+    Some(winning_board.score(winning_num))
+}
+
+#[aoc(day4, part2, Indexed)]
+fn part2_indexed((nums, boards, index): &(Vec<u32>, Vec<BingoBoard>, DrawIndex)) -> Option<u128> {
+    let mut boards: Vec<_> = boards.to_vec();
+    let (losing_num, losing_board) =
+        lose_bingo(nums.iter().copied(), &mut boards, |num, boards| {
+            call_num_indexed(num, boards, index)
+        })?;
+    Some(losing_board.score(losing_num))
+}
+
+#[aoc(day4, part1, Naive)]
+fn part1_naive((nums, boards, _): &(Vec<u32>, Vec<BingoBoard>, DrawIndex)) -> Option<u128> {
+    let mut boards: Vec<_> = boards.to_vec();
+    let (winning_num, winning_board) = win_bingo(nums.iter().copied(), &mut boards, call_num)?;
+    Some(winning_board.score(winning_num))
+}
+
+#[aoc(day4, part2, Naive)]
+fn part2_naive((nums, boards, _): &(Vec<u32>, Vec<BingoBoard>, DrawIndex)) -> Option<u128> {
+    let mut boards: Vec<_> = boards.to_vec();
+    let (losing_num, losing_board) = lose_bingo(nums.iter().copied(), &mut boards, call_num)?;
+    Some(losing_board.score(losing_num))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day04.txt");
+
+    #[test]
+    fn example() {
+        let input = bingo(EXAMPLE).unwrap();
+
+        assert_eq!(part1(&input), Some(4512));
+        assert_eq!(part2(&input), Some(1924));
+        assert_eq!(part1_naive(&input), Some(4512));
+        assert_eq!(part2_naive(&input), Some(1924));
+
+        let standings = final_standings(&input);
+        assert_eq!(
+            standings.iter().map(|s| s.board).collect::<Vec<_>>(),
+            [2, 0, 1]
+        );
+        assert_eq!(standings[0].score, 4512);
+        assert_eq!(standings[2].score, 1924);
+
+        // Scores past a u64
+        let max = u32::MAX;
+        let input = bingo(&format!(
+            "{0}\n\n{0} {0} {0}\n{1} {1} {1}\n{1} {1} {1}\n",
+            max,
+            max - 1
+        ))
+        .unwrap();
+        let score = max as u128 * 6 * (max - 1) as u128;
+        assert_eq!(
+            (part1(&input), part2_naive(&input)),
+            (Some(score), Some(score))
+        );
+    }
+
+    #[test]
+    fn duplicate_winning_number() {
+        // Drawing 1 completes the top row, and the 1 in the bottom row is marked on the same draw
+        let input = bingo("2,1\n\n1 2\n3 1\n").unwrap();
+        for part in [
+            part1,
+            part2,
+            part1_indexed,
+            part2_indexed,
+            part1_naive,
+            part2_naive,
+        ] {
+            assert_eq!(part(&input), Some(3));
+        }
+
+        let mut board = input.1[0].clone();
+        assert!(!board.mark(2));
+        assert!(board.mark(1));
+        assert_eq!(board.unmarked_sum(), 3);
+        // Already won, so marking another line doesn't win it again
+        assert!(!board.mark(3));
+    }
+
+    #[test]
+    fn play_all() {
+        let (nums, boards, _) = bingo(EXAMPLE).unwrap();
+        let results = BingoGame::new(boards).play_all(nums.iter().copied());
+        assert_eq!(
+            results,
+            [
+                BoardResult {
+                    won_at: Some(13),
+                    score: Some(2192),
+                    rank: 2
+                },
+                BoardResult {
+                    won_at: Some(14),
+                    score: Some(1924),
+                    rank: 3
+                },
+                BoardResult {
+                    won_at: Some(11),
+                    score: Some(4512),
+                    rank: 1
+                },
+            ]
+        );
+        assert_eq!(nums[11], 24);
+
+        // The board of 7s never wins, so it's ranked last whatever its position
+        let (nums, boards, _) = bingo("1,2,3\n\n7 7\n7 7\n\n1 2\n9 9\n\n2 8\n1 8\n").unwrap();
+        let results = BingoGame::new(boards).play_all(nums);
+        assert_eq!(
+            results.iter().map(|result| result.rank).collect::<Vec<_>>(),
+            [3, 1, 2]
+        );
+        assert_eq!(results[0].won_at, None);
+        assert_eq!(results[0].score, None);
+        assert_eq!(results[1].won_at, results[2].won_at);
+    }
+
+    #[test]
+    fn shared_draw() {
+        // The first two boards both win on 2, the third on 3 and the last never does
+        let input = bingo("1,2,3\n\n1 2\n9 9\n\n2 8\n1 8\n\n3 4\n1 5\n\n7 7\n7 7\n").unwrap();
+        let standings = final_standings(&input);
+        assert_eq!(
+            standings,
+            [
+                Standing {
+                    board: 0,
+                    draw: 2,
+                    score: 36
+                },
+                Standing {
+                    board: 1,
+                    draw: 2,
+                    score: 32
+                },
+                Standing {
+                    board: 2,
+                    draw: 3,
+                    score: 27
+                },
+            ]
+        );
+
+        let (nums, boards, index) = &input;
+        let mut naive = boards.clone();
+        let mut indexed = boards.clone();
+        assert!(call_num(nums[0], &mut naive).is_empty());
+        assert!(call_num_indexed(nums[0], &mut indexed, index).is_empty());
+        let winners = call_num(nums[1], &mut naive);
+        assert_eq!(winners.len(), 2);
+        assert_eq!(winners[0].unmarked_sum(), 18);
+        assert_eq!(winners[1].unmarked_sum(), 16);
+        let winners = call_num_indexed(nums[1], &mut indexed, index);
+        assert_eq!(winners.len(), 2);
+        assert_eq!(winners[0].unmarked_sum(), 18);
+
+        // With both tied boards among the last to win, part 2 takes the later one
+        let input = bingo("1,2\n\n1 2\n9 9\n\n2 8\n1 8\n").unwrap();
+        assert_eq!(part1(&input), Some(36));
+        assert_eq!(part2(&input), Some(32));
+        assert_eq!(part2_naive(&input), Some(32));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(bingo("").err().map(|err| err.line()), Some(1));
+        assert_eq!(
+            bingo("1,2\n\n1 2\n3\n").err().map(|err| err.line()),
+            Some(4)
+        );
+        assert!(bingo("1,2\n\n1 2\n3 4\n\n\n5 6\n7 8").is_ok());
+
+        // Nothing wins, so there's no answer to either part
+        let input = bingo("5\n\n1 2\n3 4\n").unwrap();
+        assert_eq!(part1(&input), None);
+        assert_eq!(part2(&input), None);
+        assert_eq!(part1_indexed(&input), None);
+        assert_eq!(part2_naive(&input), None);
+    }
+
+    #[test]
+    fn reports() {
+        let (draws, boards, _) = bingo(EXAMPLE).unwrap();
+        let reports = board_reports(&draws, &boards);
+        assert!(reports.iter().all(BoardReport::is_fair));
+        assert!(reports.iter().all(|report| report.dead_lines == 0));
+        let results = BingoGame::new(boards).play_all(draws.iter().copied());
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| report.win_turn)
+                .collect::<Vec<_>>(),
+            results
+                .iter()
+                .map(|result| result.won_at)
+                .collect::<Vec<_>>()
+        );
+
+        let first_report = |input| {
+            let (draws, boards, _) = bingo(input).unwrap();
+            board_reports(&draws, &boards).remove(0)
+        };
+
+        // One draw marks both 1s, which finishes the top row straight away
+        let report = first_report("1,2,3,4\n\n1 1\n2 3\n");
+        assert_eq!(report.duplicates, [1]);
+        assert!(report.dead_tiles.is_empty());
+        assert_eq!(report.win_turn, Some(0));
+        assert!(!report.is_fair());
+
+        // Only the top row is ever drawn, so it's the only line that can be completed
+        let report = first_report("3,1,2\n\n1 2 3\n4 5 6\n7 8 9\n");
+        assert_eq!(report.dead_tiles, [4, 5, 6, 7, 8, 9]);
+        assert_eq!(report.dead_lines, 5);
+        assert_eq!(report.win_turn, Some(2));
+        assert!(report.is_fair());
+
+        let report = first_report("5\n\n1 2\n3 4\n");
+        assert_eq!(report.dead_tiles, [1, 2, 3, 4]);
+        assert_eq!((report.dead_lines, report.win_turn), (4, None));
+        assert!(!report.is_fair());
+
+        // Boards that have already won, or are partly marked, are judged from where they are
+        let mut boards = bingo("0\n\n1 2\n3 4\n\n1 2\n3 4\n").unwrap().1;
+        boards[0].mark(1);
+        boards[0].mark(2);
+        boards[1].mark(1);
+        let reports = board_reports(&[3, 2], &boards);
+        assert_eq!(reports[0].win_turn, None);
+        assert_eq!(reports[1].win_turn, Some(0));
+        assert_eq!(reports[1].dead_tiles, [4]);
+        assert_eq!(reports[1].dead_lines, 2);
+        // and play on from where they are, leaving out the board that has already won
+        let mut game = BingoGame::new(boards);
+        assert_eq!(game.in_play(), 1);
+        let results = game.play_all([3, 2]);
+        assert_eq!((results[0].won_at, results[1].won_at), (None, Some(0)));
+        assert_eq!(results[1].score, Some(3 * (2 + 4)));
+
+        // Small numbers so boards repeat them and some are never drawn
+        let mut rng = Xorshift::new(0x6b8b4567);
+        for _ in 0..300 {
+            let size = 1 + rng.next_u32() as usize % 4;
+            let boards = (0..1 + rng.next_u32() % 4)
+                .map(|_| {
+                    let tiles = (0..size * size)
+                        .map(|_| rng.next_u32() % 20)
+                        .collect::<Vec<_>>();
+                    BingoBoard::with_tiles(&tiles)
+                })
+                .collect::<Vec<_>>();
+            let draws = (0..rng.next_u32() % 30)
+                .map(|_| rng.next_u32() % 24)
+                .collect::<Vec<_>>();
+
+            let results = BingoGame::new(boards.clone()).play_all(draws.iter().copied());
+            for (report, result) in board_reports(&draws, &boards).iter().zip(&results) {
+                assert_eq!(report.win_turn, result.won_at, "{:?} {:?}", draws, boards);
+                assert_eq!(report.dead_lines == 2 * size, report.win_turn.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn streamed_draws() {
+        // The stream never ends, and repeats numbers that were already called
+        let draws = Xorshift::default().map(|n| n % 27);
+
+        let (_, boards, _) = bingo(EXAMPLE).unwrap();
+        let mut game = BingoGame::new(boards.clone());
+        let mut events = Vec::new();
+        let mut called = Vec::new();
+        for num in draws.clone() {
+            called.push(num);
+            events.extend(game.call(num));
+            if game.is_over() {
+                break;
+            }
+        }
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events.iter().map(|event| event.in_play).collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+        assert!(game.boards().iter().all(BingoBoard::is_winner));
+        assert!(game.call(called[0]).is_empty());
+
+        // The same draws through the rest of the API agree with the game
+        let input = (called, boards.clone(), index_boards(&boards));
+        let standings = final_standings(&input);
+        assert_eq!(
+            standings,
+            events
+                .iter()
+                .map(|event| event.standing)
+                .collect::<Vec<_>>()
+        );
+
+        let (first, board) = win_bingo(draws.clone(), &mut boards.clone(), call_num).unwrap();
+        assert_eq!(board.score(first), standings[0].score);
+        let (last, board) = lose_bingo(draws, &mut boards.clone(), call_num).unwrap();
+        assert_eq!(board.score(last), standings[2].score);
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day04.txt");
+
+    /// The first board repeats 1, so a single draw finishes its top row
+    const DUPLICATES: &str = "1,2,3,4\n\n1 1\n2 3\n\n4 2\n2 5\n";
+
+    #[test]
+    fn part1_agrees() {
+        for input in [EXAMPLE, DUPLICATES] {
+            assert_implementations_agree!(bingo(input).unwrap(), part1, part1_indexed, part1_naive);
+        }
+    }
+
+    #[test]
+    fn part2_agrees() {
+        for input in [EXAMPLE, DUPLICATES] {
+            assert_implementations_agree!(bingo(input).unwrap(), part2, part2_indexed, part2_naive);
+        }
+    }
+}
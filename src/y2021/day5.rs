@@ -0,0 +1,1104 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+type Coord = i32;
+
+const MAX_SIZE: Coord = 9;
+
+/// Largest bounding box (in cells) that the solutions rasterize into a dense grid rather than a
+/// HashMap
+pub const DENSE_MAX_CELLS: usize = 2048 * 2048;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Hash)]
+pub struct Point {
+    pub x: Coord,
+    pub y: Coord,
+}
+
+pub struct Line {
+    start: Point,
+    end: Point,
+}
+
+/// How many lines cover each point. An indexed map also remembers which lines they were, which
+/// is only kept for maps made with `ActivityMap::indexed`
+pub struct ActivityMap {
+    vents: HashMap<Point, usize>,
+    contributors: Option<HashMap<Point, Vec<usize>>>,
+}
+
+fn inclusive_range(from: Coord, to: Coord) -> Box<dyn Iterator<Item = Coord>> {
+    if to > from {
+        Box::new(from..=to)
+    } else {
+        Box::new((to..=from).rev())
+    }
+}
+
+impl Line {
+    pub fn points(&self) -> Vec<Point> {
+        let &Point { x: x0, y: y0 } = self.start();
+        let &Point { x: x1, y: y1 } = self.end();
+        if x0 == x1 {
+            inclusive_range(y0, y1)
+                .map(|y| Point { x: x0, y })
+                .collect()
+        } else if y0 == y1 {
+            inclusive_range(x0, x1)
+                .map(|x| Point { x, y: y0 })
+                .collect()
+        } else {
+            inclusive_range(x0, x1)
+                .zip(inclusive_range(y0, y1))
+                .map(|(x, y)| Point { x, y })
+                .collect()
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.start.x != self.end.x && self.start.y != self.end.y
+    }
+
+    pub fn start(&self) -> &Point {
+        &self.start
+    }
+
+    pub fn end(&self) -> &Point {
+        &self.end
+    }
+}
+
+impl ActivityMap {
+    pub fn new() -> Self {
+        ActivityMap {
+            vents: HashMap::new(),
+            contributors: None,
+        }
+    }
+
+    /// A map that records which lines cover each point, for `contributors`
+    pub fn indexed() -> Self {
+        ActivityMap {
+            vents: HashMap::new(),
+            contributors: Some(HashMap::new()),
+        }
+    }
+
+    pub fn add_line(&mut self, line: &Line) {
+        line.points().iter().for_each(|p| {
+            *self.vents.entry(p.clone()).or_insert(0) += 1;
+        });
+    }
+
+    /// Add the line at `index` in the input, recording the index against each of its points if
+    /// the map is indexed
+    pub fn add_line_indexed(&mut self, index: usize, line: &Line) {
+        self.add_line(line);
+        if let Some(contributors) = &mut self.contributors {
+            for p in line.points() {
+                contributors.entry(p).or_default().push(index);
+            }
+        }
+    }
+
+    pub fn vents(&self) -> &HashMap<Point, usize> {
+        &self.vents
+    }
+
+    /// Points covered by at least `n` lines, with the number of lines covering them
+    pub fn cells_with_at_least(&self, n: usize) -> impl Iterator<Item = (&Point, usize)> {
+        self.vents
+            .iter()
+            .map(|(point, &overlap)| (point, overlap))
+            .filter(move |&(_, overlap)| overlap >= n)
+    }
+
+    /// Indices of the lines covering `point` in the order they were added, or None if the map
+    /// isn't indexed
+    pub fn contributors(&self, point: &Point) -> Option<&[usize]> {
+        let contributors = self.contributors.as_ref()?;
+        Some(contributors.get(point).map_or(&[], Vec::as_slice))
+    }
+
+    /// Add up two maps. The result is only indexed if both of them are
+    pub fn merge(mut self, other: ActivityMap) -> Self {
+        for (point, overlap) in other.vents.into_iter() {
+            *self.vents.entry(point).or_insert(0) += overlap;
+        }
+        self.contributors = match (self.contributors, other.contributors) {
+            (Some(mut contributors), Some(other)) => {
+                for (point, indices) in other.into_iter() {
+                    contributors.entry(point).or_default().extend(indices);
+                }
+                Some(contributors)
+            }
+            _ => None,
+        };
+        self
+    }
+}
+
+/// Exports of the density grid, for looking at the hotspots in other tools
+#[cfg(feature = "viz")]
+impl ActivityMap {
+    /// The corner of the smallest box holding every covered point, and the number of lines
+    /// covering each point in it, row by row
+    fn density_grid(&self) -> (Point, Vec<Vec<usize>>) {
+        let points = self.vents.keys();
+        let (min_x, max_x) = (
+            points.clone().map(|p| p.x).min(),
+            points.clone().map(|p| p.x).max(),
+        );
+        let (min_y, max_y) = (points.clone().map(|p| p.y).min(), points.map(|p| p.y).max());
+        let (min, max) = match (min_x, min_y, max_x, max_y) {
+            (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+                (Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y })
+            }
+            _ => return (Point { x: 0, y: 0 }, Vec::new()),
+        };
+
+        let grid = (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| self.vents.get(&Point { x, y }).copied().unwrap_or(0))
+                    .collect()
+            })
+            .collect();
+        (min, grid)
+    }
+
+    /// Plain (P2) PGM with one pixel per point, as bright as the number of lines covering it. A
+    /// comment after the magic number gives the (x, y) of the top left pixel
+    pub fn export_pgm(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let (min, grid) = self.density_grid();
+        let width = grid.first().map_or(0, Vec::len);
+        let max_overlap = grid.iter().flatten().copied().max().unwrap_or(0);
+
+        let mut pgm = format!(
+            "P2\n# origin {},{}\n{} {}\n{}\n",
+            min.x,
+            min.y,
+            width,
+            grid.len(),
+            max_overlap.max(1)
+        );
+        for row in grid.iter() {
+            let row = row.iter().map(usize::to_string).collect::<Vec<_>>();
+            pgm.push_str(&row.join(" "));
+            pgm.push('\n');
+        }
+        std::fs::write(path, pgm)
+    }
+
+    /// The number of lines covering each point as a table, with a header row of x coordinates and
+    /// each row starting with its y coordinate
+    pub fn export_csv(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let (min, grid) = self.density_grid();
+        let width = grid.first().map_or(0, Vec::len) as Coord;
+
+        let mut csv = String::from("y");
+        for x in min.x..min.x + width {
+            csv.push_str(&format!(",{}", x));
+        }
+        csv.push('\n');
+        for (y, row) in (min.y..).zip(grid.iter()) {
+            csv.push_str(&y.to_string());
+            for overlap in row {
+                csv.push_str(&format!(",{}", overlap));
+            }
+            csv.push('\n');
+        }
+        std::fs::write(path, csv)
+    }
+}
+
+impl Default for ActivityMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A point in space. Points written with two coordinates are on the z = 0 plane
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+pub struct Point3 {
+    pub x: Coord,
+    pub y: Coord,
+    pub z: Coord,
+}
+
+/// A line through space, running along one axis or diagonally across two or all three. The line
+/// moves the same distance along every axis it moves along at all, so it passes through a whole
+/// number of points
+pub struct Line3 {
+    start: Point3,
+    end: Point3,
+}
+
+impl Line3 {
+    pub fn points(&self) -> Vec<Point3> {
+        let (start, end) = (self.start, self.end);
+        let step = |from: Coord, to: Coord| (to - from).signum();
+        let (dx, dy, dz) = (
+            step(start.x, end.x),
+            step(start.y, end.y),
+            step(start.z, end.z),
+        );
+        let len = (end.x - start.x)
+            .abs()
+            .max((end.y - start.y).abs())
+            .max((end.z - start.z).abs());
+
+        (0..=len)
+            .map(|i| Point3 {
+                x: start.x + i * dx,
+                y: start.y + i * dy,
+                z: start.z + i * dz,
+            })
+            .collect()
+    }
+
+    /// Whether the line moves along more than one axis
+    pub fn is_diagonal(&self) -> bool {
+        let moves = [
+            self.start.x != self.end.x,
+            self.start.y != self.end.y,
+            self.start.z != self.end.z,
+        ];
+        moves.into_iter().filter(|&moves| moves).count() > 1
+    }
+}
+
+/// Number of points where at least two lines overlap, skipping diagonal lines unless `diagonals`
+pub fn count_overlaps_3d(lines: &[Line3], diagonals: bool) -> usize {
+    let mut vents: HashMap<Point3, usize> = HashMap::new();
+    for line in lines.iter().filter(|line| diagonals || !line.is_diagonal()) {
+        for point in line.points() {
+            *vents.entry(point).or_insert(0) += 1;
+        }
+    }
+    vents.values().filter(|&v| *v > 1).count()
+}
+
+/// Returns the (min, max) corners of the box containing every line
+fn bounds(lines: &[&Line]) -> Option<(Point, Point)> {
+    let points = lines.iter().flat_map(|line| [line.start(), line.end()]);
+    let min_x = points.clone().map(|p| p.x).min()?;
+    let min_y = points.clone().map(|p| p.y).min()?;
+    let max_x = points.clone().map(|p| p.x).max()?;
+    let max_y = points.map(|p| p.y).max()?;
+
+    Some((Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y }))
+}
+
+/// A flat grid covering the box from `min` to `max`, or None if it would have more than
+/// `max_cells` cells
+struct DenseGrid {
+    min: Point,
+    width: usize,
+    cells: usize,
+}
+
+impl DenseGrid {
+    fn fits((min, max): (Point, Point), max_cells: usize) -> Option<Self> {
+        let width = (max.x as i64 - min.x as i64 + 1) as usize;
+        let height = (max.y as i64 - min.y as i64 + 1) as usize;
+        let cells = width
+            .checked_mul(height)
+            .filter(|&cells| cells <= max_cells)?;
+        Some(DenseGrid { min, width, cells })
+    }
+
+    fn add_line(&self, grid: &mut [u16], line: &Line) {
+        for p in line.points().iter() {
+            let i = (p.y - self.min.y) as usize * self.width + (p.x - self.min.x) as usize;
+            grid[i] = grid[i].saturating_add(1);
+        }
+    }
+}
+
+/// Number of points covered by at least two lines, skipping diagonal lines unless `diagonals`.
+/// When the box around the lines has no more than `dense_max_cells` cells they're counted on a
+/// flat grid, which is much faster than an `ActivityMap` but takes memory for every cell in the
+/// box whether it's covered or not
+pub fn count_overlaps(lines: &[Line], diagonals: bool, dense_max_cells: usize) -> usize {
+    let lines = lines
+        .iter()
+        .filter(|line| diagonals || !line.is_diagonal())
+        .collect::<Vec<_>>();
+    let bounds = match bounds(&lines) {
+        Some(bounds) => bounds,
+        None => return 0,
+    };
+
+    match DenseGrid::fits(bounds, dense_max_cells) {
+        Some(dense) => {
+            let mut grid = vec![0u16; dense.cells];
+            lines
+                .iter()
+                .for_each(|line| dense.add_line(&mut grid, line));
+            grid.iter().filter(|&v| *v > 1).count()
+        }
+        None => {
+            let mut activity = ActivityMap::new();
+            lines.iter().for_each(|line| activity.add_line(line));
+            activity.cells_with_at_least(2).count()
+        }
+    }
+}
+
+/// Rasterize the lines in parallel, each rayon job filling its own map and merging them at the end.
+/// When the box around the lines has no more than `dense_max_cells` cells the maps are flat grids
+/// instead of HashMaps
+#[cfg(feature = "parallel")]
+pub fn count_overlaps_parallel(lines: &[&Line], dense_max_cells: usize) -> usize {
+    let bounds = match bounds(lines) {
+        Some(bounds) => bounds,
+        None => return 0,
+    };
+
+    if let Some(dense) = DenseGrid::fits(bounds, dense_max_cells) {
+        lines
+            .par_iter()
+            .fold(
+                || vec![0u16; dense.cells],
+                |mut grid, line| {
+                    dense.add_line(&mut grid, line);
+                    grid
+                },
+            )
+            .reduce(
+                || vec![0u16; dense.cells],
+                |mut grid, other| {
+                    grid.iter_mut()
+                        .zip(other.iter())
+                        .for_each(|(a, b)| *a = a.saturating_add(*b));
+                    grid
+                },
+            )
+            .iter()
+            .filter(|&v| *v > 1)
+            .count()
+    } else {
+        lines
+            .par_iter()
+            .fold(ActivityMap::new, |mut activity, line| {
+                activity.add_line(line);
+                activity
+            })
+            .reduce(ActivityMap::new, ActivityMap::merge)
+            .cells_with_at_least(2)
+            .count()
+    }
+}
+
+/// The four directions a line can run in
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Direction {
+    Horizontal,
+    Vertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Horizontal,
+        Direction::Vertical,
+        Direction::Diagonal,
+        Direction::AntiDiagonal,
+    ];
+
+    /// (a, b) such that every point on a line in this direction has the same a * x + b * y
+    fn coefficients(self) -> (i64, i64) {
+        match self {
+            Direction::Horizontal => (0, 1),
+            Direction::Vertical => (1, 0),
+            Direction::Diagonal => (-1, 1),
+            Direction::AntiDiagonal => (1, 1),
+        }
+    }
+
+    /// (c, t) for the line in this direction through (x, y), where c is a * x + b * y and t is the
+    /// position along the line
+    fn key(self, x: i64, y: i64) -> (i64, i64) {
+        let (a, b) = self.coefficients();
+        let t = if self == Direction::Vertical { y } else { x };
+        (a * x + b * y, t)
+    }
+}
+
+/// A line as the run of positions `t` along a * x + b * y = c, so two segments on the same line
+/// overlap where their runs do
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    direction: Direction,
+    c: i64,
+    t: (i64, i64),
+}
+
+enum Crossing {
+    Point(i64, i64),
+    Overlap(Segment),
+}
+
+impl Segment {
+    /// The segment along a line, or None if the line is neither straight nor at 45°
+    fn from_line(line: &Line) -> Option<Self> {
+        let (x0, y0) = (line.start.x as i64, line.start.y as i64);
+        let (x1, y1) = (line.end.x as i64, line.end.y as i64);
+        let direction = if y0 == y1 {
+            Direction::Horizontal
+        } else if x0 == x1 {
+            Direction::Vertical
+        } else if y1 - y0 == x1 - x0 {
+            Direction::Diagonal
+        } else if y1 - y0 == x0 - x1 {
+            Direction::AntiDiagonal
+        } else {
+            return None;
+        };
+
+        let (c, t0) = direction.key(x0, y0);
+        let (_, t1) = direction.key(x1, y1);
+        Some(Segment {
+            direction,
+            c,
+            t: (t0.min(t1), t0.max(t1)),
+        })
+    }
+
+    fn len(&self) -> usize {
+        (self.t.1 - self.t.0 + 1) as usize
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let (c, t) = self.direction.key(x, y);
+        c == self.c && self.t.0 <= t && t <= self.t.1
+    }
+
+    fn crossing(&self, other: &Segment) -> Option<Crossing> {
+        if self.direction == other.direction {
+            let t = (self.t.0.max(other.t.0), self.t.1.min(other.t.1));
+            return (self.c == other.c && t.0 <= t.1)
+                .then_some(Crossing::Overlap(Segment { t, ..*self }));
+        }
+
+        // Solve the two line equations, the lines only cross at a cell if the solution is whole
+        let (a1, b1) = self.direction.coefficients();
+        let (a2, b2) = other.direction.coefficients();
+        let det = a1 * b2 - a2 * b1;
+        let x = self.c * b2 - other.c * b1;
+        let y = a1 * other.c - a2 * self.c;
+        if x % det != 0 || y % det != 0 {
+            return None;
+        }
+
+        let (x, y) = (x / det, y / det);
+        (self.contains(x, y) && other.contains(x, y)).then_some(Crossing::Point(x, y))
+    }
+}
+
+/// Count the cells covered by at least two lines without rasterizing them. Every pair of segments
+/// either crosses at one cell or overlaps along a run. Runs on the same line are merged, then the
+/// cells where runs on different lines cross, or where lines cross away from any run, fix up the
+/// total
+fn count_overlaps_analytic(lines: &[&Line]) -> usize {
+    // Lines only parse if they're straight or at 45°, so none are left out
+    let segments = lines
+        .iter()
+        .filter_map(|line| Segment::from_line(line))
+        .collect::<Vec<_>>();
+
+    let mut points = HashSet::new();
+    let mut overlaps = HashMap::<(Direction, i64), Vec<(i64, i64)>>::new();
+    for (i, a) in segments.iter().enumerate() {
+        for b in segments[i + 1..].iter() {
+            match a.crossing(b) {
+                Some(Crossing::Point(x, y)) => {
+                    points.insert((x, y));
+                }
+                Some(Crossing::Overlap(run)) => {
+                    overlaps
+                        .entry((run.direction, run.c))
+                        .or_default()
+                        .push(run.t);
+                }
+                None => {}
+            }
+        }
+    }
+
+    // Merge the runs on each line into disjoint ones, sorted by t
+    for runs in overlaps.values_mut() {
+        runs.sort_unstable();
+        let mut merged: Vec<(i64, i64)> = Vec::with_capacity(runs.len());
+        for &(t0, t1) in runs.iter() {
+            match merged.last_mut() {
+                Some(last) if t0 <= last.1 + 1 => last.1 = last.1.max(t1),
+                _ => merged.push((t0, t1)),
+            }
+        }
+        *runs = merged;
+    }
+
+    let runs = overlaps
+        .iter()
+        .flat_map(|(&(direction, c), runs)| runs.iter().map(move |&t| Segment { direction, c, t }))
+        .collect::<Vec<_>>();
+    for (i, a) in runs.iter().enumerate() {
+        for b in runs[i + 1..].iter() {
+            if let Some(Crossing::Point(x, y)) = a.crossing(b) {
+                points.insert((x, y));
+            }
+        }
+    }
+
+    let runs_containing = |x: i64, y: i64| {
+        Direction::ALL
+            .iter()
+            .filter(|&&direction| {
+                let (c, t) = direction.key(x, y);
+                overlaps.get(&(direction, c)).is_some_and(|runs| {
+                    let i = runs.partition_point(|run| run.1 < t);
+                    runs.get(i).is_some_and(|run| run.0 <= t)
+                })
+            })
+            .count()
+    };
+
+    let mut total = runs.iter().map(Segment::len).sum::<usize>();
+    for &(x, y) in points.iter() {
+        match runs_containing(x, y) {
+            0 => total += 1,
+            n => total -= n - 1,
+        }
+    }
+    total
+}
+
+#[derive(Debug)]
+pub struct ParseLineError;
+impl std::str::FromStr for Point {
+    type Err = ParseLineError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let points = input.trim().split(',').collect::<Vec<_>>();
+        match points.len() {
+            2 => {
+                let x = points[0].parse().map_err(|_| ParseLineError)?;
+                let y = points[1].parse().map_err(|_| ParseLineError)?;
+                Ok(Point { x, y })
+            }
+            _ => Err(ParseLineError),
+        }
+    }
+}
+
+impl std::str::FromStr for Line {
+    type Err = ParseLineError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let points = input.trim().split("->").collect::<Vec<_>>();
+
+        match points.len() {
+            2 => {
+                let start: Point = points[0].parse().map_err(|_| ParseLineError)?;
+                let end: Point = points[1].parse().map_err(|_| ParseLineError)?;
+
+                // A line that moves along both axes has to move the same distance on each
+                let dx = end.x as i64 - start.x as i64;
+                let dy = end.y as i64 - start.y as i64;
+                if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+                    return Err(ParseLineError);
+                }
+                Ok(Line { start, end })
+            }
+            _ => Err(ParseLineError),
+        }
+    }
+}
+
+impl std::str::FromStr for Point3 {
+    type Err = ParseLineError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let coords = input
+            .trim()
+            .split(',')
+            .map(|coord| coord.trim().parse().or(Err(ParseLineError)))
+            .collect::<Result<Vec<Coord>, _>>()?;
+        match coords[..] {
+            [x, y] => Ok(Point3 { x, y, z: 0 }),
+            [x, y, z] => Ok(Point3 { x, y, z }),
+            _ => Err(ParseLineError),
+        }
+    }
+}
+
+impl std::str::FromStr for Line3 {
+    type Err = ParseLineError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (start, end) = input.split_once("->").ok_or(ParseLineError)?;
+        let (start, end): (Point3, Point3) = (start.parse()?, end.parse()?);
+
+        // Every axis the line moves along has to move the same distance
+        let moves = [end.x - start.x, end.y - start.y, end.z - start.z]
+            .map(Coord::abs)
+            .into_iter()
+            .filter(|&d| d != 0)
+            .collect::<Vec<_>>();
+        if moves.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err(ParseLineError);
+        }
+
+        Ok(Line3 { start, end })
+    }
+}
+
+/// Lines with either two or three coordinates per point, the same as `lines` for 2D input
+pub fn lines_3d(input: &str) -> Vec<Line3> {
+    input.lines().filter_map(|line| line.parse().ok()).collect()
+}
+
+impl std::fmt::Debug for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "({}, {}) -> ({}, {})",
+            self.start().x,
+            self.start.y,
+            self.end().x,
+            self.end().y
+        )
+    }
+}
+
+impl std::fmt::Debug for ActivityMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..=MAX_SIZE {
+            for col in 0..=MAX_SIZE {
+                match self.vents.get(&Point { x: col, y: row }) {
+                    Some(overlap) => f.write_str(&format!(" {:2}", overlap))?,
+                    None => f.write_str("  .")?,
+                }
+            }
+            f.write_str("\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "viz")]
+fn render(activity: &ActivityMap, (min, max): (Point, Point)) -> crate::viz::Canvas {
+    use crate::viz::{gray, Canvas};
+
+    let width = (max.x - min.x + 1) as usize;
+    let height = (max.y - min.y + 1) as usize;
+    let max_overlap = activity.vents().values().copied().max().unwrap_or(0);
+
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let point = Point {
+                x: min.x + x as Coord,
+                y: min.y + y as Coord,
+            };
+            let overlap = activity.vents().get(&point).copied().unwrap_or(0);
+            let c = match overlap {
+                0 => '.',
+                1..=9 => std::char::from_digit(overlap as u32, 10).unwrap(),
+                _ => '#',
+            };
+            canvas.set(x, y, c, gray(overlap, max_overlap));
+        }
+    }
+    canvas
+}
+
+/// Two frames: the vent map from the horizontal and vertical lines, then with the diagonals added
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    let lines = lines(input);
+    let bounds = match bounds(&lines.iter().collect::<Vec<_>>()) {
+        Some(bounds) => bounds,
+        None => return Vec::new(),
+    };
+
+    let mut activity = ActivityMap::new();
+    lines
+        .iter()
+        .filter(|line| !line.is_diagonal())
+        .for_each(|line| activity.add_line(line));
+    let straight = render(&activity, bounds.clone());
+
+    lines
+        .iter()
+        .filter(|line| line.is_diagonal())
+        .for_each(|line| activity.add_line(line));
+    vec![straight, render(&activity, bounds)]
+}
+
+#[aoc_generator(day5)]
+pub(crate) fn lines(input: &str) -> Vec<Line> {
+    input.lines().filter_map(|line| line.parse().ok()).collect()
+}
+
+#[aoc(day5, part1)]
+pub(crate) fn part1(lines: &[Line]) -> usize {
+    count_overlaps(lines, false, DENSE_MAX_CELLS)
+}
+
+#[aoc(day5, part2)]
+pub(crate) fn part2(lines: &[Line]) -> usize {
+    count_overlaps(lines, true, DENSE_MAX_CELLS)
+}
+
+#[cfg(feature = "parallel")]
+#[aoc(day5, part1, Rayon)]
+fn part1_rayon(lines: &[Line]) -> usize {
+    let lines = lines
+        .iter()
+        .filter(|line| !line.is_diagonal())
+        .collect::<Vec<_>>();
+    count_overlaps_parallel(&lines, DENSE_MAX_CELLS)
+}
+
+#[cfg(feature = "parallel")]
+#[aoc(day5, part2, Rayon)]
+fn part2_rayon(lines: &[Line]) -> usize {
+    count_overlaps_parallel(&lines.iter().collect::<Vec<_>>(), DENSE_MAX_CELLS)
+}
+
+#[aoc(day5, part1, Analytic)]
+fn part1_analytic(lines: &[Line]) -> usize {
+    let lines = lines
+        .iter()
+        .filter(|line| !line.is_diagonal())
+        .collect::<Vec<_>>();
+    count_overlaps_analytic(&lines)
+}
+
+#[aoc(day5, part2, Analytic)]
+fn part2_analytic(lines: &[Line]) -> usize {
+    count_overlaps_analytic(&lines.iter().collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day05.txt");
+
+    #[test]
+    fn example() {
+        let input = lines(EXAMPLE);
+
+        assert_eq!(part1(&input), 5);
+        assert_eq!(part2(&input), 12);
+        #[cfg(feature = "parallel")]
+        {
+            assert_eq!(part1_rayon(&input), 5);
+            assert_eq!(part2_rayon(&input), 12);
+        }
+        assert_eq!(part1_analytic(&input), 5);
+        assert_eq!(part2_analytic(&input), 12);
+    }
+
+    #[test]
+    fn three_dimensions() {
+        // 2D input is the z = 0 plane
+        let lines = lines_3d(EXAMPLE);
+        assert_eq!(lines.len(), 10);
+        assert_eq!(count_overlaps_3d(&lines, false), 5);
+        assert_eq!(count_overlaps_3d(&lines, true), 12);
+
+        // The same picture lifted onto z = 2 is the same, and doesn't touch the one on z = 0
+        let lifted = EXAMPLE
+            .lines()
+            .map(|line| line.replace(" ->", ",2 ->") + ",2")
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut lines = lines_3d(&lifted);
+        assert_eq!(lines[0].points()[0], Point3 { x: 0, y: 9, z: 2 });
+        assert_eq!(count_overlaps_3d(&lines, true), 12);
+        lines.extend(lines_3d(EXAMPLE));
+        assert_eq!(count_overlaps_3d(&lines, true), 24);
+
+        let lines = lines_3d(
+            r"0,0,0 -> 0,0,4
+              0,0,2 -> 4,0,2
+              0,0,0 -> 3,3,3
+              3,3,3 -> 3,3,0
+              4,0,4 -> 0,0,0",
+        );
+        assert_eq!(count_overlaps_3d(&lines, false), 1);
+        // Plus (0, 0, 0), (2, 0, 2) and (3, 3, 3)
+        assert_eq!(count_overlaps_3d(&lines, true), 4);
+
+        // Mixed dimensions are fine, but not lines that miss the whole points between their ends
+        assert_eq!(
+            lines_3d("1,1 -> 1,1,3\n0,0,0 -> 1,2,0\n0,0 -> 1,2,3,4").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn analytic() {
+        let mut rng = Xorshift::default();
+
+        for _ in 0..200 {
+            let num_lines = 1 + rng.next_u32() % 12;
+            let input = (0..num_lines)
+                .map(|_| {
+                    let (x, y) = ((rng.next_u32() % 16) as i32, (rng.next_u32() % 16) as i32);
+                    let len = (rng.next_u32() % 10) as i32;
+                    let (dx, dy) = [(1, 0), (0, 1), (1, 1), (1, -1), (-1, 0), (-1, -1)]
+                        [rng.next_u32() as usize % 6];
+                    format!("{},{} -> {},{}", x, y, x + dx * len, y + dy * len)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let input = lines(&input);
+
+            assert_eq!(part1_analytic(&input), part1(&input), "{:?}", input);
+            assert_eq!(part2_analytic(&input), part2(&input), "{:?}", input);
+        }
+
+        // Far too big to rasterize. The two vertical lines share a run, every other line crosses
+        // at the origin, and the line at y = 2 crosses the others at (0, 2), (2, 2) and (-2, 2)
+        let input = lines(
+            r"0,0 -> 0,1000000000
+              0,500000000 -> 0,1500000000
+              -1000000000,0 -> 1000000000,0
+              -1000000000,2 -> 1000000000,2
+              -3,3 -> 3,-3
+              1000000,1000000 -> -1000000,-1000000",
+        );
+        assert_eq!(part2_analytic(&input), 500000001 + 1 + 3);
+
+        // Only lines along an axis or at 45° are segments, and only those parse
+        let skewed = Line {
+            start: Point { x: 0, y: 0 },
+            end: Point { x: 2, y: 1 },
+        };
+        assert!(Segment::from_line(&skewed).is_none());
+        assert!("0,0 -> 2,1".parse::<Line>().is_err());
+        let anti = "2,0 -> 0,2".parse::<Line>().unwrap();
+        assert_eq!(
+            Segment::from_line(&anti).map(|segment| segment.direction),
+            Some(Direction::AntiDiagonal)
+        );
+        assert_eq!(lines("0,0 -> 2,1\n0,0 -> 2,2").len(), 1);
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn render_example() {
+        let frames = render_frames(EXAMPLE);
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[1].to_ascii(),
+            "1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111....
+"
+        );
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn export_heatmap() {
+        let dir = std::env::temp_dir().join("aoc21_day5_heatmap");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut activity = ActivityMap::new();
+        for line in lines("1,1 -> 3,1\n2,0 -> 2,2\n1,1 -> 3,3") {
+            activity.add_line(&line);
+        }
+
+        // Only the box around the vents, so no row for y=4 or column for x=0
+        let pgm = dir.join("tiny.pgm");
+        activity.export_pgm(&pgm).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&pgm).unwrap(),
+            include_str!("../../tests/golden/day05_heatmap.pgm")
+        );
+        let csv = dir.join("tiny.csv");
+        activity.export_csv(&csv).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&csv).unwrap(),
+            include_str!("../../tests/golden/day05_heatmap.csv")
+        );
+
+        let empty = dir.join("empty.pgm");
+        ActivityMap::new().export_pgm(&empty).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&empty).unwrap(),
+            "P2\n# origin 0,0\n0 0\n1\n"
+        );
+    }
+
+    #[test]
+    fn contributors() {
+        let input = lines(EXAMPLE);
+        let mut activity = ActivityMap::indexed();
+        for (index, line) in input.iter().enumerate() {
+            activity.add_line_indexed(index, line);
+        }
+        assert_eq!(activity.cells_with_at_least(2).count(), 12);
+
+        // 0,9 -> 5,9 and 0,9 -> 2,9 overlap along the bottom
+        assert_eq!(
+            activity.contributors(&Point { x: 1, y: 9 }),
+            Some(&[0, 6][..])
+        );
+        // Only two points are covered by three lines
+        let hotspots = activity.cells_with_at_least(3).collect::<Vec<_>>();
+        assert_eq!(hotspots.len(), 2);
+        for (point, overlap) in hotspots {
+            let contributors = activity.contributors(point).unwrap();
+            assert_eq!(contributors.len(), overlap);
+            assert!(contributors
+                .iter()
+                .all(|&index| input[index].points().contains(point)));
+        }
+        assert_eq!(activity.contributors(&Point { x: 9, y: 9 }), Some(&[][..]));
+
+        // Merged maps keep every index
+        let (first, second) = input.split_at(5);
+        let mut a = ActivityMap::indexed();
+        first
+            .iter()
+            .enumerate()
+            .for_each(|(i, line)| a.add_line_indexed(i, line));
+        let mut b = ActivityMap::indexed();
+        second
+            .iter()
+            .enumerate()
+            .for_each(|(i, line)| b.add_line_indexed(5 + i, line));
+        let merged = a.merge(b);
+        for (point, _) in activity.cells_with_at_least(1) {
+            let mut indices = merged.contributors(point).unwrap().to_vec();
+            indices.sort_unstable();
+            assert_eq!(indices, activity.contributors(point).unwrap());
+        }
+
+        // Plain maps don't keep any
+        let mut plain = ActivityMap::new();
+        input
+            .iter()
+            .enumerate()
+            .for_each(|(i, line)| plain.add_line_indexed(i, line));
+        assert_eq!(plain.cells_with_at_least(2).count(), 12);
+        assert_eq!(plain.contributors(&Point { x: 1, y: 9 }), None);
+        assert_eq!(
+            plain.merge(merged).contributors(&Point { x: 1, y: 9 }),
+            None
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn rayon_sparse() {
+        // Too spread out for the dense grid, so this goes through the merged HashMaps
+        let input = lines(
+            r"0,0 -> 0,5000
+              0,4000 -> 0,4010
+              -5000,4005 -> 5000,4005
+              0,0 -> 4000,4000",
+        );
+
+        assert_eq!(part1_rayon(&input), part1(&input));
+        assert_eq!(part2_rayon(&input), part2(&input));
+    }
+
+    #[test]
+    fn dense_threshold() {
+        let mut rng = Xorshift::new(0x7f4a7c15);
+        for _ in 0..100 {
+            let spread = [10, 200][rng.next_u32() as usize % 2];
+            let input = (0..rng.next_u32() % 30)
+                .map(|_| {
+                    let (x, y) = (
+                        (rng.next_u32() % spread) as Coord,
+                        (rng.next_u32() % spread) as Coord,
+                    );
+                    let len = (rng.next_u32() % spread) as Coord / 2;
+                    match rng.next_u32() % 4 {
+                        0 => format!("{},{} -> {},{}", x, y, x + len, y),
+                        1 => format!("{},{} -> {},{}", x, y, x, y - len),
+                        2 => format!("{},{} -> {},{}", x, y, x + len, y + len),
+                        _ => format!("{},{} -> {},{}", x, y, x - len, y + len),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let input = lines(&input);
+
+            for diagonals in [false, true] {
+                let mut activity = ActivityMap::new();
+                input
+                    .iter()
+                    .filter(|line| diagonals || !line.is_diagonal())
+                    .for_each(|line| activity.add_line(line));
+                let expected = activity.cells_with_at_least(2).count();
+
+                assert_eq!(count_overlaps(&input, diagonals, 0), expected);
+                assert_eq!(count_overlaps(&input, diagonals, usize::MAX), expected);
+                #[cfg(feature = "parallel")]
+                {
+                    let refs = input
+                        .iter()
+                        .filter(|line| diagonals || !line.is_diagonal())
+                        .collect::<Vec<_>>();
+                    assert_eq!(count_overlaps_parallel(&refs, 0), expected);
+                    assert_eq!(count_overlaps_parallel(&refs, usize::MAX), expected);
+                }
+            }
+        }
+
+        // Corners as far apart as the coordinates go, far too many cells to count or allocate
+        let input = lines(
+            r"-2147483648,-2147483648 -> -2147483648,-2147483646
+              2147483647,2147483647 -> 2147483647,2147483647
+              -2147483648,-2147483647 -> -2147483646,-2147483647",
+        );
+        assert_eq!(count_overlaps(&input, false, usize::MAX), 1);
+        #[cfg(feature = "parallel")]
+        assert_eq!(part1_rayon(&input), 1);
+        assert_eq!(count_overlaps(&[], true, 0), 0);
+    }
+}
+
+#[cfg(test)]
+mod implementations {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day05.txt");
+
+    #[test]
+    fn part1_agrees() {
+        assert_implementations_agree!(lines(EXAMPLE), part1, part1_analytic);
+        #[cfg(feature = "parallel")]
+        assert_implementations_agree!(lines(EXAMPLE), part1, part1_rayon);
+    }
+
+    #[test]
+    fn part2_agrees() {
+        assert_implementations_agree!(lines(EXAMPLE), part2, part2_analytic);
+        #[cfg(feature = "parallel")]
+        assert_implementations_agree!(lines(EXAMPLE), part2, part2_rayon);
+    }
+}
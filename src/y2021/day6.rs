@@ -0,0 +1,409 @@
+use crate::counter::Counter;
+use crate::error::ParseError;
+
+/// Timers of each fish, separated by commas. Fresh fish start on timers from 0 to 8
+#[aoc_generator(day6)]
+pub(crate) fn fish(input: &str) -> Result<Vec<i64>, ParseError> {
+    let mut all_fish = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        for timer in line
+            .split(',')
+            .map(str::trim)
+            .filter(|timer| !timer.is_empty())
+        {
+            let fish = timer
+                .parse::<i64>()
+                .ok()
+                .filter(|fish| (0..=8).contains(fish))
+                .ok_or_else(|| ParseError::at_line(i + 1, format!("bad timer {:?}", timer)))?;
+            all_fish.push(fish);
+        }
+    }
+    Ok(all_fish)
+}
+
+/// Days a fish waits between spawning, and days a newborn fish waits before it first spawns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timers {
+    pub reset: usize,
+    pub spawn: usize,
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Timers { reset: 6, spawn: 8 }
+    }
+}
+
+/// Numbers the school can be counted in. With 64 bits the example school outgrows the count
+/// after 481 days, and with 128 bits after 998
+pub use crate::counter::Count as FishCount;
+
+/// Number of fish after `num_days`, or `None` if it gets too big for `N`
+pub fn count_fish<N: FishCount>(all_fish: &[i64], num_days: usize, timers: Timers) -> Option<N> {
+    let mut counts = Counter::<usize, N>::new();
+    for &n in all_fish {
+        counts.increment(n as usize).ok()?;
+    }
+
+    for _ in 0..num_days {
+        let mut next = Counter::new();
+        for (&timer, count) in counts.iter() {
+            if timer == 0 {
+                next.add(timers.reset, count.clone()).ok()?;
+                next.add(timers.spawn, count.clone()).ok()?;
+            } else {
+                next.add(timer - 1, count.clone()).ok()?;
+            }
+        }
+        counts = next;
+    }
+
+    counts.total().ok()
+}
+
+/// Number of fish with each timer value, for the default timers
+pub type TimerCounts = [i64; 9];
+
+/// How many of `all_fish` have each timer value, or None if a fish has a timer outside 0 to 8
+pub fn timer_counts(all_fish: &[i64]) -> Option<TimerCounts> {
+    let mut counts = [0; 9];
+    for &n in all_fish {
+        *counts.get_mut(usize::try_from(n).ok()?)? += 1;
+    }
+    Some(counts)
+}
+
+/// Advance the counts of each timer value by `days` with the default timers, or None if a count
+/// gets too big for an i64
+pub fn simulate_counts(counts: &TimerCounts, days: usize) -> Option<TimerCounts> {
+    let mut counts = *counts;
+    for _ in 0..days {
+        counts.rotate_left(1);
+        counts[6] = counts[6].checked_add(counts[8])?;
+    }
+    Some(counts)
+}
+
+/// How many fish have each timer value after each of the next `days` days, with the default timers.
+/// Element `i` is the school after day `i + 1`. None if a fish has a timer outside 0 to 8, or the
+/// school gets too big for an i64 to count
+pub fn simulate_with_history(all_fish: &[i64], days: usize) -> Option<Vec<TimerCounts>> {
+    simulate_with_history_every(all_fish, days, 1)
+}
+
+/// Same as `simulate_with_history`, but only keeping every `stride`th day and the last one, so a
+/// long run doesn't need a count for every day
+pub fn simulate_with_history_every(
+    all_fish: &[i64],
+    days: usize,
+    stride: usize,
+) -> Option<Vec<TimerCounts>> {
+    assert!(stride > 0, "Need to keep at least every day");
+
+    let mut counts = timer_counts(all_fish)?;
+    let mut history = Vec::with_capacity(days.div_ceil(stride));
+    for day in 1..=days {
+        counts = simulate_counts(&counts, 1)?;
+        // Each count can fit while the whole school doesn't
+        counts
+            .iter()
+            .try_fold(0i64, |total, &count| total.checked_add(count))?;
+        if day % stride == 0 || day == days {
+            history.push(counts);
+        }
+    }
+    Some(history)
+}
+
+/// The counts `days` earlier that `simulate_counts` would have turned into `final_counts`. A day
+/// is undone by taking the newborns back out of timer 6 and rotating the other way, so the inverse
+/// never leaves the integers and the only way for it to fail is to need fewer than zero fish with
+/// some timer. Returns None if it does
+pub fn reverse_simulate(final_counts: &TimerCounts, days: usize) -> Option<TimerCounts> {
+    let mut counts = *final_counts;
+    if counts.iter().any(|&count| count < 0) {
+        return None;
+    }
+
+    for _ in 0..days {
+        counts[6] -= counts[8];
+        if counts[6] < 0 {
+            return None;
+        }
+        counts.rotate_right(1);
+    }
+    Some(counts)
+}
+
+type Matrix = [[u128; 9]; 9];
+
+/// Product of two matrices, with `reduce` applied to every sum along the way
+fn mat_mul(a: &Matrix, b: &Matrix, reduce: impl Fn(u128) -> u128) -> Matrix {
+    let mut product: Matrix = [[0; 9]; 9];
+    for (i, row) in product.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            for (k, b_row) in b.iter().enumerate() {
+                *cell = reduce(cell.saturating_add(a[i][k].saturating_mul(b_row[j])));
+            }
+        }
+    }
+    product
+}
+
+/// Total fish after `days`, found by raising the one day update to the power of `days` by repeated
+/// squaring, so it takes time in the log of `days`. With a modulus the count is taken modulo it at
+/// every step and never runs out of room. Without one, numbers past the largest u64 stop growing
+/// there, which keeps every count that fits exact, and returns None if the count doesn't fit. A
+/// modulus of zero or a negative count also give None
+pub fn simulate_fish_fast(counts: &TimerCounts, days: u64, modulus: Option<u64>) -> Option<u64> {
+    const TOO_BIG: u128 = u64::MAX as u128 + 1;
+    if modulus == Some(0) {
+        return None;
+    }
+    let reduce = |n: u128| match modulus {
+        Some(modulus) => n % modulus as u128,
+        None => n.min(TOO_BIG),
+    };
+
+    // step[i][j] is how many fish with timer i one fish with timer j turns into the next day
+    let mut step = [[0; 9]; 9];
+    for timer in 1..9 {
+        step[timer - 1][timer] = 1;
+    }
+    step[6][0] = 1;
+    step[8][0] = 1;
+
+    let mut power = [[0; 9]; 9];
+    for (i, row) in power.iter_mut().enumerate() {
+        row[i] = reduce(1);
+    }
+
+    let mut days = days;
+    while days > 0 {
+        if days & 1 == 1 {
+            power = mat_mul(&power, &step, reduce);
+        }
+        days >>= 1;
+        if days > 0 {
+            step = mat_mul(&step, &step, reduce);
+        }
+    }
+
+    let mut total = 0;
+    for row in power.iter() {
+        for (j, &n) in row.iter().enumerate() {
+            let count = reduce(u128::try_from(counts[j]).ok()?);
+            total = reduce(total + reduce(n.saturating_mul(count)));
+        }
+    }
+    u64::try_from(total).ok()
+}
+
+/// The size of the school on the last day of its history, or None if it's too big to count
+fn simulate_fish(all_fish: &[i64], num_days: usize) -> Option<i64> {
+    let history = simulate_with_history_every(all_fish, num_days, num_days.max(1))?;
+    let last = match history.last() {
+        Some(&counts) => counts,
+        None => timer_counts(all_fish)?,
+    };
+    last.iter()
+        .try_fold(0i64, |total, &count| total.checked_add(count))
+}
+
+#[aoc(day6, part1)]
+pub(crate) fn part1(fish: &[i64]) -> Option<i64> {
+    simulate_fish(fish, 80)
+}
+
+#[aoc(day6, part2)]
+pub(crate) fn part2(fish: &[i64]) -> Option<i64> {
+    simulate_fish(fish, 256)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    #[test]
+    fn timers() {
+        let fish = fish("3,4,3,1,2").unwrap();
+        assert_eq!(count_fish::<i64>(&fish, 18, Timers::default()), Some(26));
+
+        // Fish that spawn every day double
+        let timers = Timers { reset: 0, spawn: 0 };
+        assert_eq!(count_fish::<i64>(&[0], 10, timers), Some(1024));
+
+        // A newborn that has to wait longer than any starting fish
+        let timers = Timers {
+            reset: 1,
+            spawn: 12,
+        };
+        assert_eq!(count_fish::<i64>(&[0], 13, timers), Some(8));
+    }
+
+    #[test]
+    fn overflow() {
+        let fish = fish("3,4,3,1,2").unwrap();
+        let timers = Timers::default();
+
+        let fits = count_fish::<u128>(&fish, 481, timers).unwrap();
+        let too_many = count_fish::<u128>(&fish, 482, timers).unwrap();
+        assert_eq!(count_fish::<i64>(&fish, 481, timers), Some(fits as i64));
+        assert_eq!(count_fish::<i64>(&fish, 482, timers), None);
+        assert!(fits <= i64::MAX as u128 && too_many > i64::MAX as u128);
+
+        assert!(count_fish::<u128>(&fish, 998, timers).is_some());
+        assert_eq!(count_fish::<u128>(&fish, 999, timers), None);
+    }
+
+    #[test]
+    fn reverse() {
+        let start = timer_counts(&fish("3,4,3,1,2").unwrap()).unwrap();
+        assert_eq!(start, [0, 1, 1, 2, 1, 0, 0, 0, 0]);
+        assert_eq!(timer_counts(&[3, 9]), None);
+        assert_eq!(timer_counts(&[-1]), None);
+        let after = simulate_counts(&start, 18).unwrap();
+        assert_eq!(after.iter().sum::<i64>(), 26);
+        assert_eq!(reverse_simulate(&after, 18), Some(start));
+
+        // Going back past the first fish needs a negative count
+        assert_eq!(
+            reverse_simulate(&start, 5),
+            Some([1, 0, 0, 0, 0, 0, 1, 0, 2])
+        );
+        assert_eq!(reverse_simulate(&start, 6), None);
+        assert_eq!(reverse_simulate(&[0, 0, 0, 0, 0, 0, 0, 0, 1], 1), None);
+
+        let mut rng = Xorshift::default();
+        for _ in 0..100 {
+            let counts = [(); 9].map(|_| (rng.next_u32() % 1000) as i64);
+            let days = rng.next_u32() as usize % 200;
+            let forward = simulate_counts(&counts, days).unwrap();
+            assert_eq!(reverse_simulate(&forward, days), Some(counts));
+            assert_eq!(
+                forward.iter().sum::<i64>(),
+                count_fish::<i64>(
+                    &(0..9)
+                        .flat_map(|timer| std::iter::repeat_n(timer as i64, counts[timer] as usize))
+                        .collect::<Vec<_>>(),
+                    days,
+                    Timers::default()
+                )
+                .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn history() {
+        let fish = fish("3,4,3,1,2").unwrap();
+        let history = simulate_with_history(&fish, 80).unwrap();
+        assert_eq!(history.len(), 80);
+        // 2,3,2,0,1 then 1,2,1,6,0,8
+        assert_eq!(history[0], [1, 1, 2, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(history[1], [1, 2, 1, 0, 0, 0, 1, 0, 1]);
+        assert_eq!(history[17].iter().sum::<i64>(), 26);
+        assert_eq!(history[79].iter().sum::<i64>(), 5934);
+        // 6,0,6,4,5,6,0,1,1,2,6,0,1,1,1,2,2,3,3,4,6,7,8,8,8,8 on day 18
+        assert_eq!(history[17][0], 3);
+        assert_eq!(history[17][8], 4);
+
+        let every = simulate_with_history_every(&fish, 18, 5).unwrap();
+        assert_eq!(every, [history[4], history[9], history[14], history[17]]);
+        let every = simulate_with_history_every(&fish, 20, 5).unwrap();
+        assert_eq!(every.len(), 4);
+        assert_eq!(every[3], history[19]);
+        assert_eq!(
+            simulate_with_history_every(&fish, 80, 80),
+            Some(vec![history[79]])
+        );
+        assert_eq!(simulate_with_history(&fish, 0), Some(vec![]));
+
+        assert_eq!(part1(&fish), Some(5934));
+        assert_eq!(Some(history[79].iter().sum::<i64>()), part1(&fish));
+        assert_eq!(part2(&fish), Some(26984457539));
+        assert_eq!(simulate_fish(&fish, 0), Some(5));
+
+        // The school outgrows an i64 on the same day as counting it any other way
+        let timers = Timers::default();
+        assert_eq!(simulate_fish(&fish, 481), count_fish(&fish, 481, timers));
+        assert_eq!(simulate_fish(&fish, 482), None);
+        assert_eq!(simulate_with_history_every(&fish, 482, 100), None);
+        assert_eq!(simulate_fish(&[9], 0), None);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(fish("3, 4,3\n1,2\n").unwrap(), [3, 4, 3, 1, 2]);
+        assert!(fish("").unwrap().is_empty());
+        assert_eq!(fish("3,4\n9").err().map(|err| err.line()), Some(2));
+        assert_eq!(fish("3,-1").err().map(|err| err.line()), Some(1));
+        assert_eq!(fish("3,x").err().map(|err| err.line()), Some(1));
+    }
+
+    #[test]
+    fn fast() {
+        let start = timer_counts(&fish("3,4,3,1,2").unwrap()).unwrap();
+        assert_eq!(simulate_fish_fast(&start, 18, None), Some(26));
+        assert_eq!(simulate_fish_fast(&start, 256, None), Some(26984457539));
+        assert_eq!(simulate_fish_fast(&start, 0, Some(7)), Some(5));
+
+        // The count stops fitting at the same day as counting one day at a time
+        assert_eq!(
+            simulate_fish_fast(&start, 481, None),
+            count_fish::<u64>(&fish("3,4,3,1,2").unwrap(), 481, Timers::default())
+        );
+        assert!(count_fish::<u64>(&fish("3,4,3,1,2").unwrap(), 500, Timers::default()).is_none());
+        assert_eq!(simulate_fish_fast(&start, 500, None), None);
+
+        let modulus = 1_000_000_007;
+        let mut counts = start.map(|count| count as u64);
+        for days in 0..=10_000 {
+            if days % 97 == 0 || days == 10_000 {
+                let total = counts.iter().fold(0, |acc, n| (acc + n) % modulus);
+                assert_eq!(
+                    simulate_fish_fast(&start, days, Some(modulus)),
+                    Some(total),
+                    "{} days",
+                    days
+                );
+            }
+            counts.rotate_left(1);
+            counts[6] = (counts[6] + counts[8]) % modulus;
+        }
+
+        assert!(simulate_fish_fast(&start, 1_000_000_000_000_000, Some(modulus)).is_some());
+        assert_eq!(simulate_fish_fast(&start, 18, Some(0)), None);
+        assert_eq!(
+            simulate_fish_fast(&[-1, 0, 0, 0, 0, 0, 0, 0, 0], 18, None),
+            None
+        );
+
+        // An empty school stays empty however far the matrix outgrows a u64
+        assert_eq!(simulate_fish_fast(&[0; 9], 1_000_000, None), Some(0));
+        assert_eq!(
+            simulate_fish_fast(&[0; 9], u64::MAX, Some(modulus)),
+            Some(0)
+        );
+        assert_eq!(simulate_fish_fast(&start, u64::MAX, Some(1)), Some(0));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big_school() {
+        use num_bigint::BigUint;
+
+        let fish = fish("3,4,3,1,2").unwrap();
+        let timers = Timers::default();
+        let big = count_fish::<BigUint>(&fish, 998, timers).unwrap();
+        assert_eq!(
+            Some(big),
+            count_fish::<u128>(&fish, 998, timers).map(BigUint::from)
+        );
+
+        let huge = count_fish::<BigUint>(&fish, 10_000, timers).unwrap();
+        assert_eq!(huge.to_string().len(), 380);
+    }
+}
@@ -0,0 +1,296 @@
+#[aoc_generator(day7)]
+pub(crate) fn crabs(input: &str) -> Vec<i64> {
+    input
+        .lines()
+        .flat_map(|line| line.split(',').filter_map(|c| c.parse().ok()))
+        .collect()
+}
+
+fn min_max(all_crabs: &[i64]) -> (i64, i64) {
+    let min = all_crabs.iter().copied().min().unwrap_or(0);
+    let max = all_crabs.iter().copied().max().unwrap_or(0);
+
+    (min, max)
+}
+
+/// Find the position that minimizes the total fuel needed to move every crab there. `cost` maps the
+/// distance a crab travels to the fuel it burns, and is scaled by that crab's weight when weights
+/// are given. Every position in range is checked, so `cost` does not need to be convex.
+///
+/// Returns (position, fuel), or None when there are no crabs. Ties are broken towards the lower
+/// position
+pub fn align(
+    positions: &[i64],
+    weights: Option<&[i64]>,
+    cost: impl Fn(i64) -> i64,
+) -> Option<(i64, i64)> {
+    if let Some(weights) = weights {
+        assert_eq!(positions.len(), weights.len(), "Need one weight per crab");
+    }
+
+    if positions.is_empty() {
+        return None;
+    }
+    optimum(&fuel_curve(positions, weights, cost))
+}
+
+fn fuel_curve(
+    positions: &[i64],
+    weights: Option<&[i64]>,
+    cost: impl Fn(i64) -> i64,
+) -> Vec<(i64, i64)> {
+    let weight = |i: usize| weights.map_or(1, |weights| weights[i]);
+    let fuel_at = |pos: i64| -> i64 {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(i, &crab)| weight(i) * cost((crab - pos).abs()))
+            .sum()
+    };
+
+    let (min_pos, max_pos) = min_max(positions);
+    (min_pos..=max_pos).map(|pos| (pos, fuel_at(pos))).collect()
+}
+
+/// (position, fuel) for every position from the leftmost crab to the rightmost, where fuel is the
+/// total burnt moving every crab there
+pub fn cost_curve(crabs: &[i64], cost: impl Fn(i64) -> i64) -> Vec<(i64, i64)> {
+    fuel_curve(crabs, None, cost)
+}
+
+fn optimum(curve: &[(i64, i64)]) -> Option<(i64, i64)> {
+    curve.iter().copied().min_by_key(|&(_, fuel)| fuel)
+}
+
+/// The position on a cost curve that takes the least fuel to reach, the lowest one on a tie
+pub fn optimal_position(curve: &[(i64, i64)]) -> Option<i64> {
+    optimum(curve).map(|(pos, _)| pos)
+}
+
+/// The least fuel on a cost curve
+pub fn optimal_cost(curve: &[(i64, i64)]) -> Option<i64> {
+    optimum(curve).map(|(_, fuel)| fuel)
+}
+
+#[cfg(any(test, feature = "viz"))]
+fn linear(dist: i64) -> i64 {
+    dist
+}
+
+/// Each step costs one more than the last. Closed form: ((n)(n+1) / 2)
+#[cfg(any(test, feature = "viz"))]
+fn triangular(dist: i64) -> i64 {
+    dist * (dist + 1) / 2
+}
+
+/// Total fuel to move every crab to `pos`, in a type wide enough for crabs billions of positions
+/// apart
+fn total_fuel(crabs: &[i64], pos: i64, cost: impl Fn(i128) -> i128) -> i128 {
+    crabs
+        .iter()
+        .map(|&crab| cost((crab as i128 - pos as i128).abs()))
+        .sum()
+}
+
+/// (position, fuel) of the cheapest alignment when each step costs one fuel, found without
+/// scanning the range of positions. The fuel only goes down while more crabs are ahead than
+/// behind, so it bottoms out at the median, the lower middle crab when there are two. None when
+/// there are no crabs
+pub fn median_alignment(crabs: &[i64]) -> Option<(i64, i128)> {
+    let mut sorted = crabs.to_vec();
+    let middle = crabs.len().checked_sub(1)? / 2;
+    let (_, &mut median, _) = sorted.select_nth_unstable(middle);
+    Some((median, total_fuel(crabs, median, |dist| dist)))
+}
+
+/// (position, fuel) of the cheapest alignment when each step costs one more than the last, found
+/// without scanning the range of positions. None when there are no crabs.
+///
+/// Moving a crab a distance d burns (d² + d) / 2, so over the reals the slope of the total fuel at
+/// p is Σ(p - x) + ½Σsign(p - x). That is zero at p = mean - Σsign(p - x) / 2n, within ½ of the
+/// mean. The fuel is convex, so the best whole position is next to that point, and only the
+/// positions from ⌊mean - ½⌋ to ⌈mean + ½⌉ need checking. Ties go to the lower position
+pub fn mean_alignment(crabs: &[i64]) -> Option<(i64, i128)> {
+    if crabs.is_empty() {
+        return None;
+    }
+
+    let n = crabs.len() as i128;
+    let sum = crabs.iter().map(|&crab| crab as i128).sum::<i128>();
+    // ⌊(2·sum - n) / 2n⌋ and ⌈(2·sum + n) / 2n⌉
+    let low = (2 * sum - n).div_euclid(2 * n);
+    let high = -(-(2 * sum + n)).div_euclid(2 * n);
+
+    (low..=high)
+        .map(|pos| {
+            let pos = pos as i64;
+            (pos, total_fuel(crabs, pos, |dist| dist * (dist + 1) / 2))
+        })
+        .min_by_key(|&(_, fuel)| fuel)
+}
+
+#[cfg(feature = "viz")]
+fn render(curve: &[(i64, i64)]) -> crate::viz::Canvas {
+    use crate::viz::{gray, Canvas, RED, WHITE};
+
+    const HEIGHT: usize = 32;
+    let lowest = optimal_cost(curve).unwrap_or(0);
+    let highest = curve.iter().map(|&(_, fuel)| fuel).max().unwrap_or(0);
+    let best = optimal_position(curve);
+
+    // Each column is a position, with a bar as tall as the fuel above the optimum
+    let mut canvas = Canvas::new(curve.len(), HEIGHT);
+    for (x, &(pos, fuel)) in curve.iter().enumerate() {
+        let bar = if highest == lowest {
+            0
+        } else {
+            ((fuel - lowest) as usize * (HEIGHT - 1)) / (highest - lowest) as usize
+        };
+        for y in 0..HEIGHT {
+            let (c, color) = if Some(pos) == best {
+                ('|', RED)
+            } else if HEIGHT - 1 - y <= bar {
+                ('#', gray(HEIGHT - y, HEIGHT))
+            } else {
+                (' ', WHITE)
+            };
+            canvas.set(x, y, c, color);
+        }
+    }
+    canvas
+}
+
+/// The cost curves for part 1 and part 2, with the optimal position marked
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    let crabs = crabs(input);
+    vec![
+        render(&cost_curve(&crabs, linear)),
+        render(&cost_curve(&crabs, triangular)),
+    ]
+}
+
+#[aoc(day7, part1)]
+pub(crate) fn part1(crabs: &[i64]) -> i128 {
+    median_alignment(crabs).map_or(0, |(_, fuel)| fuel)
+}
+
+#[aoc(day7, part2)]
+pub(crate) fn part2(crabs: &[i64]) -> i128 {
+    mean_alignment(crabs).map_or(0, |(_, fuel)| fuel)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    #[test]
+    fn example() {
+        let input = crabs(r"16,1,2,0,4,2,7,1,2,14");
+        assert_eq!(part1(&input), 37);
+        assert_eq!(part2(&input), 168);
+
+        assert_eq!(align(&input, None, |dist| dist), Some((2, 37)));
+        assert_eq!(
+            align(&input, None, |dist| dist * (dist + 1) / 2),
+            Some((5, 168))
+        );
+    }
+
+    #[test]
+    fn weighted() {
+        assert_eq!(align(&[0, 10], Some(&[1, 3]), |dist| dist), Some((10, 10)));
+        assert_eq!(align(&[0, 10], Some(&[3, 1]), |dist| dist), Some((0, 10)));
+    }
+
+    #[test]
+    fn capped_cost() {
+        let crabs = [0, 1, 2, 100];
+        assert_eq!(align(&crabs, None, |dist| dist), Some((1, 101)));
+        assert_eq!(align(&crabs, None, |dist| dist.min(3)), Some((1, 5)));
+        assert_eq!(align(&[], None, |dist| dist), None);
+    }
+
+    #[test]
+    fn curve() {
+        let input = crabs(r"16,1,2,0,4,2,7,1,2,14");
+        let curve = cost_curve(&input, linear);
+        assert_eq!(curve.len(), 17);
+        assert_eq!(curve[..4], [(0, 49), (1, 41), (2, 37), (3, 39)]);
+        assert_eq!(optimal_position(&curve), Some(2));
+        assert_eq!(optimal_cost(&curve), Some(37));
+
+        // Both costs are convex, so the fuel falls to the optimum and then rises
+        let curve = cost_curve(&input, triangular);
+        let best = optimal_position(&curve).unwrap() as usize;
+        assert_eq!(curve[best], (5, 168));
+        assert!(curve[..=best].windows(2).all(|w| w[0].1 >= w[1].1));
+        assert!(curve[best..].windows(2).all(|w| w[0].1 <= w[1].1));
+
+        assert_eq!(optimal_position(&[]), None);
+        assert_eq!(optimal_cost(&[(3, 1), (4, 1)]), Some(1));
+        assert_eq!(optimal_position(&[(3, 1), (4, 1)]), Some(3));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let input = crabs(r"16,1,2,0,4,2,7,1,2,14");
+        assert_eq!(median_alignment(&input), Some((2, 37)));
+        assert_eq!(mean_alignment(&input), Some((5, 168)));
+        assert_eq!(median_alignment(&[]), None);
+        assert_eq!(mean_alignment(&[]), None);
+        assert_eq!((part1(&[]), part2(&[])), (0, 0));
+
+        // Far too wide a range to scan
+        let far = [-3_000_000_000, 0, 7, 5_000_000_000];
+        assert_eq!(median_alignment(&far), Some((0, 8_000_000_007)));
+        let wide = [0, 4_000_000_000];
+        let half = 2_000_000_000i128;
+        assert_eq!(
+            mean_alignment(&wide),
+            Some((2_000_000_000, half * (half + 1)))
+        );
+
+        // More fuel than fits in an i64
+        let wider = [0, 8_000_000_000];
+        assert_eq!(part2(&wider), 16_000_000_004_000_000_000);
+        assert_eq!(part1(&wider), 8_000_000_000);
+
+        let mut rng = Xorshift::new(0x1f123bb5);
+        for _ in 0..500 {
+            let len = 1 + rng.next_u32() as usize % 12;
+            let spread = [3, 20, 1000][rng.next_u32() as usize % 3];
+            let crabs = (0..len)
+                .map(|_| (rng.next_u32() % spread) as i64 - spread as i64 / 2)
+                .collect::<Vec<_>>();
+
+            let (pos, fuel) = align(&crabs, None, linear).unwrap();
+            assert_eq!(
+                median_alignment(&crabs),
+                Some((pos, fuel as i128)),
+                "{:?}",
+                crabs
+            );
+            let (pos, fuel) = align(&crabs, None, triangular).unwrap();
+            assert_eq!(
+                mean_alignment(&crabs),
+                Some((pos, fuel as i128)),
+                "{:?}",
+                crabs
+            );
+        }
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn render_curve() {
+        let frames = render_frames("0,2,2");
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].width(), 3);
+        let ascii = frames[0].to_ascii();
+        let rows = ascii.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0], "# |");
+        assert_eq!(rows[rows.len() - 1], "##|");
+    }
+}
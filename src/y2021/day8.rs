@@ -0,0 +1,517 @@
+pub struct LogEntry {
+    patterns: Vec<String>,
+    output: Vec<String>,
+}
+
+impl LogEntry {
+    pub fn patterns_of_len(&self, len: usize) -> Vec<String> {
+        self.patterns
+            .iter()
+            .filter(|pat| pat.len() == len)
+            .cloned()
+            .collect()
+    }
+}
+
+pub fn lhs_subsets_rhs(sub: &str, sup: &str) -> bool {
+    sub.chars().all(|c| sup.contains(c))
+}
+
+pub fn sort_str(s: &str) -> String {
+    let mut char_vec = s.chars().collect::<Vec<_>>();
+    char_vec.sort();
+    String::from_iter(char_vec.iter())
+}
+
+pub fn parse_patterns(pats: &str) -> Vec<String> {
+    pats.split(' ').map(sort_str).collect::<Vec<String>>()
+}
+
+/// Segments lit for each digit, with bit i set for segment `'a' + i`
+const DIGIT_SEGMENTS: [u8; 10] = [
+    0b1110111, 0b0100100, 0b1011101, 0b1101101, 0b0101110, 0b1101011, 0b1111011, 0b0100101,
+    0b1111111, 0b1101111,
+];
+
+/// Reasons the wiring of an entry can't be worked out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A pattern uses a wire other than a to g
+    UnknownWire(char),
+    /// The entry has this many different patterns instead of one for each digit
+    PatternCount(usize),
+    /// No wiring turns the patterns into the ten digits
+    Inconsistent,
+    /// This many wirings turn every pattern into a digit, so there's no telling which is right
+    Ambiguous(usize),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::UnknownWire(c) => write!(f, "unknown wire {:?}", c),
+            DecodeError::PatternCount(n) => write!(f, "expected 10 different patterns, got {}", n),
+            DecodeError::Inconsistent => f.write_str("patterns don't match any wiring"),
+            DecodeError::Ambiguous(n) => write!(f, "patterns match {} different wirings", n),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Which segment each scrambled wire is connected to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentMapping {
+    segments: [u8; 7],
+}
+
+fn wire_index(wire: char) -> Result<usize, DecodeError> {
+    match wire {
+        'a'..='g' => Ok(wire as usize - 'a' as usize),
+        _ => Err(DecodeError::UnknownWire(wire)),
+    }
+}
+
+impl SegmentMapping {
+    /// The segment, from 'a' to 'g', that `wire` lights
+    pub fn segment(&self, wire: char) -> Option<char> {
+        let index = wire_index(wire).ok()?;
+        Some((b'a' + self.segments[index]) as char)
+    }
+
+    /// Segments lit by a pattern of wires, with bit i set for segment `'a' + i`
+    pub fn lit_segments(&self, pattern: &str) -> Option<u8> {
+        pattern.chars().try_fold(0, |lit, wire| {
+            let index = wire_index(wire).ok()?;
+            Some(lit | 1 << self.segments[index])
+        })
+    }
+
+    /// The digit a pattern of wires shows, or None if it doesn't show one
+    pub fn decode(&self, pattern: &str) -> Option<u8> {
+        let lit = self.lit_segments(pattern)?;
+        DIGIT_SEGMENTS
+            .iter()
+            .position(|&segments| segments == lit)
+            .map(|digit| digit as u8)
+    }
+}
+
+/// Work out the wiring from the ten patterns of an entry. Across the ten digits, segments b, e and
+/// f are each lit a different number of times (6, 4 and 9). Of the two lit 8 times, only c is part
+/// of 1, and of the two lit 7 times only d is part of 4
+pub fn deduce_mapping(entry: &LogEntry) -> Result<SegmentMapping, DecodeError> {
+    let mut patterns = entry
+        .patterns
+        .iter()
+        .map(|pat| sort_str(pat))
+        .collect::<Vec<_>>();
+    patterns.sort();
+    patterns.dedup();
+    if patterns.len() != 10 {
+        return Err(DecodeError::PatternCount(patterns.len()));
+    }
+
+    let mut counts = [0; 7];
+    for wire in patterns.iter().flat_map(|pat| pat.chars()) {
+        counts[wire_index(wire)?] += 1;
+    }
+
+    let one = patterns.iter().find(|pat| pat.len() == 2);
+    let four = patterns.iter().find(|pat| pat.len() == 4);
+    let (one, four) = one.zip(four).ok_or(DecodeError::Inconsistent)?;
+
+    let mut segments = [0; 7];
+    for (index, count) in counts.into_iter().enumerate() {
+        let wire = (b'a' + index as u8) as char;
+        segments[index] = match count {
+            4 => b'e',
+            6 => b'b',
+            9 => b'f',
+            7 if four.contains(wire) => b'd',
+            7 => b'g',
+            8 if one.contains(wire) => b'c',
+            8 => b'a',
+            _ => return Err(DecodeError::Inconsistent),
+        } - b'a';
+    }
+
+    let mapping = SegmentMapping { segments };
+    let mut digits = patterns
+        .iter()
+        .map(|pat| mapping.decode(pat))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(DecodeError::Inconsistent)?;
+    digits.sort_unstable();
+    if digits != (0..10).collect::<Vec<_>>() {
+        return Err(DecodeError::Inconsistent);
+    }
+
+    Ok(mapping)
+}
+
+/// How the wiring of an entry was worked out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// From how often each segment is lit, by `deduce_mapping`
+    Constraints,
+    /// By trying every wiring against the patterns and output, for entries that don't show each
+    /// digit exactly once
+    BruteForce,
+}
+
+/// Step `wires` on to the next ordering in lexicographic order, returning false once it has wrapped
+/// back around to the first
+fn next_permutation(wires: &mut [u8]) -> bool {
+    let Some(pivot) = wires.windows(2).rposition(|pair| pair[0] < pair[1]) else {
+        wires.reverse();
+        return false;
+    };
+    let successor = wires.iter().rposition(|&wire| wire > wires[pivot]).unwrap();
+    wires.swap(pivot, successor);
+    wires[pivot + 1..].reverse();
+    true
+}
+
+/// The only wiring under which every pattern and output of the entry shows a digit
+fn brute_force_mapping(entry: &LogEntry) -> Result<SegmentMapping, DecodeError> {
+    for wire in entry
+        .patterns
+        .iter()
+        .chain(&entry.output)
+        .flat_map(|pat| pat.chars())
+    {
+        wire_index(wire)?;
+    }
+
+    let mut segments = [0, 1, 2, 3, 4, 5, 6];
+    let mut found = None;
+    let mut matches = 0;
+    loop {
+        let mapping = SegmentMapping { segments };
+        let shows_digits = entry
+            .patterns
+            .iter()
+            .chain(&entry.output)
+            .all(|pat| mapping.decode(pat).is_some());
+        if shows_digits {
+            found = Some(mapping);
+            matches += 1;
+        }
+        if !next_permutation(&mut segments) {
+            break;
+        }
+    }
+
+    match (found, matches) {
+        (Some(mapping), 1) => Ok(mapping),
+        (None, _) => Err(DecodeError::Inconsistent),
+        (Some(_), matches) => Err(DecodeError::Ambiguous(matches)),
+    }
+}
+
+/// Work out the wiring with `deduce_mapping`, falling back on trying all 7! wirings when the entry
+/// doesn't have a pattern for each digit. Wires other than a to g are never recovered from
+pub fn deduce_mapping_with_fallback(
+    entry: &LogEntry,
+) -> Result<(SegmentMapping, Strategy), DecodeError> {
+    match deduce_mapping(entry) {
+        Ok(mapping) => Ok((mapping, Strategy::Constraints)),
+        Err(DecodeError::UnknownWire(wire)) => Err(DecodeError::UnknownWire(wire)),
+        Err(_) => Ok((brute_force_mapping(entry)?, Strategy::BruteForce)),
+    }
+}
+
+/// The number on the display, or None if the wiring can't be worked out, an output isn't a digit
+/// or there are too many digits for a u64
+pub fn decode_entry(ent: &LogEntry) -> Option<u64> {
+    let (mapping, _) = deduce_mapping_with_fallback(ent).ok()?;
+    ent.output.iter().try_fold(0u64, |acc, s| {
+        let digit = mapping.decode(s)?;
+        acc.checked_mul(10)?.checked_add(digit as u64)
+    })
+}
+
+/// Where each segment sits in a digit's 3x3 cell, as (segment, row, column, character)
+#[cfg(feature = "viz")]
+const SEGMENT_CELLS: [(u8, usize, usize, char); 7] = [
+    (0, 0, 1, '_'),
+    (1, 1, 0, '|'),
+    (2, 1, 2, '|'),
+    (3, 1, 1, '_'),
+    (4, 2, 0, '|'),
+    (5, 2, 2, '|'),
+    (6, 2, 1, '_'),
+];
+
+/// Draw the output digits of an entry as they'd appear on the display, three rows of text with a
+/// column of space between digits. Patterns that use unknown wires are left dark
+#[cfg(feature = "viz")]
+pub fn render_display(entry: &LogEntry, mapping: &SegmentMapping) -> String {
+    let mut rows = vec![String::new(); 3];
+    for (index, pattern) in entry.output.iter().enumerate() {
+        let lit = mapping.lit_segments(pattern).unwrap_or(0);
+        let mut cell = [[' '; 3]; 3];
+        for &(segment, row, col, c) in SEGMENT_CELLS.iter() {
+            if lit & 1 << segment != 0 {
+                cell[row][col] = c;
+            }
+        }
+        for (row, text) in rows.iter_mut().enumerate() {
+            if index > 0 {
+                text.push(' ');
+            }
+            text.extend(cell[row]);
+        }
+    }
+    rows.join("\n")
+}
+
+/// The display of every entry whose wiring can be worked out, stacked with a blank row between
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    use crate::viz::{Canvas, WHITE};
+
+    let displays = digits(input)
+        .iter()
+        .filter_map(|entry| Some(render_display(entry, &deduce_mapping(entry).ok()?)))
+        .collect::<Vec<_>>();
+    let width = displays
+        .iter()
+        .flat_map(|display| display.lines())
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut canvas = Canvas::new(width, (4 * displays.len()).saturating_sub(1));
+    for (index, display) in displays.iter().enumerate() {
+        for (row, line) in display.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                canvas.set(x, 4 * index + row, c, WHITE);
+            }
+        }
+    }
+    vec![canvas]
+}
+
+#[aoc_generator(day8)]
+pub(crate) fn digits(input: &str) -> Vec<LogEntry> {
+    input
+        .lines()
+        .filter_map(|line| {
+            if let Some((patterns_str, output_str)) = line.split_once('|') {
+                let patterns = parse_patterns(patterns_str.trim());
+                let output = parse_patterns(output_str.trim());
+                Some(LogEntry { patterns, output })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[aoc(day8, part1)]
+pub(crate) fn part1(input: &[LogEntry]) -> usize {
+    input
+        .iter()
+        .map(|entry| {
+            entry
+                .output
+                .iter()
+                .filter(|segments| matches!(segments.len(), 2 | 3 | 4 | 7))
+                .count()
+        })
+        .sum()
+}
+
+#[aoc(day8, part2)]
+pub(crate) fn part2(entries: &[LogEntry]) -> Option<u64> {
+    entries
+        .iter()
+        .try_fold(0u64, |sum, entry| sum.checked_add(decode_entry(entry)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day08.txt");
+
+    /// The single entry worked through in the puzzle
+    const SMALL_EXAMPLE: &str =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    #[test]
+    fn example() {
+        let input = digits(EXAMPLE);
+        assert_eq!(part1(&input), 26);
+        assert_eq!(part2(&input), Some(61229));
+    }
+
+    #[test]
+    fn small_example() {
+        let input = digits(SMALL_EXAMPLE);
+
+        assert_eq!(part2(&input), Some(5353));
+    }
+
+    #[test]
+    fn mapping() {
+        let input = digits(SMALL_EXAMPLE);
+        let mapping = deduce_mapping(&input[0]).unwrap();
+        let wiring = "abcdefg"
+            .chars()
+            .map(|wire| mapping.segment(wire).unwrap())
+            .collect::<String>();
+        assert_eq!(wiring, "cfgabde");
+
+        assert_eq!(mapping.decode("acedgfb"), Some(8));
+        assert_eq!(mapping.decode("ab"), Some(1));
+        assert_eq!(mapping.decode("gcdfa"), Some(2));
+        assert_eq!(mapping.decode("ba"), Some(1));
+        assert_eq!(mapping.decode("abc"), None);
+        assert_eq!(mapping.decode("abz"), None);
+        assert_eq!(mapping.lit_segments("ab"), Some(0b0100100));
+
+        // The identity wiring
+        let plain = digits(
+            "abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg | abcdefg cf abdfg acf",
+        );
+        let mapping = deduce_mapping(&plain[0]).unwrap();
+        assert!("abcdefg".chars().all(|c| mapping.segment(c) == Some(c)));
+        assert_eq!(decode_entry(&plain[0]), Some(8157));
+
+        let repeated = digits("ab ab dab eafb cdfbe gcdfa fbcad cefabd cdfgeb acedgfb | ab");
+        assert_eq!(
+            deduce_mapping(&repeated[0]),
+            Err(DecodeError::PatternCount(9))
+        );
+        let unknown = digits("ab dab eafb cdfbe gcdfa fbcad cefabd cdfgeb acedgfb abcdefz | ab");
+        assert_eq!(
+            deduce_mapping(&unknown[0]),
+            Err(DecodeError::UnknownWire('z'))
+        );
+        let swapped = digits("ab dab eafb cdfbe gcdfa fbcad cefabd cdfgeb acedgfb acdefg | ab");
+        assert_eq!(deduce_mapping(&swapped[0]), Err(DecodeError::Inconsistent));
+    }
+
+    #[test]
+    fn fallback() {
+        let input = digits(SMALL_EXAMPLE);
+        let (mapping, strategy) = deduce_mapping_with_fallback(&input[0]).unwrap();
+        assert_eq!(strategy, Strategy::Constraints);
+        assert_eq!(brute_force_mapping(&input[0]), Ok(mapping));
+
+        // Without the patterns for 1, 4 or 8 the segment counts are off, but the remaining digits
+        // only fit one wiring
+        let (patterns, output) = SMALL_EXAMPLE.split_once(" | ").unwrap();
+        let without = |missing: &str| {
+            let patterns = patterns
+                .split(' ')
+                .filter(|&pattern| pattern != missing)
+                .collect::<Vec<_>>();
+            digits(&format!("{} | {}", patterns.join(" "), output))
+        };
+        for missing in ["ab", "eafb", "acedgfb"] {
+            let entry = &without(missing)[0];
+            assert_eq!(
+                deduce_mapping(entry),
+                Err(DecodeError::PatternCount(9)),
+                "{}",
+                missing
+            );
+            assert_eq!(
+                deduce_mapping_with_fallback(entry),
+                Ok((mapping, Strategy::BruteForce)),
+                "{}",
+                missing
+            );
+            assert_eq!(decode_entry(entry), Some(5353));
+        }
+
+        // Without 7 a second wiring fits too
+        assert_eq!(
+            deduce_mapping_with_fallback(&without("dab")[0]),
+            Err(DecodeError::Ambiguous(2))
+        );
+
+        // An 8 and a 1 say next to nothing about the wiring
+        let sparse = &digits("acedgfb ab | ab ba")[0];
+        assert!(matches!(
+            deduce_mapping_with_fallback(sparse),
+            Err(DecodeError::Ambiguous(n)) if n > 1
+        ));
+        assert_eq!(decode_entry(sparse), None);
+        assert_eq!(part2(&digits("ab | ab")), None);
+
+        // Eleven digits are past a u32, and twenty past a u64
+        let (patterns, _) = SMALL_EXAMPLE.split_once(" | ").unwrap();
+        let long = |digits: usize| format!("{} | {}\n", patterns, vec!["cdfgeb"; digits].join(" "));
+        assert_eq!(part2(&digits(&long(11))), Some(66_666_666_666));
+        assert_eq!(part2(&digits(&long(20))), None);
+        assert_eq!(part2(&digits(&long(19).repeat(3))), None);
+        let unknown = &digits("dab eafb cdfbe gcdfa | abz")[0];
+        assert_eq!(
+            deduce_mapping_with_fallback(unknown),
+            Err(DecodeError::UnknownWire('z'))
+        );
+        let impossible = &digits("ab abc abcd | abcde")[0];
+        assert_eq!(
+            deduce_mapping_with_fallback(impossible),
+            Err(DecodeError::Inconsistent)
+        );
+
+        let mut wires = [0, 1, 2];
+        let mut orders = vec![wires];
+        while next_permutation(&mut wires) {
+            orders.push(wires);
+        }
+        assert_eq!(
+            orders,
+            [
+                [0, 1, 2],
+                [0, 2, 1],
+                [1, 0, 2],
+                [1, 2, 0],
+                [2, 0, 1],
+                [2, 1, 0]
+            ]
+        );
+        assert_eq!(wires, [0, 1, 2]);
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn display() {
+        let input = digits(SMALL_EXAMPLE);
+        let mapping = deduce_mapping(&input[0]).unwrap();
+        assert_eq!(
+            render_display(&input[0], &mapping),
+            [" _   _   _   _ ", "|_   _| |_   _|", " _|  _|  _|  _|"].join("\n")
+        );
+
+        let plain = digits(
+            "abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg | abcdefg cf abdfg abcefg",
+        );
+        let mapping = deduce_mapping(&plain[0]).unwrap();
+        assert_eq!(
+            render_display(&plain[0], &mapping),
+            [" _       _   _ ", "|_|   | |_  | |", "|_|   |  _| |_|"].join("\n")
+        );
+
+        let unknown = digits("abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg | xy cf");
+        assert_eq!(
+            render_display(&unknown[0], &mapping),
+            ["       ", "      |", "      |"].join("\n")
+        );
+
+        let frames = render_frames(&format!(
+            "{}\nab | ab\n{}",
+            SMALL_EXAMPLE,
+            "abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg | abcdefg cf abdfg abcefg"
+        ));
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].height(), 7);
+        assert_eq!(frames[0].to_ascii().lines().nth(5), Some("|_|   | |_  | |"));
+    }
+}
@@ -0,0 +1,610 @@
+use crate::error::ParseError;
+use crate::grid::{parse_rows, RowWindow, Rows};
+use std::collections::{HashMap, VecDeque};
+use std::io::BufRead;
+
+pub type HeightMap = Vec<Vec<i32>>;
+
+/// How cells are grouped into basins. Cells of height 9 are never part of a basin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasinPolicy {
+    /// Basins flood out from each low point until they reach a 9, as in the puzzle
+    Flood,
+    /// Every cell flows to its lowest neighbor, the first in `neighbors` order on a tie, and
+    /// belongs to the basin it ends up in. Flat areas drain towards their nearest way down, and a
+    /// flat bottom is a single basin
+    Watershed,
+}
+
+fn height(c: char) -> Result<i32, String> {
+    c.to_digit(10)
+        .map(|d| d as i32)
+        .ok_or_else(|| format!("invalid height {:?}", c))
+}
+
+/// One row of the map, turning down anything that isn't a digit the same way `heightmap` does
+fn heights(number: usize, line: &str) -> Result<Vec<i32>, ParseError> {
+    line.trim()
+        .chars()
+        .map(|c| height(c).map_err(|message| ParseError::at_line(number, message)))
+        .collect()
+}
+
+/// Rows of single digit heights, all as long as the first
+#[aoc_generator(day9)]
+pub(crate) fn heightmap(input: &str) -> Result<HeightMap, ParseError> {
+    parse_rows(input, height)
+}
+
+/// Orthogonal neighbors of (row, col). Positions that would fall off the edge of the map are
+/// skipped, so callers don't need to pad the map
+pub fn neighbors(
+    heightmap: &HeightMap,
+    row: usize,
+    col: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let rows = heightmap.len();
+    let cols = heightmap[row].len();
+
+    let mut adjacent = Vec::with_capacity(4);
+    if col > 0 {
+        adjacent.push((row, col - 1));
+    }
+    if col + 1 < cols {
+        adjacent.push((row, col + 1));
+    }
+    if row > 0 {
+        adjacent.push((row - 1, col));
+    }
+    if row + 1 < rows {
+        adjacent.push((row + 1, col));
+    }
+
+    adjacent.into_iter()
+}
+
+pub fn is_low_point(heightmap: &HeightMap, row: usize, col: usize) -> bool {
+    let center = heightmap[row][col];
+    neighbors(heightmap, row, col).all(|(row, col)| center < heightmap[row][col])
+}
+
+/// Low points in reading order, found as they're asked for
+pub fn lowpoints(heightmap: &HeightMap) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let rows = heightmap.len();
+    let cols = heightmap.first().map_or(0, Vec::len);
+
+    (0..rows).flat_map(move |row| {
+        (0..cols).filter_map(move |col| {
+            if is_low_point(heightmap, row, col) {
+                Some((row, col))
+            } else {
+                None
+            }
+        })
+    })
+}
+
+pub fn find_lowpoints(heightmap: &HeightMap) -> Vec<(usize, usize)> {
+    lowpoints(heightmap).collect()
+}
+
+/// Low points in a heightmap read a row at a time from `reader`, with the sum of their risk levels,
+/// for maps too big to read into memory. Only three rows are kept at once, and blank lines are
+/// skipped. A character that isn't a digit is an `InvalidData` error naming it and its line. Only
+/// part 1 can be answered this way: a basin can stretch over any number of rows, so sizing basins
+/// needs the whole map
+pub fn lowpoints_streaming(reader: impl BufRead) -> std::io::Result<(Vec<(usize, usize)>, i64)> {
+    let mut low_points = Vec::new();
+    let mut risk = 0;
+    let mut find = |rows: Rows<i32>| {
+        for (col, &height) in rows.current.iter().enumerate() {
+            if rows.neighbors(col).all(|&neighbor| height < neighbor) {
+                low_points.push((rows.index, col));
+                risk += 1 + height as i64;
+            }
+        }
+    };
+
+    let mut window = RowWindow::new();
+    for (i, line) in reader.lines().enumerate() {
+        let row = heights(i + 1, &line?)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        if row.is_empty() {
+            continue;
+        }
+        if let Some(rows) = window.push(row) {
+            find(rows);
+        }
+    }
+    if let Some(rows) = window.finish() {
+        find(rows);
+    }
+
+    Ok((low_points, risk))
+}
+
+pub fn basin_size(heightmap: &HeightMap, row: usize, col: usize) -> i32 {
+    let rows = heightmap.len();
+    let cols = heightmap.first().map_or(0, Vec::len);
+    let mut visited = vec![vec![false; cols]; rows];
+
+    floodfill(heightmap, row, col, &mut visited)
+}
+
+pub fn floodfill(
+    heightmap: &HeightMap,
+    row: usize,
+    col: usize,
+    visited: &mut Vec<Vec<bool>>,
+) -> i32 {
+    let center = heightmap[row][col];
+    if center >= 9 || visited[row][col] {
+        return 0;
+    }
+    visited[row][col] = true;
+
+    1 + neighbors(heightmap, row, col)
+        .map(|(row, col)| floodfill(heightmap, row, col, visited))
+        .sum::<i32>()
+}
+
+/// Label every cell with the index of the basin (in `find_lowpoints` order) that it drains into.
+/// Cells of height 9 are not part of any basin
+pub fn label_basins(heightmap: &HeightMap) -> Vec<Vec<Option<usize>>> {
+    label_basins_with(heightmap, BasinPolicy::Flood)
+}
+
+/// Label every cell with the index of its basin under `policy`. Basins are numbered by where
+/// their bottom first appears reading row by row, which is `find_lowpoints` order when every
+/// bottom is a single low point
+pub fn label_basins_with(heightmap: &HeightMap, policy: BasinPolicy) -> Vec<Vec<Option<usize>>> {
+    match policy {
+        BasinPolicy::Flood => flood_basins(heightmap),
+        BasinPolicy::Watershed => watershed_basins(heightmap),
+    }
+}
+
+fn flood_basins(heightmap: &HeightMap) -> Vec<Vec<Option<usize>>> {
+    let mut labels = heightmap
+        .iter()
+        .map(|row| vec![None; row.len()])
+        .collect::<Vec<_>>();
+
+    for (basin, (row, col)) in lowpoints(heightmap).enumerate() {
+        let mut to_visit = vec![(row, col)];
+        while let Some((row, col)) = to_visit.pop() {
+            if heightmap[row][col] >= 9 || labels[row][col].is_some() {
+                continue;
+            }
+
+            labels[row][col] = Some(basin);
+            to_visit.extend(neighbors(heightmap, row, col));
+        }
+    }
+
+    labels
+}
+
+fn watershed_basins(heightmap: &HeightMap) -> Vec<Vec<Option<usize>>> {
+    let cells = || {
+        (0..heightmap.len()).flat_map(|row| (0..heightmap[row].len()).map(move |col| (row, col)))
+    };
+    let height = |(row, col): (usize, usize)| heightmap[row][col];
+
+    // Where each cell flows to next, None for the bottom of a basin
+    let mut drain = heightmap
+        .iter()
+        .map(|row| vec![None; row.len()])
+        .collect::<Vec<_>>();
+    for (row, col) in cells().filter(|&pos| height(pos) < 9) {
+        drain[row][col] = neighbors(heightmap, row, col)
+            .filter(|&pos| height(pos) < heightmap[row][col])
+            .min_by_key(|&pos| height(pos));
+    }
+
+    // Cells with nowhere lower to go may be on a plateau that has a way down somewhere else
+    let mut seen = heightmap
+        .iter()
+        .map(|row| vec![false; row.len()])
+        .collect::<Vec<_>>();
+    for (row, col) in cells() {
+        if height((row, col)) >= 9 || drain[row][col].is_some() || seen[row][col] {
+            continue;
+        }
+
+        let level = heightmap[row][col];
+        let mut plateau = vec![(row, col)];
+        seen[row][col] = true;
+        let mut next = 0;
+        while let Some(&(r, c)) = plateau.get(next) {
+            next += 1;
+            for (r, c) in neighbors(heightmap, r, c) {
+                if heightmap[r][c] == level && !seen[r][c] {
+                    seen[r][c] = true;
+                    plateau.push((r, c));
+                }
+            }
+        }
+
+        // Distance across the plateau to the nearest cell with a lower neighbor
+        let mut distance = HashMap::new();
+        let mut to_visit = VecDeque::new();
+        for &(r, c) in plateau.iter().filter(|&&(r, c)| drain[r][c].is_some()) {
+            distance.insert((r, c), 0);
+            to_visit.push_back((r, c));
+        }
+
+        if to_visit.is_empty() {
+            // A flat bottom, which all drains into its first cell
+            for &(r, c) in &plateau[1..] {
+                drain[r][c] = Some((row, col));
+            }
+            continue;
+        }
+
+        while let Some((r, c)) = to_visit.pop_front() {
+            let d = distance[&(r, c)];
+            for pos in neighbors(heightmap, r, c) {
+                if height(pos) == level && !distance.contains_key(&pos) {
+                    distance.insert(pos, d + 1);
+                    to_visit.push_back(pos);
+                }
+            }
+        }
+
+        for &(r, c) in &plateau {
+            let d = distance[&(r, c)];
+            if d > 0 {
+                drain[r][c] =
+                    neighbors(heightmap, r, c).find(|pos| distance.get(pos) == Some(&(d - 1)));
+            }
+        }
+    }
+
+    let mut labels = heightmap
+        .iter()
+        .map(|row| vec![None; row.len()])
+        .collect::<Vec<_>>();
+    let bottoms = cells().filter(|&(row, col)| height((row, col)) < 9 && drain[row][col].is_none());
+    for (basin, (row, col)) in bottoms.enumerate() {
+        labels[row][col] = Some(basin);
+    }
+
+    for (row, col) in cells().filter(|&pos| height(pos) < 9) {
+        let mut bottom = (row, col);
+        while let Some(next) = drain[bottom.0][bottom.1] {
+            bottom = next;
+        }
+        labels[row][col] = labels[bottom.0][bottom.1];
+    }
+
+    labels
+}
+
+/// A basin from `find_basins_topk`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basin {
+    pub low_point: (usize, usize),
+    /// In reading order
+    pub cells: Vec<(usize, usize)>,
+    pub size: usize,
+}
+
+/// The `k` largest basins, largest first, with ties going to the basin whose low point comes first.
+/// Only the largest `k` are sorted, so asking for a few basins doesn't sort all of them
+pub fn find_basins_topk(heightmap: &HeightMap, k: usize) -> Vec<Basin> {
+    let mut basins = lowpoints(heightmap)
+        .map(|low_point| Basin {
+            low_point,
+            cells: Vec::new(),
+            size: 0,
+        })
+        .collect::<Vec<_>>();
+    for (row, labels) in label_basins(heightmap).iter().enumerate() {
+        for (col, label) in labels.iter().enumerate() {
+            if let Some(basin) = *label {
+                basins[basin].cells.push((row, col));
+                basins[basin].size += 1;
+            }
+        }
+    }
+
+    let larger = |a: &Basin, b: &Basin| b.size.cmp(&a.size).then(a.low_point.cmp(&b.low_point));
+    if k < basins.len() {
+        if k > 0 {
+            basins.select_nth_unstable_by(k - 1, larger);
+        }
+        basins.truncate(k);
+    }
+    basins.sort_unstable_by(larger);
+    basins
+}
+
+/// Every basin shaded by its size, with the `k` largest numbered by their rank. Ridges are drawn
+/// as `#`
+#[cfg(feature = "viz")]
+pub fn render_heatmap(heights: &HeightMap, k: usize) -> crate::viz::Canvas {
+    use crate::viz::{gray, Canvas, BLACK};
+
+    let cols = heights.first().map_or(0, |row| row.len());
+    let mut canvas = Canvas::new(cols, heights.len());
+    for (row, labels) in label_basins(heights).iter().enumerate() {
+        for (col, label) in labels.iter().enumerate() {
+            if label.is_none() {
+                canvas.set(col, row, '#', BLACK);
+            }
+        }
+    }
+
+    let basins = find_basins_topk(heights, usize::MAX);
+    let largest = basins.first().map_or(0, |basin| basin.size);
+    for (rank, basin) in basins.iter().enumerate() {
+        let c = match rank {
+            rank if rank >= k => '.',
+            rank if rank < 9 => std::char::from_digit(rank as u32 + 1, 10).unwrap(),
+            _ => '*',
+        };
+        for &(row, col) in basin.cells.iter() {
+            canvas.set(col, row, c, gray(basin.size, largest));
+        }
+    }
+    canvas
+}
+
+/// The basins, each drawn with its own letter and color. Low points are capitalized and ridges
+/// are drawn as `#`
+#[cfg(feature = "viz")]
+pub fn render_frames(input: &str) -> Vec<crate::viz::Canvas> {
+    use crate::viz::{label_color, Canvas, BLACK, WHITE};
+
+    let heights = match heightmap(input) {
+        Ok(heights) if !heights.is_empty() => heights,
+        _ => return Vec::new(),
+    };
+
+    let labels = label_basins(&heights);
+    let mut canvas = Canvas::new(heights[0].len(), heights.len());
+    for (row, row_labels) in labels.iter().enumerate() {
+        for (col, label) in row_labels.iter().enumerate() {
+            match *label {
+                Some(basin) => {
+                    let letter = (b'a' + (basin % 26) as u8) as char;
+                    if is_low_point(&heights, row, col) {
+                        canvas.set(col, row, letter.to_ascii_uppercase(), WHITE);
+                    } else {
+                        canvas.set(col, row, letter, label_color(basin));
+                    }
+                }
+                None => canvas.set(col, row, '#', BLACK),
+            }
+        }
+    }
+
+    vec![canvas]
+}
+
+#[aoc(day9, part1)]
+pub(crate) fn part1(heights: &HeightMap) -> i32 {
+    lowpoints(heights).fold(0, |sum, (row, col)| 1 + sum + heights[row][col])
+}
+
+#[aoc(day9, part2)]
+pub(crate) fn part2(heights: &HeightMap) -> Option<u64> {
+    find_basins_topk(heights, 3)
+        .iter()
+        .try_fold(1u64, |product, basin| {
+            product.checked_mul(basin.size as u64)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Xorshift;
+
+    const EXAMPLE: &str = include_str!("../../examples/2021/day09.txt");
+
+    #[test]
+    fn basin_labels() {
+        let input = heightmap("2199\n3989\n9856").unwrap();
+        let labels = label_basins(&input);
+        assert_eq!(labels[0], [Some(0), Some(0), None, None]);
+        assert_eq!(labels[1], [Some(0), None, Some(1), None]);
+        assert_eq!(labels[2], [None, Some(1), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn topk() {
+        let input = heightmap(EXAMPLE).unwrap();
+        let sizes = |k| {
+            find_basins_topk(&input, k)
+                .iter()
+                .map(|basin| basin.size)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sizes(3), [14, 9, 9]);
+        assert!(sizes(0).is_empty());
+        assert_eq!(sizes(10), [14, 9, 9, 3]);
+
+        // The tied basins are in low point order
+        let basins = find_basins_topk(&input, 3);
+        assert_eq!(basins[1].low_point, (0, 9));
+        assert_eq!(basins[2].low_point, (4, 6));
+        assert_eq!(basins[2].cells.len(), 9);
+        assert_eq!(basins[0].cells[0], (1, 2));
+
+        let input = heightmap("2199\n3989\n9856").unwrap();
+        let basins = find_basins_topk(&input, 5);
+        assert_eq!(basins.len(), 2);
+        assert_eq!(basins[0].cells, [(1, 2), (2, 1), (2, 2), (2, 3)]);
+        assert_eq!(basins[1].cells, [(0, 0), (0, 1), (1, 0)]);
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn heatmap() {
+        let input = heightmap("2199\n3989\n9856").unwrap();
+        let canvas = render_heatmap(&input, 1);
+        assert_eq!(canvas.to_ascii(), "..##\n.#1#\n#111\n");
+        assert_eq!(render_heatmap(&input, 5).to_ascii(), "22##\n2#1#\n#111\n");
+    }
+
+    #[test]
+    fn watershed() {
+        // Both policies agree when basins are walled off by 9s
+        let input = heightmap("2199\n3989\n9856").unwrap();
+        assert_eq!(
+            label_basins_with(&input, BasinPolicy::Watershed),
+            label_basins(&input)
+        );
+
+        // Without walls the flood spills everywhere, but the watershed splits at the peak, which
+        // drains left on the tie
+        let input = heightmap("12321").unwrap();
+        assert_eq!(label_basins(&input)[0], [Some(0); 5]);
+        assert_eq!(
+            label_basins_with(&input, BasinPolicy::Watershed)[0],
+            [Some(0), Some(0), Some(0), Some(1), Some(1)]
+        );
+
+        // The plateau of 5s drains towards whichever way down is closer
+        let input = heightmap("55555\n51555\n55552").unwrap();
+        let labels = label_basins_with(&input, BasinPolicy::Watershed);
+        assert_eq!(labels[0], [Some(0), Some(0), Some(0), Some(0), Some(1)]);
+        assert_eq!(labels[1], [Some(0), Some(0), Some(0), Some(0), Some(1)]);
+        assert_eq!(labels[2], [Some(0), Some(0), Some(0), Some(1), Some(1)]);
+
+        // A flat bottom is a single basin, even though it has no low point
+        let input = heightmap("9999\n3113\n3129").unwrap();
+        let labels = label_basins_with(&input, BasinPolicy::Watershed);
+        assert!(find_lowpoints(&input).is_empty());
+        assert_eq!(labels[0], [None; 4]);
+        assert_eq!(labels[1], [Some(0); 4]);
+        assert_eq!(labels[2], [Some(0), Some(0), Some(0), None]);
+    }
+
+    #[cfg(feature = "viz")]
+    #[test]
+    fn render_example() {
+        let frames = render_frames(EXAMPLE);
+        assert_eq!(
+            frames[0].to_ascii(),
+            "aA###bbbbB
+a#ccc#b#bb
+#cCccc#d#b
+ccccc#ddd#
+#c###dDddd
+"
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let (low_points, risk) = lowpoints_streaming(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(low_points, find_lowpoints(&heightmap(EXAMPLE).unwrap()));
+        assert_eq!(risk, 15);
+
+        let mut rng = Xorshift::default();
+        for (rows, cols) in [(1, 1), (1, 8), (7, 1), (2, 5), (20, 30)] {
+            let map = (0..rows)
+                .map(|_| {
+                    (0..cols)
+                        .map(|_| char::from(b'0' + (rng.next_u32() % 10) as u8))
+                        .collect()
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+            let heights = heightmap(&map).unwrap();
+            let (low_points, risk) = lowpoints_streaming(map.as_bytes()).unwrap();
+            assert_eq!(low_points, find_lowpoints(&heights), "{}", map);
+            assert_eq!(risk, part1(&heights) as i64);
+        }
+
+        let (low_points, _) = lowpoints_streaming("".as_bytes()).unwrap();
+        assert!(low_points.is_empty());
+        let (low_points, _) = lowpoints_streaming("\n21\n\n39\n".as_bytes()).unwrap();
+        assert_eq!(low_points, [(0, 1)]);
+
+        // The generator reads the same map, and turns down the same line
+        let err = lowpoints_streaming("21\n\n3x\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 3: invalid height 'x'");
+        assert_eq!(
+            heightmap("21\n\n3x\n").err().map(|err| err.to_string()),
+            Some(err.to_string())
+        );
+    }
+
+    #[test]
+    fn lazy_lowpoints() {
+        let mut rng = Xorshift::new(0x9e3779b9);
+        for (rows, cols) in [(1, 1), (3, 1), (1, 6), (5, 10), (40, 25)] {
+            let heights = (0..rows)
+                .map(|_| (0..cols).map(|_| (rng.next_u32() % 10) as i32).collect())
+                .collect::<HeightMap>();
+
+            // Every cell checked up front, as the collected version used to
+            let mut expected = Vec::new();
+            for row in 0..rows {
+                for col in 0..cols {
+                    if is_low_point(&heights, row, col) {
+                        expected.push((row, col));
+                    }
+                }
+            }
+            assert_eq!(lowpoints(&heights).collect::<Vec<_>>(), expected);
+            assert_eq!(find_lowpoints(&heights), expected);
+            assert_eq!(
+                part1(&heights),
+                expected
+                    .iter()
+                    .map(|&(row, col)| 1 + heights[row][col])
+                    .sum::<i32>()
+            );
+
+            // Every low point still seeds a basin
+            let low_points = find_basins_topk(&heights, usize::MAX)
+                .iter()
+                .map(|basin| basin.low_point)
+                .collect::<std::collections::BTreeSet<_>>();
+            assert_eq!(low_points, expected.iter().copied().collect());
+        }
+
+        // Stops as soon as it has what it was asked for
+        let example = heightmap("2199943210\n3987894921\n9856789892").unwrap();
+        assert_eq!(lowpoints(&example).next(), Some((0, 1)));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(heightmap("219\n39x").err().map(|err| err.line()), Some(2));
+        assert_eq!(heightmap("219\n\n39").err().map(|err| err.line()), Some(3));
+
+        let empty = heightmap("\n").unwrap();
+        assert_eq!(part1(&empty), 0);
+        // The product of no basins at all
+        assert_eq!(part2(&empty), Some(1));
+
+        // Three basins of 2000 cells, with a product past an i32
+        let basin = format!("0{}9", "1".repeat(1999));
+        let row = heightmap(&basin.repeat(3)).unwrap();
+        assert_eq!(part2(&row), Some(8_000_000_000));
+    }
+
+    #[test]
+    fn neighbors_at_border() {
+        let input = heightmap("123\n456\n789").unwrap();
+        assert_eq!(
+            neighbors(&input, 0, 0).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            neighbors(&input, 1, 1).collect::<Vec<_>>(),
+            [(1, 0), (1, 2), (0, 1), (2, 1)]
+        );
+        assert_eq!(
+            neighbors(&input, 2, 2).collect::<Vec<_>>(),
+            [(2, 1), (1, 2)]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Solutions for 2021. These are the days registered with `cargo aoc`
+
+/// Assert that every implementation of a part gives the same answer as the first one on `input`
+#[cfg(test)]
+macro_rules! assert_implementations_agree {
+    ($input:expr, $reference:path $(, $alternative:path)+ $(,)?) => {{
+        let input = $input;
+        let expected = $reference(&input);
+        $(
+            assert_eq!(
+                $alternative(&input),
+                expected,
+                "{} disagrees with {}",
+                stringify!($alternative),
+                stringify!($reference)
+            );
+        )+
+    }};
+}
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day12_2;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day2;
+pub mod day21;
+pub mod day22;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
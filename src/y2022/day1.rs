@@ -0,0 +1,25 @@
+/// Calories carried by each elf, with each elf's items separated by a blank line
+pub(crate) fn calories(input: &str) -> Vec<u32> {
+    input
+        .split("\n\n")
+        .map(|elf| {
+            elf.lines()
+                .filter_map(|line| line.trim().parse::<u32>().ok())
+                .sum()
+        })
+        .collect()
+}
+
+fn most_calories(elves: &[u32], count: usize) -> u32 {
+    let mut elves = elves.to_vec();
+    elves.sort_unstable_by(|a, b| b.cmp(a));
+    elves.iter().take(count).sum()
+}
+
+pub(crate) fn part1(elves: &[u32]) -> u32 {
+    most_calories(elves, 1)
+}
+
+pub(crate) fn part2(elves: &[u32]) -> u32 {
+    most_calories(elves, 3)
+}
@@ -0,0 +1,4 @@
+//! Solutions for 2022. `cargo aoc` only runs one year per crate, so these aren't registered with
+//! it and are only reachable through `solve`
+
+pub mod day1;
@@ -0,0 +1,67 @@
+//! Runs every day's solution on the puzzle example in `examples/YYYY/dayNN.txt` and checks the
+//! answers against `examples/YYYY/dayNN.expected`. Each answer there is written as `partN: answer`, with any
+//! further lines up to the next part being part of a multi-line answer.
+
+use std::fs;
+use std::path::Path;
+
+fn expected_answers(expected: &str) -> Vec<(u32, String)> {
+    let mut answers: Vec<(u32, String)> = Vec::new();
+    for line in expected.lines() {
+        let part = line
+            .strip_prefix("part")
+            .and_then(|rest| rest.split_once(':'))
+            .and_then(|(part, answer)| Some((part.parse().ok()?, answer)));
+
+        match (part, answers.last_mut()) {
+            (Some((part, answer)), _) => answers.push((part, answer.trim().to_string())),
+            (None, Some((_, answer))) => {
+                answer.push('\n');
+                answer.push_str(line);
+            }
+            (None, None) => panic!("expected `partN:` before {:?}", line),
+        }
+    }
+    answers
+}
+
+#[test]
+fn examples() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let mut checked = 0;
+    let mut failures = Vec::new();
+
+    for &(year, day) in aoc21::DAYS {
+        let dir = dir.join(year.to_string());
+        let input_path = dir.join(format!("day{:02}.txt", day));
+        let expected_path = dir.join(format!("day{:02}.expected", day));
+        let (input, expected) = match (
+            fs::read_to_string(&input_path),
+            fs::read_to_string(&expected_path),
+        ) {
+            (Ok(input), Ok(expected)) => (input, expected),
+            _ => {
+                failures.push(format!(
+                    "{} day {}: missing example or expected answers",
+                    year, day
+                ));
+                continue;
+            }
+        };
+
+        for (part, answer) in expected_answers(&expected) {
+            checked += 1;
+            match aoc21::solve(year, day, part, &input) {
+                Ok(actual) if actual == answer => {}
+                Ok(actual) => failures.push(format!(
+                    "{} day {} part {}: expected {:?}, got {:?}",
+                    year, day, part, answer, actual
+                )),
+                Err(err) => failures.push(format!("{} day {} part {}: {}", year, day, part, err)),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    assert!(checked > 0);
+}
@@ -0,0 +1,22 @@
+//! Run with `wasm-pack test --headless --firefox --features wasm`
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use aoc21::wasm::solve;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const EXAMPLE: &str = include_str!("../examples/2021/day01.txt");
+
+#[wasm_bindgen_test]
+fn day1() {
+    assert_eq!(solve(1, 1, EXAMPLE).ok().as_deref(), Some("7"));
+    assert_eq!(solve(1, 2, EXAMPLE).ok().as_deref(), Some("5"));
+}
+
+#[wasm_bindgen_test]
+fn errors() {
+    assert!(solve(4, 1, "").is_err());
+    assert!(solve(13, 1, "0,0\n4,0\n\nfold along x=1\n").is_err());
+    assert!(solve(19, 1, EXAMPLE).is_err());
+}